@@ -0,0 +1,183 @@
+#[cfg(test)]
+mod tests;
+
+use chrono::{Datelike, Days, NaiveDate, Weekday};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+
+/// Which days are holidays. Weekends are never business days, whatever the calendar.
+pub trait HolidayCalendar {
+    fn is_holiday(&self, date: &NaiveDate) -> bool;
+
+    fn is_business_day(&self, date: &NaiveDate) -> bool {
+        !matches!(date.weekday(), Weekday::Sat | Weekday::Sun) & !self.is_holiday(date)
+    }
+}
+
+/// A holiday calendar of the US federal holidays, any custom `holidays`, or both. The default
+/// calendar has no holidays, only weekends.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(deny_unknown_fields)
+)]
+pub struct Calendar {
+    #[cfg_attr(feature = "serde", serde(default))]
+    us_federal: bool,
+    #[cfg_attr(feature = "serde", serde(default))]
+    holidays: BTreeSet<NaiveDate>,
+}
+
+impl Calendar {
+    /// The US federal holidays, on the weekday they are observed
+    pub fn us_federal() -> Self {
+        Calendar {
+            us_federal: true,
+            holidays: BTreeSet::new(),
+        }
+    }
+
+    pub fn with_holidays(mut self, holidays: impl IntoIterator<Item = NaiveDate>) -> Self {
+        self.holidays.extend(holidays);
+        self
+    }
+
+    pub fn is_us_federal(&self) -> bool {
+        self.us_federal
+    }
+
+    pub fn holidays(&self) -> &BTreeSet<NaiveDate> {
+        &self.holidays
+    }
+
+    #[cfg(feature = "serde")]
+    pub(crate) fn is_default(&self) -> bool {
+        *self == Calendar::default()
+    }
+}
+
+impl HolidayCalendar for Calendar {
+    fn is_holiday(&self, date: &NaiveDate) -> bool {
+        self.holidays.contains(date) | (self.us_federal & is_us_federal_holiday(date))
+    }
+}
+
+/// The `nth` `weekday` of a month, counting from the end when `nth` is negative
+pub(crate) fn nth_weekday_of_month(
+    year: i32,
+    month: u32,
+    weekday: Weekday,
+    nth: i8,
+) -> Option<NaiveDate> {
+    if nth < 0 {
+        let next_month = if month == 12 {
+            NaiveDate::from_ymd_opt(year + 1, 1, 1)?
+        } else {
+            NaiveDate::from_ymd_opt(year, month + 1, 1)?
+        };
+        let last = next_month.pred_opt()?;
+        let back = last.weekday().days_since(weekday) + 7 * (nth.unsigned_abs() as u32 - 1);
+        last.checked_sub_days(Days::new(back.into()))
+            .filter(|date| date.month() == month)
+    } else {
+        NaiveDate::from_weekday_of_month_opt(year, month, weekday, nth.try_into().ok()?)
+    }
+}
+
+/// Fixed-date holidays falling on a weekend are observed on the nearest weekday
+fn observed(date: NaiveDate) -> NaiveDate {
+    match date.weekday() {
+        Weekday::Sat => date.pred_opt().unwrap_or(date),
+        Weekday::Sun => date.succ_opt().unwrap_or(date),
+        _ => date,
+    }
+}
+
+/// Whether `date` is an observed US federal holiday, as set by 5 U.S.C. 6103
+fn is_us_federal_holiday(date: &NaiveDate) -> bool {
+    let year = date.year();
+    let fixed =
+        |year, month, day| NaiveDate::from_ymd_opt(year, month, day).map(observed) == Some(*date);
+    let nth = |weekday, nth| nth_weekday_of_month(year, date.month(), weekday, nth) == Some(*date);
+
+    match date.month() {
+        1 => fixed(year, 1, 1) | nth(Weekday::Mon, 3),
+        2 => nth(Weekday::Mon, 3),
+        5 => nth(Weekday::Mon, -1),
+        // Juneteenth became a federal holiday in 2021
+        6 => (year >= 2021) & fixed(year, 6, 19),
+        7 => fixed(year, 7, 4),
+        9 => nth(Weekday::Mon, 1),
+        10 => nth(Weekday::Mon, 2),
+        11 => fixed(year, 11, 11) | nth(Weekday::Thu, 4),
+        // New Year's Day on a Saturday is observed on the last day of the year before
+        12 => fixed(year, 12, 25) | fixed(year + 1, 1, 1),
+        _ => false,
+    }
+}
+
+/// How a date falling on a weekend or holiday moves to a business day
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(rename_all = "snake_case")
+)]
+pub enum RollConvention {
+    /// Stay on the date
+    #[default]
+    None,
+    /// The business day before, as for paychecks
+    Preceding,
+    /// The business day after, as for autopays
+    Following,
+    /// The business day after, unless that is in the next month, in which case the one before
+    ModifiedFollowing,
+}
+
+impl RollConvention {
+    pub fn roll(&self, date: NaiveDate, calendar: &dyn HolidayCalendar) -> NaiveDate {
+        let preceding = || {
+            date.iter_days()
+                .rev()
+                .find(|day| calendar.is_business_day(day))
+                .unwrap_or(date)
+        };
+        let following = || {
+            date.iter_days()
+                .find(|day| calendar.is_business_day(day))
+                .unwrap_or(date)
+        };
+
+        match self {
+            RollConvention::None => date,
+            RollConvention::Preceding => preceding(),
+            RollConvention::Following => following(),
+            RollConvention::ModifiedFollowing => {
+                let next = following();
+                if next.month() == date.month() {
+                    next
+                } else {
+                    preceding()
+                }
+            }
+        }
+    }
+
+    /// Roll each of `dates`, which stay in order. Dates which roll onto the same business day
+    /// each keep their occurrence there.
+    pub(crate) fn roll_all<'a>(
+        &'a self,
+        dates: impl Iterator<Item = NaiveDate> + 'a,
+        calendar: &'a dyn HolidayCalendar,
+    ) -> impl Iterator<Item = NaiveDate> + 'a {
+        dates.map(|date| self.roll(date, calendar))
+    }
+
+    #[cfg(feature = "serde")]
+    pub(crate) fn is_none(&self) -> bool {
+        *self == RollConvention::None
+    }
+}
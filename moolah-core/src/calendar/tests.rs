@@ -0,0 +1,149 @@
+use super::*;
+use crate::date_helpers::ymd;
+
+#[test]
+fn test_default_has_only_weekends() {
+    let calendar = Calendar::default();
+    assert!(calendar.is_business_day(&ymd(2023, 12, 25)));
+    assert!(!calendar.is_business_day(&ymd(2023, 12, 23)));
+    assert!(!calendar.is_business_day(&ymd(2023, 12, 24)));
+}
+
+#[test]
+fn test_us_federal_holidays() {
+    let calendar = Calendar::us_federal();
+    let holidays: Vec<_> = ymd(2023, 1, 1)
+        .iter_days()
+        .take_while(|date| date.year() == 2023)
+        .filter(|date| calendar.is_holiday(date))
+        .collect();
+
+    assert_eq!(
+        holidays,
+        [
+            ymd(2023, 1, 2), // New Year's Day falls on a Sunday
+            ymd(2023, 1, 16),
+            ymd(2023, 2, 20),
+            ymd(2023, 5, 29),
+            ymd(2023, 6, 19),
+            ymd(2023, 7, 4),
+            ymd(2023, 9, 4),
+            ymd(2023, 10, 9),
+            ymd(2023, 11, 10), // Veterans Day falls on a Saturday
+            ymd(2023, 11, 23),
+            ymd(2023, 12, 25),
+        ]
+    );
+}
+
+#[test]
+fn test_new_years_day_observed_in_previous_year() {
+    let calendar = Calendar::us_federal();
+    assert!(calendar.is_holiday(&ymd(2021, 12, 31)));
+    assert!(!calendar.is_holiday(&ymd(2022, 1, 1)));
+    assert!(!calendar.is_holiday(&ymd(2022, 1, 3)));
+}
+
+#[test]
+fn test_juneteenth_from_2021() {
+    let calendar = Calendar::us_federal();
+    assert!(!calendar.is_holiday(&ymd(2020, 6, 19)));
+    assert!(calendar.is_holiday(&ymd(2021, 6, 18)));
+}
+
+#[test]
+fn test_custom_holidays() {
+    let calendar = Calendar::default().with_holidays([ymd(2023, 3, 15)]);
+    assert!(calendar.is_holiday(&ymd(2023, 3, 15)));
+    assert!(!calendar.is_holiday(&ymd(2023, 12, 25)));
+
+    let calendar = Calendar::us_federal().with_holidays([ymd(2023, 3, 15)]);
+    assert!(calendar.is_holiday(&ymd(2023, 3, 15)));
+    assert!(calendar.is_holiday(&ymd(2023, 12, 25)));
+}
+
+#[test]
+fn test_nth_weekday_of_month() {
+    assert_eq!(
+        nth_weekday_of_month(2023, 11, Weekday::Thu, 4),
+        Some(ymd(2023, 11, 23))
+    );
+    assert_eq!(
+        nth_weekday_of_month(2023, 5, Weekday::Mon, -1),
+        Some(ymd(2023, 5, 29))
+    );
+    assert_eq!(
+        nth_weekday_of_month(2023, 12, Weekday::Fri, -2),
+        Some(ymd(2023, 12, 22))
+    );
+    assert_eq!(nth_weekday_of_month(2023, 2, Weekday::Mon, 5), None);
+    assert_eq!(nth_weekday_of_month(2023, 2, Weekday::Mon, -5), None);
+    assert_eq!(nth_weekday_of_month(2023, 2, Weekday::Mon, 0), None);
+}
+
+#[test]
+fn test_business_days_do_not_roll() {
+    let calendar = Calendar::us_federal();
+    for roll in [
+        RollConvention::None,
+        RollConvention::Preceding,
+        RollConvention::Following,
+        RollConvention::ModifiedFollowing,
+    ] {
+        assert_eq!(roll.roll(ymd(2023, 7, 5), &calendar), ymd(2023, 7, 5));
+    }
+}
+
+#[test]
+fn test_roll_weekend() {
+    let calendar = Calendar::default();
+    let saturday = ymd(2023, 7, 1);
+    assert_eq!(RollConvention::None.roll(saturday, &calendar), saturday);
+    assert_eq!(
+        RollConvention::Preceding.roll(saturday, &calendar),
+        ymd(2023, 6, 30)
+    );
+    assert_eq!(
+        RollConvention::Following.roll(saturday, &calendar),
+        ymd(2023, 7, 3)
+    );
+    assert_eq!(
+        RollConvention::ModifiedFollowing.roll(saturday, &calendar),
+        ymd(2023, 7, 3)
+    );
+}
+
+#[test]
+fn test_roll_holiday() {
+    let calendar = Calendar::us_federal();
+    assert_eq!(
+        RollConvention::Preceding.roll(ymd(2023, 7, 4), &calendar),
+        ymd(2023, 7, 3)
+    );
+    assert_eq!(
+        RollConvention::Following.roll(ymd(2023, 7, 4), &calendar),
+        ymd(2023, 7, 5)
+    );
+    // Labor Day follows the weekend
+    assert_eq!(
+        RollConvention::Following.roll(ymd(2023, 9, 2), &calendar),
+        ymd(2023, 9, 5)
+    );
+}
+
+#[test]
+fn test_modified_following_stays_in_month() {
+    let calendar = Calendar::us_federal();
+    assert_eq!(
+        RollConvention::ModifiedFollowing.roll(ymd(2023, 9, 30), &calendar),
+        ymd(2023, 9, 29)
+    );
+    assert_eq!(
+        RollConvention::ModifiedFollowing.roll(ymd(2023, 12, 30), &calendar),
+        ymd(2023, 12, 29)
+    );
+    assert_eq!(
+        RollConvention::Following.roll(ymd(2023, 12, 30), &calendar),
+        ymd(2024, 1, 2)
+    );
+}
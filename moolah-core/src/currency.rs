@@ -0,0 +1,329 @@
+#[cfg(test)]
+mod tests;
+
+use crate::{errors::MoolahCoreError, money::Money};
+use chrono::NaiveDate;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{BTreeMap, HashMap},
+    fmt,
+    str::FromStr,
+};
+
+/// An ISO 4217 style currency code, e.g. `USD` or `EUR`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(try_from = "String", into = "String")
+)]
+pub struct Currency([u8; 3]);
+
+impl Currency {
+    pub fn try_new(code: &str) -> Result<Self, MoolahCoreError> {
+        match code.as_bytes() {
+            [a, b, c] if code.bytes().all(|byte| byte.is_ascii_uppercase()) => {
+                Ok(Currency([*a, *b, *c]))
+            }
+            _ => Err(MoolahCoreError::InvalidCurrencyCode(code.into())),
+        }
+    }
+
+    pub fn code(&self) -> &str {
+        std::str::from_utf8(&self.0).expect("currency codes are always ASCII")
+    }
+}
+
+impl FromStr for Currency {
+    type Err = MoolahCoreError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Currency::try_new(s)
+    }
+}
+
+impl TryFrom<String> for Currency {
+    type Error = MoolahCoreError;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        Currency::try_new(&value)
+    }
+}
+
+impl From<Currency> for String {
+    fn from(value: Currency) -> Self {
+        value.code().into()
+    }
+}
+
+impl fmt::Display for Currency {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad(self.code())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(rename_all = "snake_case")
+)]
+pub enum Interpolation {
+    /// Use the most recent rate on or before the date
+    #[default]
+    Previous,
+    /// Interpolate linearly between the rates on either side of the date
+    Linear,
+}
+
+/// The rate between two currencies, either fixed or changing over time.
+///
+/// Dated rates have no rate before their first date. From the last date onwards the last rate
+/// holds, however they are interpolated.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RateSchedule(Schedule);
+
+#[derive(Debug, Clone, PartialEq)]
+enum Schedule {
+    Fixed(f64),
+    Dated {
+        rates: BTreeMap<NaiveDate, f64>,
+        interpolation: Interpolation,
+    },
+}
+
+fn valid_rate(rate: f64) -> Result<f64, MoolahCoreError> {
+    if rate.is_finite() & (rate > 0.0) {
+        Ok(rate)
+    } else {
+        Err(MoolahCoreError::InvalidExchangeRate(rate))
+    }
+}
+
+impl RateSchedule {
+    pub fn try_fixed(rate: f64) -> Result<Self, MoolahCoreError> {
+        Ok(RateSchedule(Schedule::Fixed(valid_rate(rate)?)))
+    }
+
+    /// Rates from their dates onwards, of which there must be at least one
+    pub fn try_dated(
+        rates: impl IntoIterator<Item = (NaiveDate, f64)>,
+        interpolation: Interpolation,
+    ) -> Result<Self, MoolahCoreError> {
+        let rates = rates
+            .into_iter()
+            .map(|(date, rate)| Ok((date, valid_rate(rate)?)))
+            .collect::<Result<BTreeMap<_, _>, MoolahCoreError>>()?;
+        if rates.is_empty() {
+            return Err(MoolahCoreError::EmptyRateSchedule);
+        }
+
+        Ok(RateSchedule(Schedule::Dated {
+            rates,
+            interpolation,
+        }))
+    }
+
+    /// The rate, if it is fixed
+    pub fn fixed_rate(&self) -> Option<f64> {
+        match &self.0 {
+            Schedule::Fixed(rate) => Some(*rate),
+            Schedule::Dated { .. } => None,
+        }
+    }
+
+    /// The dated rates and how to interpolate between them, if the rate changes over time
+    pub fn dated_rates(&self) -> Option<(&BTreeMap<NaiveDate, f64>, Interpolation)> {
+        match &self.0 {
+            Schedule::Fixed(_) => None,
+            Schedule::Dated {
+                rates,
+                interpolation,
+            } => Some((rates, *interpolation)),
+        }
+    }
+
+    fn rate(&self, date: &NaiveDate) -> Option<f64> {
+        let (rates, interpolation) = match &self.0 {
+            Schedule::Fixed(rate) => return Some(*rate),
+            Schedule::Dated {
+                rates,
+                interpolation,
+            } => (rates, interpolation),
+        };
+
+        let (before_date, before) = rates.range(..=*date).next_back()?;
+        let after = rates.range(*date..).next();
+        match (interpolation, after) {
+            (Interpolation::Linear, Some((after_date, after))) if after_date != before_date => {
+                let span = (*after_date - *before_date).num_days() as f64;
+                let elapsed = (*date - *before_date).num_days() as f64;
+                Some(before + (after - before) * elapsed / span)
+            }
+            _ => Some(*before),
+        }
+    }
+}
+
+/// A user-supplied table of exchange rates between pairs of currencies.
+///
+/// A rate from `A` to `B` is the number of `B` one unit of `A` buys. When only the rate from `B`
+/// to `A` is known, its reciprocal is used.
+#[derive(Debug, Clone, PartialEq, Default)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(try_from = "Vec<RawExchangeRate>", into = "Vec<RawExchangeRate>")
+)]
+pub struct ExchangeRates {
+    schedules: HashMap<(Currency, Currency), RateSchedule>,
+}
+
+impl ExchangeRates {
+    pub fn insert(&mut self, from: Currency, to: Currency, schedule: RateSchedule) {
+        self.schedules.insert((from, to), schedule);
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.schedules.is_empty()
+    }
+
+    pub fn rate(
+        &self,
+        from: &Currency,
+        to: &Currency,
+        date: &NaiveDate,
+    ) -> Result<f64, MoolahCoreError> {
+        if from == to {
+            return Ok(1.0);
+        }
+
+        let direct = self
+            .schedules
+            .get(&(*from, *to))
+            .and_then(|schedule| schedule.rate(date));
+        let inverse = || {
+            self.schedules
+                .get(&(*to, *from))
+                .and_then(|schedule| schedule.rate(date))
+                .map(|rate| 1.0 / rate)
+        };
+
+        direct
+            .or_else(inverse)
+            .ok_or(MoolahCoreError::MissingExchangeRate {
+                from: *from,
+                to: *to,
+                date: *date,
+            })
+    }
+
+    /// Convert `amount` on `date`, rounding to the nearest cent (see [`Money::scale`])
+    pub fn convert(
+        &self,
+        amount: Money,
+        from: &Currency,
+        to: &Currency,
+        date: &NaiveDate,
+    ) -> Result<Money, MoolahCoreError> {
+        Ok(amount.scale(self.rate(from, to, date)?))
+    }
+}
+
+#[cfg(feature = "serde")]
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub(crate) struct DatedRate {
+    date: NaiveDate,
+    rate: f64,
+}
+
+#[cfg(feature = "serde")]
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub(crate) struct RawExchangeRate {
+    from: Currency,
+    to: Currency,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    rate: Option<f64>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    rates: Vec<DatedRate>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    interpolation: Option<Interpolation>,
+}
+
+#[cfg(feature = "serde")]
+impl TryFrom<Vec<RawExchangeRate>> for ExchangeRates {
+    type Error = MoolahCoreError;
+
+    fn try_from(value: Vec<RawExchangeRate>) -> Result<Self, Self::Error> {
+        let mut exchange_rates = ExchangeRates::default();
+        for raw in value {
+            exchange_rates.insert_raw(raw)?;
+        }
+        Ok(exchange_rates)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl ExchangeRates {
+    pub(crate) fn insert_raw(&mut self, raw: RawExchangeRate) -> Result<(), MoolahCoreError> {
+        let schedule = match (raw.rate, raw.rates.is_empty()) {
+            (Some(rate), true) => RateSchedule::try_fixed(rate)?,
+            (None, false) => RateSchedule::try_dated(
+                raw.rates.into_iter().map(|dated| (dated.date, dated.rate)),
+                raw.interpolation.unwrap_or_default(),
+            )?,
+            _ => {
+                return Err(MoolahCoreError::MalformedExchangeRate {
+                    from: raw.from,
+                    to: raw.to,
+                })
+            }
+        };
+        self.insert(raw.from, raw.to, schedule);
+        Ok(())
+    }
+
+    pub(crate) fn to_raw(&self) -> Vec<RawExchangeRate> {
+        let mut raw: Vec<RawExchangeRate> = self
+            .schedules
+            .iter()
+            .map(|((from, to), schedule)| match &schedule.0 {
+                Schedule::Fixed(rate) => RawExchangeRate {
+                    from: *from,
+                    to: *to,
+                    rate: Some(*rate),
+                    rates: vec![],
+                    interpolation: None,
+                },
+                Schedule::Dated {
+                    rates,
+                    interpolation,
+                } => RawExchangeRate {
+                    from: *from,
+                    to: *to,
+                    rate: None,
+                    rates: rates
+                        .iter()
+                        .map(|(date, rate)| DatedRate {
+                            date: *date,
+                            rate: *rate,
+                        })
+                        .collect(),
+                    interpolation: Some(*interpolation),
+                },
+            })
+            .collect();
+        raw.sort_by_key(|raw| (raw.from, raw.to));
+        raw
+    }
+}
+
+#[cfg(feature = "serde")]
+impl From<ExchangeRates> for Vec<RawExchangeRate> {
+    fn from(value: ExchangeRates) -> Self {
+        value.to_raw()
+    }
+}
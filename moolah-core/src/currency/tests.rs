@@ -0,0 +1,191 @@
+use super::*;
+use crate::date_helpers::naive_ymd;
+
+fn usd() -> Currency {
+    Currency::try_new("USD").unwrap()
+}
+
+fn eur() -> Currency {
+    Currency::try_new("EUR").unwrap()
+}
+
+fn gbp() -> Currency {
+    Currency::try_new("GBP").unwrap()
+}
+
+#[test]
+fn test_currency_codes() {
+    assert_eq!(usd().code(), "USD");
+    assert_eq!("EUR".parse::<Currency>().unwrap(), eur());
+    assert_eq!(format!("{:>5}", gbp()), "  GBP");
+
+    for invalid in ["", "US", "usd", "USDX", "U$D", "ÜSD"] {
+        assert!(matches!(
+            Currency::try_new(invalid),
+            Err(MoolahCoreError::InvalidCurrencyCode(code)) if code == invalid
+        ));
+    }
+}
+
+#[test]
+fn test_invalid_rates() {
+    for rate in [0.0, -1.2, f64::NAN, f64::INFINITY] {
+        assert!(matches!(
+            RateSchedule::try_fixed(rate),
+            Err(MoolahCoreError::InvalidExchangeRate(_))
+        ));
+    }
+
+    assert!(RateSchedule::try_dated(
+        [
+            (naive_ymd(2023, 1, 1).unwrap(), 1.1),
+            (naive_ymd(2023, 2, 1).unwrap(), 0.0)
+        ],
+        Interpolation::Previous
+    )
+    .is_err());
+    assert!(matches!(
+        RateSchedule::try_dated([], Interpolation::Linear),
+        Err(MoolahCoreError::EmptyRateSchedule)
+    ));
+}
+
+#[test]
+fn test_fixed_rate() {
+    let mut rates = ExchangeRates::default();
+    rates.insert(eur(), usd(), RateSchedule::try_fixed(1.25).unwrap());
+    let date = naive_ymd(2023, 6, 1).unwrap();
+
+    assert_eq!(rates.rate(&eur(), &usd(), &date).unwrap(), 1.25);
+    assert_eq!(rates.rate(&usd(), &eur(), &date).unwrap(), 0.8);
+    assert_eq!(rates.rate(&gbp(), &gbp(), &date).unwrap(), 1.0);
+    assert_eq!(
+        rates
+            .convert(Money::from(100), &eur(), &usd(), &date)
+            .unwrap(),
+        Money::from(125)
+    );
+    assert_eq!(
+        rates
+            .convert(Money::from_cents(333), &usd(), &eur(), &date)
+            .unwrap(),
+        Money::from_cents(266)
+    );
+}
+
+#[test]
+fn test_missing_rate() {
+    let mut rates = ExchangeRates::default();
+    rates.insert(eur(), usd(), RateSchedule::try_fixed(1.25).unwrap());
+    let date = naive_ymd(2023, 6, 1).unwrap();
+
+    assert!(matches!(
+        rates.rate(&gbp(), &usd(), &date),
+        Err(MoolahCoreError::MissingExchangeRate { from, to, date: d })
+            if (from == gbp()) & (to == usd()) & (d == date)
+    ));
+}
+
+#[test]
+fn test_dated_rates_previous() {
+    let mut rates = ExchangeRates::default();
+    rates.insert(
+        eur(),
+        usd(),
+        RateSchedule::try_dated(
+            [
+                (naive_ymd(2023, 1, 1).unwrap(), 1.1),
+                (naive_ymd(2023, 3, 1).unwrap(), 1.2),
+            ],
+            Interpolation::Previous,
+        )
+        .unwrap(),
+    );
+
+    assert!(rates
+        .rate(&eur(), &usd(), &naive_ymd(2022, 12, 31).unwrap())
+        .is_err());
+    assert_eq!(
+        rates
+            .rate(&eur(), &usd(), &naive_ymd(2023, 1, 1).unwrap())
+            .unwrap(),
+        1.1
+    );
+    assert_eq!(
+        rates
+            .rate(&eur(), &usd(), &naive_ymd(2023, 2, 28).unwrap())
+            .unwrap(),
+        1.1
+    );
+    assert_eq!(
+        rates
+            .rate(&eur(), &usd(), &naive_ymd(2024, 1, 1).unwrap())
+            .unwrap(),
+        1.2
+    );
+}
+
+#[test]
+fn test_dated_rates_linear() {
+    let mut rates = ExchangeRates::default();
+    rates.insert(
+        eur(),
+        usd(),
+        RateSchedule::try_dated(
+            [
+                (naive_ymd(2023, 1, 1).unwrap(), 1.0),
+                (naive_ymd(2023, 1, 11).unwrap(), 2.0),
+            ],
+            Interpolation::Linear,
+        )
+        .unwrap(),
+    );
+
+    let rate = |day| rates.rate(&eur(), &usd(), &naive_ymd(2023, 1, day).unwrap());
+    assert_eq!(rate(1).unwrap(), 1.0);
+    assert!((rate(4).unwrap() - 1.3).abs() < 1e-12);
+    assert_eq!(rate(11).unwrap(), 2.0);
+    // The last rate holds after it, as with `Previous`
+    assert_eq!(rate(12).unwrap(), 2.0);
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn test_serde_exchange_rates() {
+    let serialized = r#"[
+        {"from": "EUR", "to": "USD", "rate": 1.25},
+        {"from": "GBP", "to": "USD", "rates": [
+            {"date": "2023-01-01", "rate": 1.2},
+            {"date": "2023-02-01", "rate": 1.3}
+        ], "interpolation": "linear"}
+    ]"#;
+    let rates: ExchangeRates = serde_json::from_str(serialized).unwrap();
+
+    assert_eq!(
+        rates
+            .rate(&gbp(), &usd(), &naive_ymd(2023, 2, 1).unwrap())
+            .unwrap(),
+        1.3
+    );
+
+    let round_trip: ExchangeRates =
+        serde_json::from_str(&serde_json::to_string(&rates).unwrap()).unwrap();
+    assert_eq!(round_trip, rates);
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn test_serde_malformed_exchange_rate() {
+    for serialized in [
+        r#"[{"from": "EUR", "to": "USD"}]"#,
+        r#"[{"from": "EUR", "to": "USD", "rate": 1.2, "rates": [{"date": "2023-01-01", "rate": 1.2}]}]"#,
+    ] {
+        let err = serde_json::from_str::<ExchangeRates>(serialized).unwrap_err();
+        assert!(err.to_string().contains("EUR"), "{}", err);
+    }
+
+    assert!(
+        serde_json::from_str::<ExchangeRates>(r#"[{"from": "eur", "to": "USD", "rate": 1}]"#)
+            .is_err()
+    );
+}
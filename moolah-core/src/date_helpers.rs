@@ -6,3 +6,9 @@ pub fn naive_ymd(year: i32, month: u32, day: u32) -> Result<NaiveDate, MoolahCor
     NaiveDate::from_ymd_opt(year, month, day)
         .ok_or_else(|| MoolahCoreError::InvalidDate(format!("{}-{}-{}", year, month, day)))
 }
+
+/// A date known to be valid, for building test fixtures
+#[cfg(test)]
+pub(crate) fn ymd(year: i32, month: u32, day: u32) -> NaiveDate {
+    naive_ymd(year, month, day).unwrap()
+}
@@ -1,23 +1,50 @@
 pub mod custom_delta;
 pub mod daily_delta;
+pub mod distribution;
+pub mod escalation;
+pub mod interest_delta;
+pub mod loan_delta;
 pub mod monthly_delta;
 pub mod one_time_delta;
+pub mod rrule_delta;
+#[cfg(feature = "serde")]
+pub mod tagged;
 pub mod weekly_delta;
 pub mod yearly_delta;
 
 #[cfg(test)]
 mod tests;
 
-use crate::errors::MoolahCoreError;
+use crate::{
+    currency::Currency, errors::MoolahCoreError, money::Money, prediction::PredictionState,
+    simulation::triangular,
+};
 use chrono::NaiveDate;
+use rand::RngCore;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::{fmt, str::FromStr};
 
 pub use custom_delta::CustomDelta;
 pub use daily_delta::DailyDelta;
+pub use distribution::Quantiles;
+pub use escalation::{Escalation, EscalationStep, IndexLevel};
+pub use interest_delta::{Compounding, InterestDelta, RateUncertainty};
+pub use loan_delta::{ExtraPayment, LoanDelta, LoanPayment};
 pub use monthly_delta::{MonthDay, MonthlyDelta};
 pub use one_time_delta::OneTimeDelta;
+pub use rrule_delta::{RRule, RRuleDelta};
+#[cfg(feature = "serde")]
+pub use tagged::{TaggedDelta, TaggedDeltaRef};
 pub use weekly_delta::WeeklyDelta;
 pub use yearly_delta::YearlyDelta;
 
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(try_from = "f64", into = "f64")
+)]
 pub struct PositiveF64(f64);
 
 impl TryFrom<f64> for PositiveF64 {
@@ -32,11 +59,63 @@ impl TryFrom<f64> for PositiveF64 {
     }
 }
 
+impl From<PositiveF64> for f64 {
+    fn from(value: PositiveF64) -> Self {
+        value.0
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(try_from = "Money", into = "Money")
+)]
+pub struct PositiveMoney(Money);
+
+impl TryFrom<Money> for PositiveMoney {
+    type Error = MoolahCoreError;
+
+    fn try_from(value: Money) -> Result<Self, Self::Error> {
+        if value.is_negative() {
+            Err(MoolahCoreError::UnexpectedNegativeMoney(value))
+        } else {
+            Ok(PositiveMoney(value))
+        }
+    }
+}
+
+impl TryFrom<f64> for PositiveMoney {
+    type Error = MoolahCoreError;
+
+    fn try_from(value: f64) -> Result<Self, Self::Error> {
+        Money::try_from(value)?.try_into()
+    }
+}
+
+impl From<PositiveMoney> for Money {
+    fn from(value: PositiveMoney) -> Self {
+        value.0
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(rename_all = "snake_case")
+)]
 pub enum UncertaintyType {
-    Dollars(PositiveF64),
+    Dollars(PositiveMoney),
     Percent(PositiveF64),
 }
 
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(rename_all = "snake_case")
+)]
 pub enum Uncertainty {
     Balanced(UncertaintyType),
     Unbalanced {
@@ -44,12 +123,61 @@ pub enum Uncertainty {
         high: UncertaintyType,
     },
     Bounds {
-        low: f64,
-        high: f64,
+        low: Money,
+        high: Money,
+    },
+    /// Normally distributed around the value
+    Normal {
+        std_dev: PositiveMoney,
+        #[cfg_attr(
+            feature = "serde",
+            serde(default, skip_serializing_if = "Quantiles::is_default")
+        )]
+        quantiles: Quantiles,
+    },
+    /// Log-normally distributed with the value as its median, `sigma` being the standard deviation
+    /// of the amount's logarithm
+    #[cfg_attr(feature = "serde", serde(rename = "lognormal"))]
+    LogNormal {
+        sigma: PositiveF64,
+        #[cfg_attr(
+            feature = "serde",
+            serde(default, skip_serializing_if = "Quantiles::is_default")
+        )]
+        quantiles: Quantiles,
+    },
+    /// Between `low` and `high`, most likely at the value
+    Triangular {
+        low: Money,
+        high: Money,
+        #[cfg_attr(
+            feature = "serde",
+            serde(default, skip_serializing_if = "Quantiles::is_default")
+        )]
+        quantiles: Quantiles,
+    },
+    /// Anywhere between `low` and `high` with equal likelihood
+    Uniform {
+        low: Money,
+        high: Money,
+        #[cfg_attr(
+            feature = "serde",
+            serde(default, skip_serializing_if = "Quantiles::is_default")
+        )]
+        quantiles: Quantiles,
+    },
+    /// Any of `amounts` with equal likelihood, such as last year's actual bills
+    Empirical {
+        amounts: Vec<Money>,
+        #[cfg_attr(
+            feature = "serde",
+            serde(default, skip_serializing_if = "Quantiles::is_default")
+        )]
+        quantiles: Quantiles,
     },
 }
 
-pub fn reasonable_bounds(low: f64, high: f64, value: f64) -> Result<(), MoolahCoreError> {
+pub fn reasonable_bounds(low: Money, high: Money, value: Money) -> Result<(), MoolahCoreError> {
     if (low <= value) & (value <= high) {
         Ok(())
     } else {
@@ -57,50 +185,286 @@ pub fn reasonable_bounds(low: f64, high: f64, value: f64) -> Result<(), MoolahCo
     }
 }
 
+/// Check that a delta's `start` is not after its `end`
+pub(crate) fn validate_span(
+    start: NaiveDate,
+    end: Option<NaiveDate>,
+) -> Result<(), MoolahCoreError> {
+    match end {
+        Some(end) if start > end => Err(MoolahCoreError::StartAfterEnd { start, end }),
+        _ => Ok(()),
+    }
+}
+
+/// Set a delta's `value` to `new` if it fits the delta's `uncertainty`
+pub(crate) fn set_value(
+    value: &mut Money,
+    uncertainty: &Option<Uncertainty>,
+    new: Money,
+) -> Result<(), MoolahCoreError> {
+    if let Some(uncertainty) = uncertainty {
+        uncertainty.validate(new)?;
+    }
+    *value = new;
+    Ok(())
+}
+
+/// Set a delta's `uncertainty` to `new` if each of the delta's `values` fits it
+pub(crate) fn set_uncertainty(
+    uncertainty: &mut Option<Uncertainty>,
+    values: impl IntoIterator<Item = Money>,
+    new: Option<Uncertainty>,
+) -> Result<(), MoolahCoreError> {
+    if let Some(new) = &new {
+        for value in values {
+            new.validate(value)?;
+        }
+    }
+    *uncertainty = new;
+    Ok(())
+}
+
+impl Uncertainty {
+    /// The `(min, max)` of an amount of `value`
+    pub fn band(&self, value: Money) -> (Money, Money) {
+        let below = |unc: &UncertaintyType| match unc {
+            UncertaintyType::Dollars(unc) => value - Money::from(*unc),
+            UncertaintyType::Percent(unc) => value - value.abs().percent(unc.0),
+        };
+        let above = |unc: &UncertaintyType| match unc {
+            UncertaintyType::Dollars(unc) => value + Money::from(*unc),
+            UncertaintyType::Percent(unc) => value + value.abs().percent(unc.0),
+        };
+
+        match self {
+            Uncertainty::Balanced(unc) => (below(unc), above(unc)),
+            Uncertainty::Unbalanced { low, high } => (below(low), above(high)),
+            Uncertainty::Bounds { low, high } => (*low, *high),
+            distribution => distribution.quantile_band(value).unwrap_or((value, value)),
+        }
+    }
+}
+
+/// A key that picks out one delta of a [`Prediction`](crate::prediction::Prediction), which
+/// names alone cannot when two deltas share a name
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(try_from = "String", into = "String")
+)]
+pub struct DeltaId(String);
+
+impl DeltaId {
+    pub fn try_new(id: &str) -> Result<Self, MoolahCoreError> {
+        if id.trim().is_empty() {
+            return Err(MoolahCoreError::EmptyDeltaId);
+        }
+        Ok(DeltaId(id.into()))
+    }
+
+    /// `name`, or `name` with the first of `-2`, `-3`, ... that makes it free
+    pub(crate) fn from_name(name: &str, is_taken: impl Fn(&str) -> bool) -> Self {
+        let name = match name.trim() {
+            "" => "delta",
+            name => name,
+        };
+        let id = (1..)
+            .map(|n| match n {
+                1 => name.to_string(),
+                n => format!("{name}-{n}"),
+            })
+            .find(|id| !is_taken(id))
+            .unwrap_or_default();
+        DeltaId(id)
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl FromStr for DeltaId {
+    type Err = MoolahCoreError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        DeltaId::try_new(s)
+    }
+}
+
+impl TryFrom<String> for DeltaId {
+    type Error = MoolahCoreError;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        DeltaId::try_new(&value)
+    }
+}
+
+impl From<DeltaId> for String {
+    fn from(value: DeltaId) -> Self {
+        value.0
+    }
+}
+
+impl fmt::Display for DeltaId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad(&self.0)
+    }
+}
+
+/// One occurrence of a delta: what it adds on `date`, and the band of uncertainty around that
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Occurrence {
+    pub date: NaiveDate,
+    pub value: Money,
+    pub min: Money,
+    pub max: Money,
+}
+
+/// A delta borrowed mutably as its concrete type, from [`Delta::edit`]
+pub enum DeltaMut<'a> {
+    OneTime(&'a mut OneTimeDelta),
+    Daily(&'a mut DailyDelta),
+    Weekly(&'a mut WeeklyDelta),
+    Monthly(&'a mut MonthlyDelta),
+    Yearly(&'a mut YearlyDelta),
+    Custom(&'a mut CustomDelta),
+    Interest(&'a mut InterestDelta),
+    Loan(&'a mut LoanDelta),
+    RRule(&'a mut RRuleDelta),
+}
+
 pub trait Delta {
     fn name(&self) -> &str;
 
-    fn value(&self) -> f64;
+    fn value(&self) -> Money;
 
     fn uncertainty(&self) -> &Option<Uncertainty>;
 
-    fn dates(&self) -> &[NaiveDate];
+    /// Currency of the delta's amounts, or `None` when they are in the prediction's currency
+    fn currency(&self) -> Option<&Currency>;
 
-    fn max_uncertainty_value(&self) -> f64 {
-        match self.uncertainty() {
-            Some(Uncertainty::Balanced(UncertaintyType::Dollars(unc))) => self.value() + unc.0,
-            Some(Uncertainty::Balanced(UncertaintyType::Percent(unc))) => {
-                self.value() + unc.0 / 100.0 * self.value().abs()
+    /// The delta's dates in ascending order, generated as they are needed. A recurring delta without an
+    /// end never runs out of dates, so prefer [`Delta::dates_between`].
+    fn dates(&self) -> Box<dyn Iterator<Item = NaiveDate> + '_>;
+
+    /// The delta's dates from `start` to `end` inclusive
+    fn dates_between(
+        &self,
+        start: &NaiveDate,
+        end: &NaiveDate,
+    ) -> Box<dyn Iterator<Item = NaiveDate> + '_> {
+        let (start, end) = (*start, *end);
+        Box::new(
+            self.dates()
+                .skip_while(move |date| *date < start)
+                .take_while(move |date| *date <= end),
+        )
+    }
+
+    /// Whether the delta recurs without an end, so that [`Delta::dates`] never runs out
+    fn is_open_ended(&self) -> bool {
+        false
+    }
+
+    /// Borrow the delta as its tagged concrete type so a `Box<dyn Delta>` can be serialized
+    #[cfg(feature = "serde")]
+    fn tagged(&self) -> TaggedDeltaRef<'_>;
+
+    /// Borrow the delta as its concrete type so a `Box<dyn Delta>` can be changed with its
+    /// setters, or `None` for deltas defined outside this crate
+    fn edit(&mut self) -> Option<DeltaMut<'_>> {
+        None
+    }
+
+    /// How much the delta's occurrence on `date` is scaled by, for deltas with an [`Escalation`]
+    fn escalation_factor(&self, _date: &NaiveDate) -> f64 {
+        1.0
+    }
+
+    /// The `(value, min, max)` of the delta's occurrence on `date`, which are escalated by
+    /// [`Delta::escalation_factor`] and may be overridden by deltas whose amount varies from one
+    /// occurrence to the next in other ways
+    fn amounts_on(&self, date: &NaiveDate) -> (Money, Money, Money) {
+        let factor = self.escalation_factor(date);
+        (
+            self.value().scale(factor),
+            self.min_uncertainty_value().scale(factor),
+            self.max_uncertainty_value().scale(factor),
+        )
+    }
+
+    /// Each of the delta's dates from `start` to `end` with the amount it adds on that date
+    fn occurrences(
+        &self,
+        start: &NaiveDate,
+        end: &NaiveDate,
+    ) -> Box<dyn Iterator<Item = Occurrence> + '_> {
+        Box::new(self.dates_between(start, end).map(|date| {
+            let (value, min, max) = self.amounts_on(&date);
+            Occurrence {
+                date,
+                value,
+                min,
+                max,
             }
-            Some(Uncertainty::Unbalanced {
-                low: _,
-                high: UncertaintyType::Dollars(unc),
-            }) => self.value() + unc.0,
-            Some(Uncertainty::Unbalanced {
-                low: _,
-                high: UncertaintyType::Percent(unc),
-            }) => self.value() + unc.0 / 100.0 * self.value().abs(),
-            Some(Uncertainty::Bounds { low: _, high: unc }) => *unc,
-            None => self.value(),
-        }
+        }))
+    }
+
+    /// Whether the delta's amounts depend on the running balance, in which case a prediction
+    /// takes them from [`Delta::evaluate`] as it goes rather than from `value`
+    fn is_balance_dependent(&self) -> bool {
+        false
     }
 
-    fn min_uncertainty_value(&self) -> f64 {
+    /// The `(value, min, max)` a balance-dependent delta adds when it lands on a prediction in
+    /// `state`
+    fn evaluate(&self, _state: &PredictionState) -> (Money, Money, Money) {
+        (
+            self.value(),
+            self.min_uncertainty_value(),
+            self.max_uncertainty_value(),
+        )
+    }
+
+    /// A random draw of the delta's occurrence on `date` for a Monte Carlo simulation, from the
+    /// uncertainty's distribution if it has one. Otherwise the draw is from a triangular
+    /// distribution peaking at the value and bounded by the min and max.
+    fn sample_on(&self, date: &NaiveDate, rng: &mut dyn RngCore) -> Money {
         match self.uncertainty() {
-            Some(Uncertainty::Balanced(UncertaintyType::Dollars(unc))) => self.value() - unc.0,
-            Some(Uncertainty::Balanced(UncertaintyType::Percent(unc))) => {
-                self.value() - unc.0 / 100.0 * self.value().abs()
+            Some(uncertainty) if uncertainty.is_distribution() => uncertainty
+                .sample(self.value(), rng)
+                .scale(self.escalation_factor(date)),
+            _ => {
+                let (value, min, max) = self.amounts_on(date);
+                triangular(min, value, max, rng)
             }
-            Some(Uncertainty::Unbalanced {
-                low: UncertaintyType::Dollars(unc),
-                high: _,
-            }) => self.value() - unc.0,
-            Some(Uncertainty::Unbalanced {
-                low: UncertaintyType::Percent(unc),
-                high: _,
-            }) => self.value() - unc.0 / 100.0 * self.value().abs(),
-            Some(Uncertainty::Bounds { low: unc, high: _ }) => *unc,
-            None => self.value(),
         }
     }
+
+    /// A random draw of what a balance-dependent delta adds to a simulated `balance`, drawn the
+    /// same way as [`Delta::sample_on`]
+    fn sample_evaluate(&self, balance: Money, rng: &mut dyn RngCore) -> Money {
+        let state = PredictionState::new(balance, balance, balance, [].into());
+        let (value, min, max) = self.evaluate(&state);
+        triangular(min, value, max, rng)
+    }
+
+    /// Percent uncertainty is taken of the value's magnitude and rounded to the nearest cent, with
+    /// halves rounded away from zero (see [`Money::percent`]). Distributions give their high
+    /// quantile.
+    fn max_uncertainty_value(&self) -> Money {
+        self.uncertainty()
+            .as_ref()
+            .map_or(self.value(), |uncertainty| uncertainty.band(self.value()).1)
+    }
+
+    /// Percent uncertainty is rounded the same way as in [`Delta::max_uncertainty_value`], so
+    /// balanced uncertainty stays symmetric around the value. Distributions give their low
+    /// quantile.
+    fn min_uncertainty_value(&self) -> Money {
+        self.uncertainty()
+            .as_ref()
+            .map_or(self.value(), |uncertainty| uncertainty.band(self.value()).0)
+    }
 }
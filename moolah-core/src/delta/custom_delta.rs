@@ -1,33 +1,177 @@
-use super::{reasonable_bounds, Delta, Uncertainty};
-use crate::errors::MoolahCoreError;
+#[cfg(feature = "serde")]
+use super::TaggedDeltaRef;
+use super::{Delta, DeltaMut, Uncertainty};
+use crate::{currency::Currency, errors::MoolahCoreError, money::Money, simulation::triangular};
 use chrono::NaiveDate;
+use rand::RngCore;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeMap, iter};
 
+/// A delta on any list of `dates`, such as a known payment plan. Each date adds the `value`
+/// unless it has its own amount in `amounts`.
 #[derive(Default)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(try_from = "RawCustomDelta")
+)]
 pub struct CustomDelta {
     name: String,
-    value: f64,
+    value: Money,
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
     uncertainty: Option<Uncertainty>,
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    currency: Option<Currency>,
+    #[cfg_attr(feature = "serde", serde(rename = "dates"))]
+    value_dates: Vec<NaiveDate>,
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "BTreeMap::is_empty"))]
+    amounts: BTreeMap<NaiveDate, Money>,
+    #[cfg_attr(feature = "serde", serde(skip))]
     dates: Vec<NaiveDate>,
 }
 
 impl CustomDelta {
     pub fn try_new(
         name: String,
-        value: f64,
+        value: Money,
         uncertainty: Option<Uncertainty>,
-        dates: Vec<NaiveDate>,
+        mut dates: Vec<NaiveDate>,
     ) -> Result<Self, MoolahCoreError> {
-        if let Some(Uncertainty::Bounds { low, high }) = uncertainty {
-            reasonable_bounds(low, high, value)?;
+        if let Some(uncertainty) = &uncertainty {
+            uncertainty.validate(value)?;
         }
 
+        dates.sort();
         Ok(CustomDelta {
             name,
             value,
             uncertainty,
+            currency: None,
+            value_dates: dates.clone(),
             dates,
+            amounts: BTreeMap::new(),
         })
     }
+
+    /// Add an explicit amount on each of its dates, in place of the value
+    pub fn with_amounts(
+        mut self,
+        amounts: impl IntoIterator<Item = (NaiveDate, Money)>,
+    ) -> Result<Self, MoolahCoreError> {
+        for (date, amount) in amounts {
+            if let Some(uncertainty) = &self.uncertainty {
+                uncertainty.validate(amount)?;
+            }
+            self.insert_amount(date, amount);
+        }
+        Ok(self)
+    }
+
+    fn insert_amount(&mut self, date: NaiveDate, amount: Money) {
+        self.value_dates.retain(|value_date| *value_date != date);
+        if let Err(index) = self.dates.binary_search(&date) {
+            self.dates.insert(index, date);
+        }
+        self.amounts.insert(date, amount);
+    }
+
+    /// The dates which add the value, rather than an amount of their own
+    pub fn value_dates(&self) -> &[NaiveDate] {
+        &self.value_dates
+    }
+
+    pub fn amounts(&self) -> &BTreeMap<NaiveDate, Money> {
+        &self.amounts
+    }
+
+    fn amount_on(&self, date: &NaiveDate) -> Money {
+        self.amounts.get(date).copied().unwrap_or(self.value)
+    }
+
+    pub fn with_currency(mut self, currency: Currency) -> Self {
+        self.currency = Some(currency);
+        self
+    }
+
+    pub fn set_name(&mut self, name: String) {
+        self.name = name;
+    }
+
+    pub fn set_value(&mut self, value: Money) -> Result<(), MoolahCoreError> {
+        super::set_value(&mut self.value, &self.uncertainty, value)
+    }
+
+    pub fn set_uncertainty(
+        &mut self,
+        uncertainty: Option<Uncertainty>,
+    ) -> Result<(), MoolahCoreError> {
+        super::set_uncertainty(
+            &mut self.uncertainty,
+            iter::once(self.value).chain(self.amounts.values().copied()),
+            uncertainty,
+        )
+    }
+
+    pub fn set_currency(&mut self, currency: Option<Currency>) {
+        self.currency = currency;
+    }
+
+    /// The dates which add the value. Dates with an amount of their own keep it.
+    pub fn set_dates(&mut self, mut dates: Vec<NaiveDate>) {
+        dates.sort();
+        self.value_dates = dates.clone();
+        self.dates = dates;
+        for (date, amount) in std::mem::take(&mut self.amounts) {
+            self.insert_amount(date, amount);
+        }
+    }
+
+    /// Replace the explicit amounts, dropping the dates of any left out
+    pub fn set_amounts(
+        &mut self,
+        amounts: impl IntoIterator<Item = (NaiveDate, Money)>,
+    ) -> Result<(), MoolahCoreError> {
+        let amounts: Vec<_> = amounts.into_iter().collect();
+        if let Some(uncertainty) = &self.uncertainty {
+            for (_, amount) in &amounts {
+                uncertainty.validate(*amount)?;
+            }
+        }
+        self.dates = self.value_dates.clone();
+        self.amounts.clear();
+        for (date, amount) in amounts {
+            self.insert_amount(date, amount);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(feature = "serde")]
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+pub(crate) struct RawCustomDelta {
+    name: String,
+    #[serde(default)]
+    value: Money,
+    uncertainty: Option<Uncertainty>,
+    currency: Option<Currency>,
+    #[serde(default)]
+    dates: Vec<NaiveDate>,
+    #[serde(default)]
+    amounts: BTreeMap<NaiveDate, Money>,
+}
+
+#[cfg(feature = "serde")]
+impl TryFrom<RawCustomDelta> for CustomDelta {
+    type Error = MoolahCoreError;
+
+    fn try_from(raw: RawCustomDelta) -> Result<Self, Self::Error> {
+        let mut delta = CustomDelta::try_new(raw.name, raw.value, raw.uncertainty, raw.dates)?
+            .with_amounts(raw.amounts)?;
+        delta.currency = raw.currency;
+        Ok(delta)
+    }
 }
 
 impl Delta for CustomDelta {
@@ -35,7 +179,7 @@ impl Delta for CustomDelta {
         &self.name
     }
 
-    fn value(&self) -> f64 {
+    fn value(&self) -> Money {
         self.value
     }
 
@@ -43,7 +187,41 @@ impl Delta for CustomDelta {
         &self.uncertainty
     }
 
-    fn dates(&self) -> &[NaiveDate] {
-        &self.dates
+    fn currency(&self) -> Option<&Currency> {
+        self.currency.as_ref()
+    }
+
+    fn dates(&self) -> Box<dyn Iterator<Item = NaiveDate> + '_> {
+        Box::new(self.dates.iter().copied())
+    }
+
+    fn amounts_on(&self, date: &NaiveDate) -> (Money, Money, Money) {
+        let amount = self.amount_on(date);
+        let (min, max) = self
+            .uncertainty
+            .as_ref()
+            .map_or((amount, amount), |uncertainty| uncertainty.band(amount));
+        (amount, min, max)
+    }
+
+    fn sample_on(&self, date: &NaiveDate, rng: &mut dyn RngCore) -> Money {
+        match &self.uncertainty {
+            Some(uncertainty) if uncertainty.is_distribution() => {
+                uncertainty.sample(self.amount_on(date), rng)
+            }
+            _ => {
+                let (value, min, max) = self.amounts_on(date);
+                triangular(min, value, max, rng)
+            }
+        }
+    }
+
+    #[cfg(feature = "serde")]
+    fn tagged(&self) -> TaggedDeltaRef<'_> {
+        TaggedDeltaRef::Custom(self)
+    }
+
+    fn edit(&mut self) -> Option<DeltaMut<'_>> {
+        Some(DeltaMut::Custom(self))
     }
 }
@@ -1,15 +1,30 @@
-use super::{reasonable_bounds, Delta, Uncertainty};
-use crate::errors::MoolahCoreError;
+#[cfg(feature = "serde")]
+use super::TaggedDeltaRef;
+use super::{validate_span, Delta, DeltaMut, Escalation, Uncertainty};
+use crate::{currency::Currency, errors::MoolahCoreError, money::Money};
 use chrono::{Duration, Local, NaiveDate};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::iter;
 
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(try_from = "RawDailyDelta")
+)]
 pub struct DailyDelta {
     name: String,
-    value: f64,
+    value: Money,
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
     uncertainty: Option<Uncertainty>,
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    currency: Option<Currency>,
     start: NaiveDate,
-    end: NaiveDate,
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    end: Option<NaiveDate>,
     skip_days: u32,
-    dates: Vec<NaiveDate>,
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    escalation: Option<Escalation>,
 }
 
 impl Default for DailyDelta {
@@ -20,36 +35,40 @@ impl Default for DailyDelta {
             name: Default::default(),
             value: Default::default(),
             uncertainty: Default::default(),
+            currency: Default::default(),
             start: today,
-            end: today,
+            end: Some(today),
             skip_days: Default::default(),
-            dates: vec![today],
+            escalation: Default::default(),
         }
     }
 }
 
-fn build_dates(start: &NaiveDate, end: &NaiveDate, every_days: i64) -> Vec<NaiveDate> {
+fn build_dates(
+    start: NaiveDate,
+    end: Option<NaiveDate>,
+    every_days: i64,
+) -> impl Iterator<Item = NaiveDate> {
     let duration = Duration::days(every_days);
-    let n_days: i32 = ((*end - *start).num_days() / every_days)
-        .try_into()
-        .expect("Too many days");
-    (0..=n_days).map(|date| *start + duration * date).collect()
+    iter::successors(Some(start), move |date| date.checked_add_signed(duration))
+        .take_while(move |date| end.is_none_or(|end| *date <= end))
 }
 
 impl DailyDelta {
     pub fn try_new(
         name: String,
-        value: f64,
+        value: Money,
         uncertainty: Option<Uncertainty>,
         start: NaiveDate,
-        end: NaiveDate,
+        end: impl Into<Option<NaiveDate>>,
         skip_days: u32,
     ) -> Result<Self, MoolahCoreError> {
-        if let Some(Uncertainty::Bounds { low, high }) = uncertainty {
-            reasonable_bounds(low, high, value)?;
+        if let Some(uncertainty) = &uncertainty {
+            uncertainty.validate(value)?;
         }
 
-        if start > end {
+        let end = end.into();
+        if let Some(end) = end.filter(|end| start > *end) {
             return Err(MoolahCoreError::StartAfterEnd { start, end });
         }
 
@@ -57,10 +76,11 @@ impl DailyDelta {
             name,
             value,
             uncertainty,
+            currency: None,
             start,
             end,
             skip_days,
-            dates: build_dates(&start, &end, (skip_days + 1).into()),
+            escalation: None,
         })
     }
 
@@ -68,13 +88,100 @@ impl DailyDelta {
         &self.start
     }
 
-    pub fn end(&self) -> &NaiveDate {
-        &self.end
+    pub fn end(&self) -> Option<&NaiveDate> {
+        self.end.as_ref()
     }
 
     pub fn skip_days(&self) -> u32 {
         self.skip_days
     }
+
+    pub fn escalation(&self) -> Option<&Escalation> {
+        self.escalation.as_ref()
+    }
+
+    pub fn with_escalation(mut self, escalation: Escalation) -> Result<Self, MoolahCoreError> {
+        escalation.validate()?;
+        self.escalation = Some(escalation);
+        Ok(self)
+    }
+
+    pub fn with_currency(mut self, currency: Currency) -> Self {
+        self.currency = Some(currency);
+        self
+    }
+
+    pub fn set_name(&mut self, name: String) {
+        self.name = name;
+    }
+
+    pub fn set_value(&mut self, value: Money) -> Result<(), MoolahCoreError> {
+        super::set_value(&mut self.value, &self.uncertainty, value)
+    }
+
+    pub fn set_uncertainty(
+        &mut self,
+        uncertainty: Option<Uncertainty>,
+    ) -> Result<(), MoolahCoreError> {
+        super::set_uncertainty(&mut self.uncertainty, [self.value], uncertainty)
+    }
+
+    pub fn set_currency(&mut self, currency: Option<Currency>) {
+        self.currency = currency;
+    }
+
+    pub fn set_start(&mut self, start: NaiveDate) -> Result<(), MoolahCoreError> {
+        validate_span(start, self.end)?;
+        self.start = start;
+        Ok(())
+    }
+
+    /// `None` to recur without an end
+    pub fn set_end(&mut self, end: Option<NaiveDate>) -> Result<(), MoolahCoreError> {
+        validate_span(self.start, end)?;
+        self.end = end;
+        Ok(())
+    }
+
+    pub fn set_skip_days(&mut self, skip_days: u32) {
+        self.skip_days = skip_days;
+    }
+}
+
+#[cfg(feature = "serde")]
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+pub(crate) struct RawDailyDelta {
+    name: String,
+    value: Money,
+    uncertainty: Option<Uncertainty>,
+    currency: Option<Currency>,
+    start: NaiveDate,
+    end: Option<NaiveDate>,
+    #[serde(default)]
+    skip_days: u32,
+    escalation: Option<Escalation>,
+}
+
+#[cfg(feature = "serde")]
+impl TryFrom<RawDailyDelta> for DailyDelta {
+    type Error = MoolahCoreError;
+
+    fn try_from(raw: RawDailyDelta) -> Result<Self, Self::Error> {
+        let mut delta = DailyDelta::try_new(
+            raw.name,
+            raw.value,
+            raw.uncertainty,
+            raw.start,
+            raw.end,
+            raw.skip_days,
+        )?;
+        delta.currency = raw.currency;
+        match raw.escalation {
+            Some(escalation) => delta.with_escalation(escalation),
+            None => Ok(delta),
+        }
+    }
 }
 
 impl Delta for DailyDelta {
@@ -82,7 +189,7 @@ impl Delta for DailyDelta {
         &self.name
     }
 
-    fn value(&self) -> f64 {
+    fn value(&self) -> Money {
         self.value
     }
 
@@ -90,7 +197,34 @@ impl Delta for DailyDelta {
         &self.uncertainty
     }
 
-    fn dates(&self) -> &[NaiveDate] {
-        &self.dates
+    fn currency(&self) -> Option<&Currency> {
+        self.currency.as_ref()
+    }
+
+    fn dates(&self) -> Box<dyn Iterator<Item = NaiveDate> + '_> {
+        Box::new(build_dates(
+            self.start,
+            self.end,
+            (self.skip_days + 1).into(),
+        ))
+    }
+
+    fn is_open_ended(&self) -> bool {
+        self.end.is_none()
+    }
+
+    fn escalation_factor(&self, date: &NaiveDate) -> f64 {
+        self.escalation
+            .as_ref()
+            .map_or(1.0, |escalation| escalation.factor(&self.start, date))
+    }
+
+    #[cfg(feature = "serde")]
+    fn tagged(&self) -> TaggedDeltaRef<'_> {
+        TaggedDeltaRef::Daily(self)
+    }
+
+    fn edit(&mut self) -> Option<DeltaMut<'_>> {
+        Some(DeltaMut::Daily(self))
     }
 }
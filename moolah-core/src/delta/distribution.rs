@@ -0,0 +1,256 @@
+use super::{reasonable_bounds, Uncertainty};
+use crate::{
+    errors::MoolahCoreError,
+    money::Money,
+    simulation::{quantile, triangular},
+};
+use rand::{Rng, RngCore};
+use rand_distr::{Distribution, StandardNormal};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// The quantiles of a distribution taken as a delta's min & max, 5% and 95% by default
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(try_from = "RawQuantiles")
+)]
+pub struct Quantiles {
+    low: f64,
+    high: f64,
+}
+
+impl Default for Quantiles {
+    fn default() -> Self {
+        Quantiles {
+            low: 0.05,
+            high: 0.95,
+        }
+    }
+}
+
+impl Quantiles {
+    /// Both quantiles are fractions strictly between 0 and 1, as the tails of normal and lognormal
+    /// distributions never end
+    pub fn try_new(low: f64, high: f64) -> Result<Self, MoolahCoreError> {
+        if (0.0 < low) & (low <= high) & (high < 1.0) {
+            Ok(Quantiles { low, high })
+        } else {
+            Err(MoolahCoreError::InvalidQuantiles { low, high })
+        }
+    }
+
+    pub fn low(&self) -> f64 {
+        self.low
+    }
+
+    pub fn high(&self) -> f64 {
+        self.high
+    }
+
+    #[cfg(feature = "serde")]
+    pub(crate) fn is_default(&self) -> bool {
+        *self == Quantiles::default()
+    }
+}
+
+#[cfg(feature = "serde")]
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+pub(crate) struct RawQuantiles {
+    low: f64,
+    high: f64,
+}
+
+#[cfg(feature = "serde")]
+impl TryFrom<RawQuantiles> for Quantiles {
+    type Error = MoolahCoreError;
+
+    fn try_from(raw: RawQuantiles) -> Result<Self, Self::Error> {
+        Quantiles::try_new(raw.low, raw.high)
+    }
+}
+
+/// The inverse of the standard normal CDF, by Acklam's rational approximation (relative error
+/// below 1.2e-9)
+fn probit(p: f64) -> f64 {
+    const A: [f64; 6] = [
+        -3.969_683_028_665_376e1,
+        2.209_460_984_245_205e2,
+        -2.759_285_104_469_687e2,
+        1.383_577_518_672_69e2,
+        -3.066_479_806_614_716e1,
+        2.506_628_277_459_239,
+    ];
+    const B: [f64; 5] = [
+        -5.447_609_879_822_406e1,
+        1.615_858_368_580_409e2,
+        -1.556_989_798_598_866e2,
+        6.680_131_188_771_972e1,
+        -1.328_068_155_288_572e1,
+    ];
+    const C: [f64; 6] = [
+        -7.784_894_002_430_293e-3,
+        -3.223_964_580_411_365e-1,
+        -2.400_758_277_161_838,
+        -2.549_732_539_343_734,
+        4.374_664_141_464_968,
+        2.938_163_982_698_783,
+    ];
+    const D: [f64; 4] = [
+        7.784_695_709_041_462e-3,
+        3.224_671_290_700_398e-1,
+        2.445_134_137_142_996,
+        3.754_408_661_907_416,
+    ];
+    const P_LOW: f64 = 0.02425;
+
+    let tail = |q: f64| {
+        let q = (-2.0 * q.ln()).sqrt();
+        (((((C[0] * q + C[1]) * q + C[2]) * q + C[3]) * q + C[4]) * q + C[5])
+            / ((((D[0] * q + D[1]) * q + D[2]) * q + D[3]) * q + 1.0)
+    };
+
+    if p < P_LOW {
+        tail(p)
+    } else if p <= 1.0 - P_LOW {
+        let q = p - 0.5;
+        let r = q * q;
+        (((((A[0] * r + A[1]) * r + A[2]) * r + A[3]) * r + A[4]) * r + A[5]) * q
+            / (((((B[0] * r + B[1]) * r + B[2]) * r + B[3]) * r + B[4]) * r + 1.0)
+    } else {
+        -tail(1.0 - p)
+    }
+}
+
+fn to_money(amount: f64) -> Money {
+    Money::from_cents((amount * 100.0).round() as i64)
+}
+
+impl Uncertainty {
+    /// Check the uncertainty makes sense for a delta worth `value`. Bounds, including those of
+    /// triangular, uniform & empirical distributions, must contain the value.
+    pub fn validate(&self, value: Money) -> Result<(), MoolahCoreError> {
+        match self {
+            Uncertainty::Bounds { low, high }
+            | Uncertainty::Triangular { low, high, .. }
+            | Uncertainty::Uniform { low, high, .. } => reasonable_bounds(*low, *high, value),
+            Uncertainty::LogNormal { sigma, .. } if !sigma.0.is_finite() => {
+                Err(MoolahCoreError::InvalidSigma(sigma.0))
+            }
+            Uncertainty::Empirical { amounts, .. } => {
+                let low = amounts
+                    .iter()
+                    .min()
+                    .ok_or(MoolahCoreError::EmptyDistribution)?;
+                let high = amounts
+                    .iter()
+                    .max()
+                    .ok_or(MoolahCoreError::EmptyDistribution)?;
+                reasonable_bounds(*low, *high, value)
+            }
+            _ => Ok(()),
+        }
+    }
+
+    pub fn is_distribution(&self) -> bool {
+        self.quantiles().is_some()
+    }
+
+    /// The quantiles taken as min & max, if the uncertainty is a distribution
+    pub fn quantiles(&self) -> Option<&Quantiles> {
+        match self {
+            Uncertainty::Normal { quantiles, .. }
+            | Uncertainty::LogNormal { quantiles, .. }
+            | Uncertainty::Triangular { quantiles, .. }
+            | Uncertainty::Uniform { quantiles, .. }
+            | Uncertainty::Empirical { quantiles, .. } => Some(quantiles),
+            Uncertainty::Balanced(_)
+            | Uncertainty::Unbalanced { .. }
+            | Uncertainty::Bounds { .. } => None,
+        }
+    }
+
+    /// The amount a `fraction` of draws fall at or below, for a distribution around `value`.
+    /// The tails of normal and lognormal distributions never end, so they have no amount at a
+    /// fraction of 0 or 1 and give `None` there.
+    pub fn quantile(&self, value: Money, fraction: f64) -> Option<Money> {
+        let unbounded = matches!(
+            self,
+            Uncertainty::Normal { .. } | Uncertainty::LogNormal { .. }
+        );
+        if !(0.0..=1.0).contains(&fraction) | (unbounded & ((fraction == 0.0) | (fraction == 1.0)))
+        {
+            return None;
+        }
+
+        let amount = match self {
+            Uncertainty::Normal { std_dev, .. } => value + std_dev.0.scale(probit(fraction)),
+            Uncertainty::LogNormal { sigma, .. } => {
+                let factor = (sigma.0 * probit(fraction)).exp();
+                // A negative value's tail stretches downwards
+                value.scale(if value.is_negative() {
+                    1.0 / factor
+                } else {
+                    factor
+                })
+            }
+            Uncertainty::Triangular { low, high, .. } => {
+                let (low, mode, high) = (low.to_f64(), value.to_f64(), high.to_f64());
+                let range = high - low;
+                if range == 0.0 {
+                    value
+                } else if fraction < (mode - low) / range {
+                    to_money(low + (fraction * range * (mode - low)).sqrt())
+                } else {
+                    to_money(high - ((1.0 - fraction) * range * (high - mode)).sqrt())
+                }
+            }
+            Uncertainty::Uniform { low, high, .. } => *low + (*high - *low).scale(fraction),
+            Uncertainty::Empirical { amounts, .. } if amounts.is_empty() => return None,
+            Uncertainty::Empirical { amounts, .. } => {
+                let mut sorted = amounts.clone();
+                sorted.sort();
+                quantile(&sorted, fraction)
+            }
+            Uncertainty::Balanced(_)
+            | Uncertainty::Unbalanced { .. }
+            | Uncertainty::Bounds { .. } => return None,
+        };
+
+        Some(amount)
+    }
+
+    /// The amounts at the low & high quantiles, if the uncertainty is a distribution
+    pub(crate) fn quantile_band(&self, value: Money) -> Option<(Money, Money)> {
+        let quantiles = self.quantiles()?;
+        Some((
+            self.quantile(value, quantiles.low)?,
+            self.quantile(value, quantiles.high)?,
+        ))
+    }
+
+    /// A random draw from the distribution around `value`. Uncertainties that are only ranges
+    /// give the value itself.
+    pub(crate) fn sample(&self, value: Money, rng: &mut dyn RngCore) -> Money {
+        match self {
+            Uncertainty::Normal { std_dev, .. } => {
+                let z: f64 = StandardNormal.sample(rng);
+                value + std_dev.0.scale(z)
+            }
+            Uncertainty::LogNormal { sigma, .. } => {
+                let z: f64 = StandardNormal.sample(rng);
+                value.scale((sigma.0 * z).exp())
+            }
+            Uncertainty::Triangular { low, high, .. } => triangular(*low, value, *high, rng),
+            Uncertainty::Uniform { low, high, .. } => {
+                Money::from_cents(rng.random_range(low.cents()..=high.cents()))
+            }
+            Uncertainty::Empirical { amounts, .. } => amounts[rng.random_range(0..amounts.len())],
+            Uncertainty::Balanced(_)
+            | Uncertainty::Unbalanced { .. }
+            | Uncertainty::Bounds { .. } => value,
+        }
+    }
+}
@@ -0,0 +1,109 @@
+use crate::errors::MoolahCoreError;
+use chrono::{Datelike, NaiveDate};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// How a recurring delta's amount changes over time, such as rent going up at each lease renewal
+/// or groceries rising with inflation. Each occurrence's amount, and its uncertainty, is the
+/// delta's value scaled by [`Escalation::factor`].
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(rename_all = "snake_case")
+)]
+pub enum Escalation {
+    /// Up by a fixed percent on each anniversary of the delta's start
+    Annual(f64),
+    /// Up (or down) by each step's percent from its date onwards
+    Steps(Vec<EscalationStep>),
+    /// In proportion to an index such as CPI, relative to its level when the delta starts. The
+    /// index keeps each level until the next one.
+    Index(Vec<IndexLevel>),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(deny_unknown_fields)
+)]
+pub struct EscalationStep {
+    pub date: NaiveDate,
+    pub percent: f64,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(deny_unknown_fields)
+)]
+pub struct IndexLevel {
+    pub date: NaiveDate,
+    pub level: f64,
+}
+
+fn valid_percent(percent: f64) -> bool {
+    percent.is_finite() & (percent > -100.0)
+}
+
+impl Escalation {
+    pub fn validate(&self) -> Result<(), MoolahCoreError> {
+        let invalid = |reason: &str| Err(MoolahCoreError::InvalidEscalation(reason.into()));
+        match self {
+            Escalation::Annual(percent) if !valid_percent(*percent) => {
+                invalid("the annual percent must be finite and above -100")
+            }
+            Escalation::Steps(steps) if steps.iter().any(|step| !valid_percent(step.percent)) => {
+                invalid("each step's percent must be finite and above -100")
+            }
+            Escalation::Index(levels) if levels.is_empty() => {
+                invalid("an index needs at least one level")
+            }
+            Escalation::Index(levels)
+                if levels
+                    .iter()
+                    .any(|level| !level.level.is_finite() | (level.level <= 0.0)) =>
+            {
+                invalid("index levels must be finite and positive")
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// How much an occurrence on `date` is scaled by, for a delta starting on `start`
+    pub fn factor(&self, start: &NaiveDate, date: &NaiveDate) -> f64 {
+        match self {
+            Escalation::Annual(percent) => {
+                (1.0 + percent / 100.0).powi(anniversaries(start, date) as i32)
+            }
+            Escalation::Steps(steps) => steps
+                .iter()
+                .filter(|step| step.date <= *date)
+                .map(|step| 1.0 + step.percent / 100.0)
+                .product(),
+            Escalation::Index(levels) => level_on(levels, date) / level_on(levels, start),
+        }
+    }
+}
+
+/// Full years from `start` to `date`
+fn anniversaries(start: &NaiveDate, date: &NaiveDate) -> u32 {
+    if date <= start {
+        return 0;
+    }
+    let years = date.year() - start.year();
+    let before_anniversary = (date.month(), date.day()) < (start.month(), start.day());
+    (years - i32::from(before_anniversary)) as u32
+}
+
+/// The latest level on or before `date`, or the first level if `date` comes before them all
+fn level_on(levels: &[IndexLevel], date: &NaiveDate) -> f64 {
+    levels
+        .iter()
+        .filter(|level| level.date <= *date)
+        .max_by_key(|level| level.date)
+        .or_else(|| levels.iter().min_by_key(|level| level.date))
+        .map_or(1.0, |level| level.level)
+}
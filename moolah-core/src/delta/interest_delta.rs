@@ -0,0 +1,320 @@
+#[cfg(feature = "serde")]
+use super::TaggedDeltaRef;
+use super::{validate_span, Delta, DeltaMut, PositiveF64, Uncertainty};
+use crate::{
+    currency::Currency, errors::MoolahCoreError, money::Money, prediction::PredictionState,
+};
+use chrono::{Days, Local, Months, NaiveDate};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(rename_all = "snake_case")
+)]
+pub enum Compounding {
+    Daily,
+    #[default]
+    Monthly,
+    Yearly,
+}
+
+impl Compounding {
+    fn periods_per_year(&self) -> f64 {
+        match self {
+            Compounding::Daily => 365.0,
+            Compounding::Monthly => 12.0,
+            Compounding::Yearly => 1.0,
+        }
+    }
+
+    fn nth_period_end(&self, start: &NaiveDate, n: u32) -> Option<NaiveDate> {
+        match self {
+            Compounding::Daily => start.checked_add_days(Days::new(n.into())),
+            Compounding::Monthly => start.checked_add_months(Months::new(n)),
+            Compounding::Yearly => start.checked_add_months(Months::new(n.checked_mul(12)?)),
+        }
+    }
+}
+
+/// Uncertainty on an annual rate, in percentage points
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(rename_all = "snake_case")
+)]
+pub enum RateUncertainty {
+    Balanced(PositiveF64),
+    Bounds { low: f64, high: f64 },
+}
+
+/// Interest or investment growth on the running balance.
+///
+/// `rate` is a nominal annual percent, so with monthly compounding a 6% rate adds 0.5% of the
+/// balance at the end of each month from `start` until `end`, or indefinitely without an `end`.
+/// Interest is taken of the balance just before each compounding date, and min & max take the
+/// rate's uncertainty into account.
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(try_from = "RawInterestDelta")
+)]
+pub struct InterestDelta {
+    name: String,
+    rate: f64,
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    rate_uncertainty: Option<RateUncertainty>,
+    compounding: Compounding,
+    start: NaiveDate,
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    end: Option<NaiveDate>,
+}
+
+impl Default for InterestDelta {
+    fn default() -> Self {
+        let today = Local::now().date_naive();
+
+        InterestDelta {
+            name: Default::default(),
+            rate: Default::default(),
+            rate_uncertainty: Default::default(),
+            compounding: Default::default(),
+            start: today,
+            end: Some(today),
+        }
+    }
+}
+
+impl InterestDelta {
+    pub fn try_new(
+        name: String,
+        rate: f64,
+        rate_uncertainty: Option<RateUncertainty>,
+        compounding: Compounding,
+        start: NaiveDate,
+        end: impl Into<Option<NaiveDate>>,
+    ) -> Result<Self, MoolahCoreError> {
+        if !rate.is_finite() {
+            return Err(MoolahCoreError::InvalidInterestRate(rate));
+        }
+
+        if let Some(RateUncertainty::Bounds { low, high }) = rate_uncertainty {
+            if !((low <= rate) & (rate <= high)) {
+                return Err(MoolahCoreError::IllogicalRateBounds { low, high, rate });
+            }
+        }
+
+        let end = end.into();
+        validate_span(start, end)?;
+
+        Ok(InterestDelta {
+            name,
+            rate,
+            rate_uncertainty,
+            compounding,
+            start,
+            end,
+        })
+    }
+
+    pub fn rate(&self) -> f64 {
+        self.rate
+    }
+
+    pub fn rate_uncertainty(&self) -> &Option<RateUncertainty> {
+        &self.rate_uncertainty
+    }
+
+    pub fn compounding(&self) -> &Compounding {
+        &self.compounding
+    }
+
+    pub fn start(&self) -> &NaiveDate {
+        &self.start
+    }
+
+    pub fn end(&self) -> Option<&NaiveDate> {
+        self.end.as_ref()
+    }
+
+    pub fn set_name(&mut self, name: String) {
+        self.name = name;
+    }
+
+    pub fn set_rate(&mut self, rate: f64) -> Result<(), MoolahCoreError> {
+        self.reschedule(
+            rate,
+            self.rate_uncertainty.clone(),
+            self.compounding,
+            self.start,
+            self.end,
+        )
+    }
+
+    pub fn set_rate_uncertainty(
+        &mut self,
+        rate_uncertainty: Option<RateUncertainty>,
+    ) -> Result<(), MoolahCoreError> {
+        self.reschedule(
+            self.rate,
+            rate_uncertainty,
+            self.compounding,
+            self.start,
+            self.end,
+        )
+    }
+
+    pub fn set_compounding(&mut self, compounding: Compounding) -> Result<(), MoolahCoreError> {
+        self.reschedule(
+            self.rate,
+            self.rate_uncertainty.clone(),
+            compounding,
+            self.start,
+            self.end,
+        )
+    }
+
+    pub fn set_start(&mut self, start: NaiveDate) -> Result<(), MoolahCoreError> {
+        self.reschedule(
+            self.rate,
+            self.rate_uncertainty.clone(),
+            self.compounding,
+            start,
+            self.end,
+        )
+    }
+
+    pub fn set_end(&mut self, end: Option<NaiveDate>) -> Result<(), MoolahCoreError> {
+        self.reschedule(
+            self.rate,
+            self.rate_uncertainty.clone(),
+            self.compounding,
+            self.start,
+            end,
+        )
+    }
+
+    /// Take on new terms, or keep the old ones if the new are invalid
+    fn reschedule(
+        &mut self,
+        rate: f64,
+        rate_uncertainty: Option<RateUncertainty>,
+        compounding: Compounding,
+        start: NaiveDate,
+        end: Option<NaiveDate>,
+    ) -> Result<(), MoolahCoreError> {
+        *self = InterestDelta::try_new(
+            self.name.clone(),
+            rate,
+            rate_uncertainty,
+            compounding,
+            start,
+            end,
+        )?;
+        Ok(())
+    }
+
+    /// The lowest and highest annual rates
+    pub fn rate_range(&self) -> (f64, f64) {
+        match self.rate_uncertainty {
+            Some(RateUncertainty::Balanced(unc)) => (self.rate - unc.0, self.rate + unc.0),
+            Some(RateUncertainty::Bounds { low, high }) => (low, high),
+            None => (self.rate, self.rate),
+        }
+    }
+
+    fn period_factor(&self, rate: f64) -> f64 {
+        rate / 100.0 / self.compounding.periods_per_year()
+    }
+}
+
+#[cfg(feature = "serde")]
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+pub(crate) struct RawInterestDelta {
+    name: String,
+    rate: f64,
+    rate_uncertainty: Option<RateUncertainty>,
+    #[serde(default)]
+    compounding: Compounding,
+    start: NaiveDate,
+    end: Option<NaiveDate>,
+}
+
+#[cfg(feature = "serde")]
+impl TryFrom<RawInterestDelta> for InterestDelta {
+    type Error = MoolahCoreError;
+
+    fn try_from(raw: RawInterestDelta) -> Result<Self, Self::Error> {
+        InterestDelta::try_new(
+            raw.name,
+            raw.rate,
+            raw.rate_uncertainty,
+            raw.compounding,
+            raw.start,
+            raw.end,
+        )
+    }
+}
+
+impl Delta for InterestDelta {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Interest has no fixed value, see [`Delta::evaluate`]
+    fn value(&self) -> Money {
+        Money::ZERO
+    }
+
+    /// See [`InterestDelta::rate_uncertainty`] instead
+    fn uncertainty(&self) -> &Option<Uncertainty> {
+        &None
+    }
+
+    /// Interest is always in the currency of the balance it is earned on
+    fn currency(&self) -> Option<&Currency> {
+        None
+    }
+
+    fn dates(&self) -> Box<dyn Iterator<Item = NaiveDate> + '_> {
+        Box::new(
+            (1..)
+                .map_while(|n| self.compounding.nth_period_end(&self.start, n))
+                .take_while(|date| self.end.is_none_or(|end| *date <= end)),
+        )
+    }
+
+    fn is_open_ended(&self) -> bool {
+        self.end.is_none()
+    }
+
+    #[cfg(feature = "serde")]
+    fn tagged(&self) -> TaggedDeltaRef<'_> {
+        TaggedDeltaRef::Interest(self)
+    }
+
+    fn edit(&mut self) -> Option<DeltaMut<'_>> {
+        Some(DeltaMut::Interest(self))
+    }
+
+    fn is_balance_dependent(&self) -> bool {
+        true
+    }
+
+    fn evaluate(&self, state: &PredictionState) -> (Money, Money, Money) {
+        let (low, high) = self.rate_range();
+        let (low, high) = (self.period_factor(low), self.period_factor(high));
+
+        let min_balance = state.min_uncertainty_val();
+        let max_balance = state.max_uncertainty_val();
+        (
+            state.value().scale(self.period_factor(self.rate)),
+            min_balance.scale(low).min(min_balance.scale(high)),
+            max_balance.scale(low).max(max_balance.scale(high)),
+        )
+    }
+}
@@ -0,0 +1,479 @@
+#[cfg(feature = "serde")]
+use super::monthly_delta::add_months::RawMonthDay;
+#[cfg(feature = "serde")]
+use super::TaggedDeltaRef;
+use super::{monthly_delta, Delta, DeltaMut, MonthDay, Uncertainty};
+use crate::{
+    calendar::{Calendar, RollConvention},
+    currency::Currency,
+    errors::MoolahCoreError,
+    money::Money,
+};
+use chrono::{Days, Local, Months, NaiveDate};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// A payment on top of the scheduled ones, which goes entirely to principal
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(rename_all = "snake_case")
+)]
+pub enum ExtraPayment {
+    /// Added to every scheduled payment on or after `from`
+    Recurring { amount: Money, from: NaiveDate },
+    /// Added to the first scheduled payment on or after `date`
+    OneTime { amount: Money, date: NaiveDate },
+}
+
+impl ExtraPayment {
+    fn amount(&self) -> Money {
+        match self {
+            ExtraPayment::Recurring { amount, .. } | ExtraPayment::OneTime { amount, .. } => {
+                *amount
+            }
+        }
+    }
+
+    /// The extra paid with the scheduled payment on `date`, the previous one having been on
+    /// `previous`
+    fn amount_on(&self, previous: &NaiveDate, date: &NaiveDate) -> Money {
+        match self {
+            ExtraPayment::Recurring { amount, from } if from <= date => *amount,
+            ExtraPayment::OneTime { amount, date: paid } if (previous < paid) & (paid <= date) => {
+                *amount
+            }
+            _ => Money::ZERO,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct LoanPayment {
+    date: NaiveDate,
+    interest: Money,
+    principal: Money,
+    balance: Money,
+}
+
+impl LoanPayment {
+    pub fn date(&self) -> &NaiveDate {
+        &self.date
+    }
+
+    /// The whole payment, interest and principal
+    pub fn amount(&self) -> Money {
+        self.interest + self.principal
+    }
+
+    pub fn interest(&self) -> Money {
+        self.interest
+    }
+
+    pub fn principal(&self) -> Money {
+        self.principal
+    }
+
+    /// What is still owed after the payment
+    pub fn balance(&self) -> Money {
+        self.balance
+    }
+}
+
+/// Monthly payments on an amortizing loan such as a mortgage.
+///
+/// `rate` is a nominal annual percent charged monthly. Payments are on `on_month_day`, starting
+/// the first such day after `start`, and are sized to pay `principal` off over `term_months`.
+/// Extra payments shorten the schedule, and the loan ends as soon as it is paid off. The delta's
+/// values are the (negative) payments out of the account.
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(try_from = "RawLoanDelta")
+)]
+pub struct LoanDelta {
+    name: String,
+    principal: Money,
+    rate: f64,
+    term_months: u32,
+    start: NaiveDate,
+    on_month_day: MonthDay,
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Vec::is_empty"))]
+    extra_payments: Vec<ExtraPayment>,
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    currency: Option<Currency>,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    payment: Money,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    schedule: Vec<LoanPayment>,
+}
+
+impl Default for LoanDelta {
+    fn default() -> Self {
+        LoanDelta {
+            name: Default::default(),
+            principal: Default::default(),
+            rate: Default::default(),
+            term_months: 1,
+            start: Local::now().date_naive(),
+            on_month_day: Default::default(),
+            extra_payments: Default::default(),
+            currency: Default::default(),
+            payment: Default::default(),
+            schedule: Default::default(),
+        }
+    }
+}
+
+/// The fixed monthly payment that pays `principal` off in `term_months`
+fn scheduled_payment(principal: Money, monthly_rate: f64, term_months: u32) -> Money {
+    if monthly_rate == 0.0 {
+        return principal.scale(1.0 / f64::from(term_months));
+    }
+    let discount = (1.0 + monthly_rate).powf(-f64::from(term_months));
+    principal.scale(monthly_rate / (1.0 - discount))
+}
+
+fn build_schedule(
+    principal: Money,
+    monthly_rate: f64,
+    payment: Money,
+    start: &NaiveDate,
+    dates: &[NaiveDate],
+    extra_payments: &[ExtraPayment],
+) -> Vec<LoanPayment> {
+    let mut schedule = vec![];
+    let mut balance = principal;
+    let mut previous = *start;
+
+    for (n, date) in dates.iter().enumerate() {
+        if balance <= Money::ZERO {
+            break;
+        }
+
+        let interest = balance.scale(monthly_rate);
+        let extra: Money = extra_payments
+            .iter()
+            .map(|extra| extra.amount_on(&previous, date))
+            .sum();
+        let mut principal = payment - interest + extra;
+        // The last payment settles whatever rounding has left over
+        if (principal > balance) | (n + 1 == dates.len()) {
+            principal = balance;
+        }
+        balance -= principal;
+
+        schedule.push(LoanPayment {
+            date: *date,
+            interest,
+            principal,
+            balance,
+        });
+        previous = *date;
+    }
+
+    schedule
+}
+
+impl LoanDelta {
+    pub fn try_new(
+        name: String,
+        principal: Money,
+        rate: f64,
+        term_months: u32,
+        start: NaiveDate,
+        on_month_day: MonthDay,
+        extra_payments: Vec<ExtraPayment>,
+    ) -> Result<Self, MoolahCoreError> {
+        if principal.is_negative() {
+            return Err(MoolahCoreError::UnexpectedNegativeMoney(principal));
+        }
+        if !rate.is_finite() {
+            return Err(MoolahCoreError::InvalidInterestRate(rate));
+        }
+        if term_months == 0 {
+            return Err(MoolahCoreError::InvalidLoanTerm(term_months));
+        }
+        if let Some(extra) = extra_payments
+            .iter()
+            .find(|extra| extra.amount().is_negative())
+        {
+            return Err(MoolahCoreError::UnexpectedNegativeMoney(extra.amount()));
+        }
+
+        let invalid_term = || MoolahCoreError::InvalidLoanTerm(term_months);
+        let first = start
+            .checked_add_days(Days::new(1))
+            .ok_or_else(invalid_term)?;
+        let last = start
+            .checked_add_months(Months::new(
+                term_months.checked_add(1).ok_or_else(invalid_term)?,
+            ))
+            .ok_or_else(invalid_term)?;
+        let payment_dates: Vec<_> = monthly_delta::build_dates(
+            first,
+            Some(last),
+            &[on_month_day],
+            1,
+            &RollConvention::None,
+            &Calendar::default(),
+        )
+        .take(term_months as usize)
+        .collect();
+
+        let monthly_rate = rate / 100.0 / 12.0;
+        let payment = scheduled_payment(principal, monthly_rate, term_months);
+        let schedule = build_schedule(
+            principal,
+            monthly_rate,
+            payment,
+            &start,
+            &payment_dates,
+            &extra_payments,
+        );
+
+        Ok(LoanDelta {
+            name,
+            principal,
+            rate,
+            term_months,
+            start,
+            on_month_day,
+            extra_payments,
+            currency: None,
+            payment,
+            schedule,
+        })
+    }
+
+    pub fn principal(&self) -> Money {
+        self.principal
+    }
+
+    pub fn rate(&self) -> f64 {
+        self.rate
+    }
+
+    pub fn term_months(&self) -> u32 {
+        self.term_months
+    }
+
+    pub fn start(&self) -> &NaiveDate {
+        &self.start
+    }
+
+    pub fn on_month_day(&self) -> &MonthDay {
+        &self.on_month_day
+    }
+
+    pub fn extra_payments(&self) -> &[ExtraPayment] {
+        &self.extra_payments
+    }
+
+    /// The regular monthly payment, before any extra payments
+    pub fn payment(&self) -> Money {
+        self.payment
+    }
+
+    pub fn schedule(&self) -> &[LoanPayment] {
+        &self.schedule
+    }
+
+    /// What is owed at `start` and after each payment
+    pub fn remaining_balance(&self) -> BTreeMap<NaiveDate, Money> {
+        std::iter::once((self.start, self.principal))
+            .chain(
+                self.schedule
+                    .iter()
+                    .map(|payment| (payment.date, payment.balance)),
+            )
+            .collect()
+    }
+
+    pub fn with_currency(mut self, currency: Currency) -> Self {
+        self.currency = Some(currency);
+        self
+    }
+
+    pub fn set_name(&mut self, name: String) {
+        self.name = name;
+    }
+
+    pub fn set_currency(&mut self, currency: Option<Currency>) {
+        self.currency = currency;
+    }
+
+    pub fn set_principal(&mut self, principal: Money) -> Result<(), MoolahCoreError> {
+        self.reschedule(
+            principal,
+            self.rate,
+            self.term_months,
+            self.start,
+            self.on_month_day,
+            self.extra_payments.clone(),
+        )
+    }
+
+    pub fn set_rate(&mut self, rate: f64) -> Result<(), MoolahCoreError> {
+        self.reschedule(
+            self.principal,
+            rate,
+            self.term_months,
+            self.start,
+            self.on_month_day,
+            self.extra_payments.clone(),
+        )
+    }
+
+    pub fn set_term_months(&mut self, term_months: u32) -> Result<(), MoolahCoreError> {
+        self.reschedule(
+            self.principal,
+            self.rate,
+            term_months,
+            self.start,
+            self.on_month_day,
+            self.extra_payments.clone(),
+        )
+    }
+
+    pub fn set_start(&mut self, start: NaiveDate) -> Result<(), MoolahCoreError> {
+        self.reschedule(
+            self.principal,
+            self.rate,
+            self.term_months,
+            start,
+            self.on_month_day,
+            self.extra_payments.clone(),
+        )
+    }
+
+    pub fn set_on_month_day(&mut self, on_month_day: MonthDay) -> Result<(), MoolahCoreError> {
+        self.reschedule(
+            self.principal,
+            self.rate,
+            self.term_months,
+            self.start,
+            on_month_day,
+            self.extra_payments.clone(),
+        )
+    }
+
+    pub fn set_extra_payments(
+        &mut self,
+        extra_payments: Vec<ExtraPayment>,
+    ) -> Result<(), MoolahCoreError> {
+        self.reschedule(
+            self.principal,
+            self.rate,
+            self.term_months,
+            self.start,
+            self.on_month_day,
+            extra_payments,
+        )
+    }
+
+    /// Take on new terms, recomputing the payment and schedule, or keep the old terms if the new
+    /// are invalid
+    fn reschedule(
+        &mut self,
+        principal: Money,
+        rate: f64,
+        term_months: u32,
+        start: NaiveDate,
+        on_month_day: MonthDay,
+        extra_payments: Vec<ExtraPayment>,
+    ) -> Result<(), MoolahCoreError> {
+        let delta = LoanDelta::try_new(
+            self.name.clone(),
+            principal,
+            rate,
+            term_months,
+            start,
+            on_month_day,
+            extra_payments,
+        )?;
+        *self = LoanDelta {
+            currency: self.currency,
+            ..delta
+        };
+        Ok(())
+    }
+}
+
+#[cfg(feature = "serde")]
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+pub(crate) struct RawLoanDelta {
+    name: String,
+    principal: Money,
+    rate: f64,
+    term_months: u32,
+    start: NaiveDate,
+    #[serde(default)]
+    on_month_day: RawMonthDay,
+    #[serde(default)]
+    extra_payments: Vec<ExtraPayment>,
+    currency: Option<Currency>,
+}
+
+#[cfg(feature = "serde")]
+impl TryFrom<RawLoanDelta> for LoanDelta {
+    type Error = MoolahCoreError;
+
+    fn try_from(raw: RawLoanDelta) -> Result<Self, Self::Error> {
+        let mut delta = LoanDelta::try_new(
+            raw.name,
+            raw.principal,
+            raw.rate,
+            raw.term_months,
+            raw.start,
+            raw.on_month_day.try_into()?,
+            raw.extra_payments,
+        )?;
+        delta.currency = raw.currency;
+        Ok(delta)
+    }
+}
+
+impl Delta for LoanDelta {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The regular payment out of the account. Occurrences with extra payments or the final
+    /// payment differ, see [`Delta::amounts_on`].
+    fn value(&self) -> Money {
+        -self.payment
+    }
+
+    fn uncertainty(&self) -> &Option<Uncertainty> {
+        &None
+    }
+
+    fn currency(&self) -> Option<&Currency> {
+        self.currency.as_ref()
+    }
+
+    fn dates(&self) -> Box<dyn Iterator<Item = NaiveDate> + '_> {
+        Box::new(self.schedule.iter().map(|payment| payment.date))
+    }
+
+    #[cfg(feature = "serde")]
+    fn tagged(&self) -> TaggedDeltaRef<'_> {
+        TaggedDeltaRef::Loan(self)
+    }
+
+    fn edit(&mut self) -> Option<DeltaMut<'_>> {
+        Some(DeltaMut::Loan(self))
+    }
+
+    fn amounts_on(&self, date: &NaiveDate) -> (Money, Money, Money) {
+        let amount = self
+            .schedule
+            .binary_search_by(|payment| payment.date.cmp(date))
+            .map_or(Money::ZERO, |index| -self.schedule[index].amount());
+        (amount, amount, amount)
+    }
+}
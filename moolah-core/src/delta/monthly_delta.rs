@@ -1,19 +1,54 @@
 pub mod add_months;
 
-use super::{reasonable_bounds, Delta, Uncertainty};
-use crate::{date_helpers::naive_ymd, errors::MoolahCoreError};
+#[cfg(feature = "serde")]
+use super::TaggedDeltaRef;
+use super::{validate_span, Delta, DeltaMut, Escalation, Uncertainty};
+use crate::{
+    calendar::{Calendar, RollConvention},
+    currency::Currency,
+    errors::MoolahCoreError,
+    money::Money,
+};
 pub use add_months::MonthDay;
-use chrono::{Datelike, Local, NaiveDate};
+#[cfg(feature = "serde")]
+use add_months::RawMonthDay;
+use chrono::{Datelike, Local, Months, NaiveDate};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::iter;
 
+/// A delta on one or more days of every month, or of every `skip_months + 1` months, such as the
+/// 15th and the last day of the month for a semi-monthly paycheck.
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(try_from = "RawMonthlyDelta")
+)]
 pub struct MonthlyDelta {
     name: String,
-    value: f64,
+    value: Money,
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
     uncertainty: Option<Uncertainty>,
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    currency: Option<Currency>,
     start: NaiveDate,
-    end: NaiveDate,
-    on_month_day: MonthDay,
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    end: Option<NaiveDate>,
+    #[cfg_attr(
+        feature = "serde",
+        serde(rename = "on_month_day", serialize_with = "serialize_month_days")
+    )]
+    on_month_days: Vec<MonthDay>,
     skip_months: u16,
-    dates: Vec<NaiveDate>,
+    #[cfg_attr(
+        feature = "serde",
+        serde(skip_serializing_if = "RollConvention::is_none")
+    )]
+    roll: RollConvention,
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Calendar::is_default"))]
+    calendar: Calendar,
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    escalation: Option<Escalation>,
 }
 
 impl Default for MonthlyDelta {
@@ -24,64 +59,94 @@ impl Default for MonthlyDelta {
             name: Default::default(),
             value: Default::default(),
             uncertainty: Default::default(),
+            currency: Default::default(),
             start: today,
-            end: today,
-            on_month_day: Default::default(),
+            end: Some(today),
+            on_month_days: vec![MonthDay::try_new(1).unwrap()],
             skip_months: Default::default(),
-            dates: vec![today],
+            roll: Default::default(),
+            calendar: Default::default(),
+            escalation: Default::default(),
         }
     }
 }
 
-fn build_dates(
-    start: &NaiveDate,
-    end: &NaiveDate,
-    on_month_day: &MonthDay,
+pub(super) fn build_dates<'a>(
+    start: NaiveDate,
+    end: Option<NaiveDate>,
+    on_month_days: &'a [MonthDay],
     every_months: u32,
-) -> Result<Vec<NaiveDate>, MoolahCoreError> {
-    let month_day: u32 = on_month_day.into();
-    let start = if start.day() > month_day {
-        naive_ymd(start.year(), start.month() + 1, month_day)?
-    } else {
-        naive_ymd(start.year(), start.month(), month_day)?
+    roll: &'a RollConvention,
+    calendar: &'a Calendar,
+) -> impl Iterator<Item = NaiveDate> + 'a {
+    let in_range = move |date: &NaiveDate| (*date >= start) & end.is_none_or(|end| *date <= end);
+    let in_month = move |month: &NaiveDate| {
+        let mut dates = on_month_days
+            .iter()
+            .filter_map(|month_day| month_day.in_month(month.year(), month.month()))
+            .filter(in_range)
+            .collect::<Vec<_>>();
+        dates.sort();
+        dates.dedup();
+        dates
     };
-
-    let end = if end.day() >= on_month_day.into() {
-        naive_ymd(end.year(), end.month(), month_day)?
+    // The schedule starts in the first month with a date on or after `start`
+    let first_month = start.with_day(1).unwrap();
+    let anchor = if in_month(&first_month).is_empty() {
+        first_month.checked_add_months(Months::new(1))
     } else {
-        naive_ymd(end.year(), end.month() - 1, month_day)?
+        Some(first_month)
     };
 
-    let year_diff = (end.year() - start.year()) * 12;
-    let month_diff: i32 = end.month() as i32 - start.month() as i32;
-    let n_tot_months: i32 = year_diff + month_diff;
-
-    if n_tot_months <= 0 {
-        return Ok(vec![]);
-    }
-    let n_months: u32 = n_tot_months as u32 / every_months;
-
-    let target_month_day = *on_month_day;
-    Ok((0..=n_months)
-        .map(|month_num| target_month_day * (month_num * every_months) + start)
-        .collect())
+    let dates = iter::successors(anchor, move |month| {
+        month.checked_add_months(Months::new(every_months))
+    })
+    .take_while(move |month| end.is_none_or(|end| *month <= end))
+    .flat_map(move |month| in_month(&month));
+    roll.roll_all(dates, calendar)
 }
 
 impl MonthlyDelta {
     pub fn try_new(
         name: String,
-        value: f64,
+        value: Money,
         uncertainty: Option<Uncertainty>,
         start: NaiveDate,
-        end: NaiveDate,
+        end: impl Into<Option<NaiveDate>>,
         on_month_day: MonthDay,
         skip_months: u16,
     ) -> Result<Self, MoolahCoreError> {
-        if let Some(Uncertainty::Bounds { low, high }) = uncertainty {
-            reasonable_bounds(low, high, value)?;
+        MonthlyDelta::try_new_on_days(
+            name,
+            value,
+            uncertainty,
+            start,
+            end,
+            vec![on_month_day],
+            skip_months,
+        )
+    }
+
+    /// A delta on each of `on_month_days`, such as `[15, -1]` for the 15th and the last day
+    pub fn try_new_on_days(
+        name: String,
+        value: Money,
+        uncertainty: Option<Uncertainty>,
+        start: NaiveDate,
+        end: impl Into<Option<NaiveDate>>,
+        on_month_days: Vec<MonthDay>,
+        skip_months: u16,
+    ) -> Result<Self, MoolahCoreError> {
+        if on_month_days.is_empty() {
+            return Err(MoolahCoreError::NoMonthDays);
         }
 
-        if start > end {
+        if let Some(uncertainty) = &uncertainty {
+            uncertainty.validate(value)?;
+        }
+
+        let end = end.into();
+        if let Some(end) = end.filter(|end| start > *end) {
             return Err(MoolahCoreError::StartAfterEnd { start, end });
         }
 
@@ -89,11 +154,14 @@ impl MonthlyDelta {
             name,
             value,
             uncertainty,
+            currency: None,
             start,
             end,
-            on_month_day,
             skip_months,
-            dates: build_dates(&start, &end, &on_month_day, (skip_months + 1).into())?,
+            roll: RollConvention::None,
+            calendar: Calendar::default(),
+            escalation: None,
+            on_month_days,
         })
     }
 
@@ -101,17 +169,184 @@ impl MonthlyDelta {
         &self.start
     }
 
-    pub fn end(&self) -> &NaiveDate {
-        &self.end
+    pub fn end(&self) -> Option<&NaiveDate> {
+        self.end.as_ref()
     }
 
+    /// The first of the month days
     pub fn on_month_day(&self) -> &MonthDay {
-        &self.on_month_day
+        &self.on_month_days[0]
+    }
+
+    pub fn on_month_days(&self) -> &[MonthDay] {
+        &self.on_month_days
     }
 
     pub fn skip_months(&self) -> u16 {
         self.skip_months
     }
+
+    pub fn roll(&self) -> &RollConvention {
+        &self.roll
+    }
+
+    pub fn calendar(&self) -> &Calendar {
+        &self.calendar
+    }
+
+    /// Move dates falling on a weekend or one of `calendar`'s holidays to a business day
+    pub fn with_roll(mut self, roll: RollConvention, calendar: Calendar) -> Self {
+        self.roll = roll;
+        self.calendar = calendar;
+        self
+    }
+
+    pub fn escalation(&self) -> Option<&Escalation> {
+        self.escalation.as_ref()
+    }
+
+    pub fn with_escalation(mut self, escalation: Escalation) -> Result<Self, MoolahCoreError> {
+        escalation.validate()?;
+        self.escalation = Some(escalation);
+        Ok(self)
+    }
+
+    pub fn with_currency(mut self, currency: Currency) -> Self {
+        self.currency = Some(currency);
+        self
+    }
+
+    pub fn set_name(&mut self, name: String) {
+        self.name = name;
+    }
+
+    pub fn set_value(&mut self, value: Money) -> Result<(), MoolahCoreError> {
+        super::set_value(&mut self.value, &self.uncertainty, value)
+    }
+
+    pub fn set_uncertainty(
+        &mut self,
+        uncertainty: Option<Uncertainty>,
+    ) -> Result<(), MoolahCoreError> {
+        super::set_uncertainty(&mut self.uncertainty, [self.value], uncertainty)
+    }
+
+    pub fn set_currency(&mut self, currency: Option<Currency>) {
+        self.currency = currency;
+    }
+
+    pub fn set_start(&mut self, start: NaiveDate) -> Result<(), MoolahCoreError> {
+        validate_span(start, self.end)?;
+        self.start = start;
+        Ok(())
+    }
+
+    /// `None` to recur without an end
+    pub fn set_end(&mut self, end: Option<NaiveDate>) -> Result<(), MoolahCoreError> {
+        validate_span(self.start, end)?;
+        self.end = end;
+        Ok(())
+    }
+
+    pub fn set_on_month_days(
+        &mut self,
+        on_month_days: Vec<MonthDay>,
+    ) -> Result<(), MoolahCoreError> {
+        if on_month_days.is_empty() {
+            return Err(MoolahCoreError::NoMonthDays);
+        }
+        self.on_month_days = on_month_days;
+        Ok(())
+    }
+
+    pub fn set_skip_months(&mut self, skip_months: u16) {
+        self.skip_months = skip_months;
+    }
+}
+
+#[cfg(feature = "serde")]
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+pub(crate) struct RawMonthlyDelta {
+    name: String,
+    value: Money,
+    uncertainty: Option<Uncertainty>,
+    currency: Option<Currency>,
+    start: NaiveDate,
+    end: Option<NaiveDate>,
+    #[serde(default)]
+    on_month_day: RawMonthDays,
+    #[serde(default)]
+    skip_months: u16,
+    #[serde(default)]
+    roll: RollConvention,
+    #[serde(default)]
+    calendar: Calendar,
+    escalation: Option<Escalation>,
+}
+
+/// A single month day, or a list of them
+#[cfg(feature = "serde")]
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum RawMonthDays {
+    One(RawMonthDay),
+    Many(Vec<RawMonthDay>),
+}
+
+#[cfg(feature = "serde")]
+impl Default for RawMonthDays {
+    fn default() -> Self {
+        RawMonthDays::One(RawMonthDay::default())
+    }
+}
+
+#[cfg(feature = "serde")]
+impl TryFrom<RawMonthDays> for Vec<MonthDay> {
+    type Error = MoolahCoreError;
+
+    fn try_from(raw: RawMonthDays) -> Result<Self, Self::Error> {
+        match raw {
+            RawMonthDays::One(month_day) => Ok(vec![month_day.try_into()?]),
+            RawMonthDays::Many(month_days) => {
+                month_days.into_iter().map(MonthDay::try_from).collect()
+            }
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+fn serialize_month_days<S: serde::Serializer>(
+    month_days: &[MonthDay],
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    match month_days {
+        [month_day] => month_day.serialize(serializer),
+        month_days => month_days.serialize(serializer),
+    }
+}
+
+#[cfg(feature = "serde")]
+impl TryFrom<RawMonthlyDelta> for MonthlyDelta {
+    type Error = MoolahCoreError;
+
+    fn try_from(raw: RawMonthlyDelta) -> Result<Self, Self::Error> {
+        let mut delta = MonthlyDelta::try_new_on_days(
+            raw.name,
+            raw.value,
+            raw.uncertainty,
+            raw.start,
+            raw.end,
+            raw.on_month_day.try_into()?,
+            raw.skip_months,
+        )?
+        .with_roll(raw.roll, raw.calendar);
+        delta.currency = raw.currency;
+        match raw.escalation {
+            Some(escalation) => delta.with_escalation(escalation),
+            None => Ok(delta),
+        }
+    }
 }
 
 impl Delta for MonthlyDelta {
@@ -119,7 +354,7 @@ impl Delta for MonthlyDelta {
         &self.name
     }
 
-    fn value(&self) -> f64 {
+    fn value(&self) -> Money {
         self.value
     }
 
@@ -127,7 +362,37 @@ impl Delta for MonthlyDelta {
         &self.uncertainty
     }
 
-    fn dates(&self) -> &[NaiveDate] {
-        &self.dates
+    fn currency(&self) -> Option<&Currency> {
+        self.currency.as_ref()
+    }
+
+    fn dates(&self) -> Box<dyn Iterator<Item = NaiveDate> + '_> {
+        Box::new(build_dates(
+            self.start,
+            self.end,
+            &self.on_month_days,
+            (self.skip_months + 1).into(),
+            &self.roll,
+            &self.calendar,
+        ))
+    }
+
+    fn is_open_ended(&self) -> bool {
+        self.end.is_none()
+    }
+
+    fn escalation_factor(&self, date: &NaiveDate) -> f64 {
+        self.escalation
+            .as_ref()
+            .map_or(1.0, |escalation| escalation.factor(&self.start, date))
+    }
+
+    #[cfg(feature = "serde")]
+    fn tagged(&self) -> TaggedDeltaRef<'_> {
+        TaggedDeltaRef::Monthly(self)
+    }
+
+    fn edit(&mut self) -> Option<DeltaMut<'_>> {
+        Some(DeltaMut::Monthly(self))
     }
 }
@@ -1,28 +1,93 @@
-use crate::errors::MoolahCoreError;
-use chrono::{Datelike, Duration, NaiveDate};
-use std::ops::{Add, Mul};
+use crate::{calendar::nth_weekday_of_month, errors::MoolahCoreError};
+use chrono::{Datelike, Days, NaiveDate, Weekday};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::{
+    fmt,
+    ops::{Add, Mul},
+    str::FromStr,
+};
 
+/// A day of the month: a day such as 15, which is rounded down to the last day of shorter
+/// months, a day counted back from the end of the month such as -1 for the last day, or the nth
+/// weekday of the month such as the first Monday.
 #[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(try_from = "RawMonthDay", into = "RawMonthDay")
+)]
 pub struct MonthDay {
-    day: u8,
+    kind: MonthDayKind,
 }
 
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum MonthDayKind {
+    Day(u8),
+    FromEnd(u8),
+    NthWeekday(i8, Weekday),
+}
+
+const ORDINALS: [(&str, i8); 6] = [
+    ("first", 1),
+    ("second", 2),
+    ("third", 3),
+    ("fourth", 4),
+    ("fifth", 5),
+    ("last", -1),
+];
+
 impl MonthDay {
-    pub fn try_new<T: TryInto<u8> + Into<i64> + Clone>(day: T) -> Result<Self, MoolahCoreError> {
-        let value: u8 = day
-            .clone()
-            .try_into()
-            .map_err(|_| MoolahCoreError::MonthDayOutOfRange(day.into()))?;
-        if !(1..=31).contains(&value) {
-            return Err(MoolahCoreError::MonthDayOutOfRange(value.into()));
+    pub fn try_new<T: Into<i64>>(day: T) -> Result<Self, MoolahCoreError> {
+        let day: i64 = day.into();
+        let kind = match day {
+            1..=31 => MonthDayKind::Day(day as u8),
+            -31..=-1 => MonthDayKind::FromEnd(day.unsigned_abs() as u8),
+            _ => return Err(MoolahCoreError::MonthDayOutOfRange(day)),
+        };
+        Ok(MonthDay { kind })
+    }
+
+    /// The `nth` `weekday` of the month, counting back from the end of the month when negative
+    pub fn nth_weekday(nth: i8, weekday: Weekday) -> Result<Self, MoolahCoreError> {
+        if !(1..=5).contains(&nth.unsigned_abs()) {
+            return Err(MoolahCoreError::InvalidMonthDay(format!("{nth} {weekday}")));
+        }
+        Ok(MonthDay {
+            kind: MonthDayKind::NthWeekday(nth, weekday),
+        })
+    }
+
+    /// The day of the month, negative when counted back from the end of the month, or `None` for
+    /// an nth weekday
+    pub fn day(&self) -> Option<i64> {
+        match self.kind {
+            MonthDayKind::Day(day) => Some(day.into()),
+            MonthDayKind::FromEnd(day) => Some(-i64::from(day)),
+            MonthDayKind::NthWeekday(..) => None,
+        }
+    }
+
+    /// The date this falls on in a month, if the month has one
+    pub fn in_month(&self, year: i32, month: u32) -> Option<NaiveDate> {
+        match self.kind {
+            MonthDayKind::Day(day) => rounded_ymd(year, month, day.into()),
+            MonthDayKind::FromEnd(day) => {
+                let last = rounded_ymd(year, month, 31)?;
+                last.checked_sub_days(Days::new((u32::from(day) - 1).min(last.day() - 1).into()))
+            }
+            MonthDayKind::NthWeekday(nth, weekday) => {
+                nth_weekday_of_month(year, month, weekday, nth)
+            }
         }
-        Ok(MonthDay { day: value })
     }
 }
 
 impl Default for MonthDay {
     fn default() -> Self {
-        MonthDay { day: 1 }
+        MonthDay {
+            kind: MonthDayKind::Day(1),
+        }
     }
 }
 
@@ -50,15 +115,102 @@ impl TryFrom<i32> for MonthDay {
     }
 }
 
+impl TryFrom<i64> for MonthDay {
+    type Error = MoolahCoreError;
+
+    fn try_from(value: i64) -> Result<Self, Self::Error> {
+        MonthDay::try_new(value)
+    }
+}
+
+impl FromStr for MonthDay {
+    type Err = MoolahCoreError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || MoolahCoreError::InvalidMonthDay(s.into());
+        if let Ok(day) = s.trim().parse::<i64>() {
+            return MonthDay::try_new(day);
+        }
+
+        let (ordinal, weekday) = s
+            .trim()
+            .split_once(char::is_whitespace)
+            .ok_or_else(invalid)?;
+        let nth = ORDINALS
+            .iter()
+            .find(|(name, _)| name.eq_ignore_ascii_case(ordinal))
+            .map(|(_, nth)| *nth)
+            .or_else(|| ordinal.parse().ok())
+            .ok_or_else(invalid)?;
+        let weekday = weekday.trim().parse().map_err(|_| invalid())?;
+        MonthDay::nth_weekday(nth, weekday)
+    }
+}
+
+impl fmt::Display for MonthDay {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.kind {
+            MonthDayKind::NthWeekday(nth, weekday) => match ORDINALS.iter().find(|o| o.1 == nth) {
+                Some((ordinal, _)) => write!(f, "{ordinal} {weekday}"),
+                None => write!(f, "{nth} {weekday}"),
+            },
+            _ => write!(f, "{}", self.day().unwrap_or_default()),
+        }
+    }
+}
+
+/// A month day as written in a scenario: a number of days, or a rule such as `first Mon`
+#[cfg(feature = "serde")]
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(untagged)]
+pub(crate) enum RawMonthDay {
+    Day(i64),
+    Rule(String),
+}
+
+#[cfg(feature = "serde")]
+impl Default for RawMonthDay {
+    fn default() -> Self {
+        MonthDay::default().into()
+    }
+}
+
+#[cfg(feature = "serde")]
+impl TryFrom<RawMonthDay> for MonthDay {
+    type Error = MoolahCoreError;
+
+    fn try_from(raw: RawMonthDay) -> Result<Self, Self::Error> {
+        match raw {
+            RawMonthDay::Day(day) => MonthDay::try_new(day),
+            RawMonthDay::Rule(rule) => rule.parse(),
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl From<MonthDay> for RawMonthDay {
+    fn from(month_day: MonthDay) -> Self {
+        match month_day.day() {
+            Some(day) => RawMonthDay::Day(day),
+            None => RawMonthDay::Rule(month_day.to_string()),
+        }
+    }
+}
+
+/// The day of the month, or 0 for a day counted back from the end of the month or an nth
+/// weekday, which have no fixed day (see [`MonthDay::day`])
 impl From<MonthDay> for u32 {
     fn from(val: MonthDay) -> Self {
-        val.day.into()
+        u32::from(&val)
     }
 }
 
 impl From<&MonthDay> for u32 {
     fn from(val: &MonthDay) -> Self {
-        val.day.into()
+        match val.kind {
+            MonthDayKind::Day(day) => day.into(),
+            MonthDayKind::FromEnd(_) | MonthDayKind::NthWeekday(..) => 0,
+        }
     }
 }
 
@@ -80,7 +232,7 @@ impl Add<NaiveDate> for MultiMonthDuration {
     fn add(self, rhs: NaiveDate) -> Self::Output {
         let mut month = MonthAdded::Exact(rhs);
         for _ in 0..self.n_months {
-            month = add_month(&month, 0);
+            month = add_month(&month);
         }
         match month {
             MonthAdded::Exact(date) => date,
@@ -91,21 +243,21 @@ impl Add<NaiveDate> for MultiMonthDuration {
 
 enum MonthAdded {
     Exact(NaiveDate),
-    Rounded(NaiveDate, MonthDay),
+    Rounded(NaiveDate, u32),
 }
 
-const MAX_RECURSION: usize = 5;
-fn add_month(date: &MonthAdded, recursion_level: usize) -> MonthAdded {
-    if recursion_level >= MAX_RECURSION {
-        panic!("Reached max recursion level - this most likely means an impossible date was attempted to be created");
-    }
+/// `day` of the month, rounded down to the last day of months which are too short
+fn rounded_ymd(year: i32, month: u32, day: u32) -> Option<NaiveDate> {
+    (28..=day)
+        .rev()
+        .find_map(|day| NaiveDate::from_ymd_opt(year, month, day))
+        .or_else(|| NaiveDate::from_ymd_opt(year, month, day))
+}
 
+fn add_month(date: &MonthAdded) -> MonthAdded {
     let (date, target_day) = match date {
-        MonthAdded::Exact(date) => (
-            date,
-            date.day().try_into().expect("this should never throw"),
-        ),
-        MonthAdded::Rounded(date, month_day) => (date, *month_day),
+        MonthAdded::Exact(date) => (date, date.day()),
+        MonthAdded::Rounded(date, target_day) => (date, *target_day),
     };
 
     let month = match date.month() {
@@ -116,29 +268,18 @@ fn add_month(date: &MonthAdded, recursion_level: usize) -> MonthAdded {
         1 => date.year() + 1,
         _ => date.year(),
     };
-    let day = match recursion_level {
-        0 => target_day.into(),
-        _ => date.day(),
-    };
 
-    match NaiveDate::from_ymd_opt(year, month, day) {
-        Some(date) => {
-            if date.day() == target_day.into() {
-                MonthAdded::Exact(date)
-            } else {
-                MonthAdded::Rounded(date, target_day)
-            }
-        }
-        None => add_month(
-            &MonthAdded::Rounded(*date - Duration::days(1), target_day),
-            recursion_level + 1,
-        ),
+    match rounded_ymd(year, month, target_day) {
+        Some(date) if date.day() == target_day => MonthAdded::Exact(date),
+        Some(date) => MonthAdded::Rounded(date, target_day),
+        None => panic!("tried to add a month to {date}, which is past the latest possible date"),
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::date_helpers::ymd;
 
     #[test]
     fn test_mult_month_day() {
@@ -176,6 +317,97 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_month_day_in_month() {
+        let in_feb = |month_day: MonthDay| month_day.in_month(2024, 2);
+
+        assert_eq!(
+            in_feb(MonthDay::try_new(15).unwrap()),
+            Some(ymd(2024, 2, 15))
+        );
+        assert_eq!(
+            in_feb(MonthDay::try_new(31).unwrap()),
+            Some(ymd(2024, 2, 29))
+        );
+        assert_eq!(
+            in_feb(MonthDay::try_new(-1).unwrap()),
+            Some(ymd(2024, 2, 29))
+        );
+        assert_eq!(
+            in_feb(MonthDay::try_new(-3).unwrap()),
+            Some(ymd(2024, 2, 27))
+        );
+        assert_eq!(
+            in_feb(MonthDay::try_new(-31).unwrap()),
+            Some(ymd(2024, 2, 1))
+        );
+        assert_eq!(
+            in_feb(MonthDay::nth_weekday(1, Weekday::Mon).unwrap()),
+            Some(ymd(2024, 2, 5))
+        );
+        assert_eq!(
+            in_feb(MonthDay::nth_weekday(-1, Weekday::Thu).unwrap()),
+            Some(ymd(2024, 2, 29))
+        );
+        assert_eq!(
+            in_feb(MonthDay::nth_weekday(5, Weekday::Fri).unwrap()),
+            None
+        );
+    }
+
+    #[test]
+    fn test_month_day_out_of_range() {
+        for day in [0, 32, -32] {
+            assert!(matches!(
+                MonthDay::try_new(day),
+                Err(MoolahCoreError::MonthDayOutOfRange(d)) if d == day
+            ));
+        }
+        assert!(MonthDay::nth_weekday(0, Weekday::Mon).is_err());
+        assert!(MonthDay::nth_weekday(6, Weekday::Mon).is_err());
+        assert!(MonthDay::nth_weekday(-6, Weekday::Mon).is_err());
+    }
+
+    #[test]
+    fn test_parse_month_day() {
+        assert_eq!(
+            "15".parse::<MonthDay>().unwrap(),
+            MonthDay::try_new(15).unwrap()
+        );
+        assert_eq!(
+            "-1".parse::<MonthDay>().unwrap(),
+            MonthDay::try_new(-1).unwrap()
+        );
+        assert_eq!(
+            "first Monday".parse::<MonthDay>().unwrap(),
+            MonthDay::nth_weekday(1, Weekday::Mon).unwrap()
+        );
+        assert_eq!(
+            "Last fri".parse::<MonthDay>().unwrap(),
+            MonthDay::nth_weekday(-1, Weekday::Fri).unwrap()
+        );
+        assert_eq!(
+            "-2 Tue".parse::<MonthDay>().unwrap(),
+            MonthDay::nth_weekday(-2, Weekday::Tue).unwrap()
+        );
+        for invalid in ["", "first", "sixth Mon", "first Funday", "32"] {
+            assert!(
+                invalid.parse::<MonthDay>().is_err(),
+                "{invalid} should not parse"
+            );
+        }
+    }
+
+    #[test]
+    fn test_display_month_day() {
+        for month_day in ["15", "-1", "first Mon", "third Wed", "last Fri", "-2 Tue"] {
+            assert_eq!(
+                month_day.parse::<MonthDay>().unwrap().to_string(),
+                month_day
+            );
+        }
+    }
+
     #[test]
     #[should_panic]
     fn test_panic_add_month_over_max_recursion() {
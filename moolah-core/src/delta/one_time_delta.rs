@@ -1,13 +1,25 @@
-use super::{reasonable_bounds, Delta, Uncertainty};
-use crate::errors::MoolahCoreError;
+#[cfg(feature = "serde")]
+use super::TaggedDeltaRef;
+use super::{Delta, DeltaMut, Uncertainty};
+use crate::{currency::Currency, errors::MoolahCoreError, money::Money};
 use chrono::{Local, NaiveDate};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::iter;
 
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(try_from = "RawOneTimeDelta")
+)]
 pub struct OneTimeDelta {
     name: String,
-    value: f64,
+    value: Money,
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
     uncertainty: Option<Uncertainty>,
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    currency: Option<Currency>,
     date: NaiveDate,
-    dates: Vec<NaiveDate>,
 }
 
 impl Default for OneTimeDelta {
@@ -17,8 +29,8 @@ impl Default for OneTimeDelta {
             name: Default::default(),
             value: Default::default(),
             uncertainty: Default::default(),
+            currency: Default::default(),
             date: today,
-            dates: vec![today],
         }
     }
 }
@@ -26,26 +38,76 @@ impl Default for OneTimeDelta {
 impl OneTimeDelta {
     pub fn try_new(
         name: String,
-        value: f64,
+        value: Money,
         uncertainty: Option<Uncertainty>,
         date: NaiveDate,
     ) -> Result<Self, MoolahCoreError> {
-        if let Some(Uncertainty::Bounds { low, high }) = uncertainty {
-            reasonable_bounds(low, high, value)?;
+        if let Some(uncertainty) = &uncertainty {
+            uncertainty.validate(value)?;
         }
 
         Ok(OneTimeDelta {
             name,
             value,
             uncertainty,
+            currency: None,
             date,
-            dates: vec![date],
         })
     }
 
     pub fn date(&self) -> &NaiveDate {
         &self.date
     }
+
+    pub fn with_currency(mut self, currency: Currency) -> Self {
+        self.currency = Some(currency);
+        self
+    }
+
+    pub fn set_name(&mut self, name: String) {
+        self.name = name;
+    }
+
+    pub fn set_value(&mut self, value: Money) -> Result<(), MoolahCoreError> {
+        super::set_value(&mut self.value, &self.uncertainty, value)
+    }
+
+    pub fn set_uncertainty(
+        &mut self,
+        uncertainty: Option<Uncertainty>,
+    ) -> Result<(), MoolahCoreError> {
+        super::set_uncertainty(&mut self.uncertainty, [self.value], uncertainty)
+    }
+
+    pub fn set_currency(&mut self, currency: Option<Currency>) {
+        self.currency = currency;
+    }
+
+    pub fn set_date(&mut self, date: NaiveDate) {
+        self.date = date;
+    }
+}
+
+#[cfg(feature = "serde")]
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+pub(crate) struct RawOneTimeDelta {
+    name: String,
+    value: Money,
+    uncertainty: Option<Uncertainty>,
+    currency: Option<Currency>,
+    date: NaiveDate,
+}
+
+#[cfg(feature = "serde")]
+impl TryFrom<RawOneTimeDelta> for OneTimeDelta {
+    type Error = MoolahCoreError;
+
+    fn try_from(raw: RawOneTimeDelta) -> Result<Self, Self::Error> {
+        let mut delta = OneTimeDelta::try_new(raw.name, raw.value, raw.uncertainty, raw.date)?;
+        delta.currency = raw.currency;
+        Ok(delta)
+    }
 }
 
 impl Delta for OneTimeDelta {
@@ -53,7 +115,7 @@ impl Delta for OneTimeDelta {
         &self.name
     }
 
-    fn value(&self) -> f64 {
+    fn value(&self) -> Money {
         self.value
     }
 
@@ -61,7 +123,20 @@ impl Delta for OneTimeDelta {
         &self.uncertainty
     }
 
-    fn dates(&self) -> &[NaiveDate] {
-        &self.dates
+    fn currency(&self) -> Option<&Currency> {
+        self.currency.as_ref()
+    }
+
+    fn dates(&self) -> Box<dyn Iterator<Item = NaiveDate> + '_> {
+        Box::new(iter::once(self.date))
+    }
+
+    #[cfg(feature = "serde")]
+    fn tagged(&self) -> TaggedDeltaRef<'_> {
+        TaggedDeltaRef::OneTime(self)
+    }
+
+    fn edit(&mut self) -> Option<DeltaMut<'_>> {
+        Some(DeltaMut::OneTime(self))
     }
 }
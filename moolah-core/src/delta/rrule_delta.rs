@@ -0,0 +1,265 @@
+pub mod rrule;
+
+#[cfg(feature = "serde")]
+use super::TaggedDeltaRef;
+use super::{validate_span, Delta, DeltaMut, Escalation, Uncertainty};
+use crate::{currency::Currency, errors::MoolahCoreError, money::Money};
+use chrono::{Local, NaiveDate};
+pub use rrule::{ByDay, Frequency, RRule};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// A delta recurring by an RFC 5545 recurrence rule, such as `FREQ=MONTHLY;BYDAY=2FR,4FR` for
+/// the second & fourth Friday of each month.
+///
+/// The rule starts from `start`, and its dates are kept between `start` and `end` like those of
+/// the other recurring deltas. Any `exdates` are left out. Without an `end`, `UNTIL` or `COUNT`
+/// the dates go on indefinitely.
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(try_from = "RawRRuleDelta")
+)]
+pub struct RRuleDelta {
+    name: String,
+    value: Money,
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    uncertainty: Option<Uncertainty>,
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    currency: Option<Currency>,
+    start: NaiveDate,
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    end: Option<NaiveDate>,
+    #[cfg_attr(feature = "serde", serde(serialize_with = "serialize_rrule"))]
+    rrule: RRule,
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Vec::is_empty"))]
+    exdates: Vec<NaiveDate>,
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    escalation: Option<Escalation>,
+}
+
+impl Default for RRuleDelta {
+    fn default() -> Self {
+        let today = Local::now().date_naive();
+
+        RRuleDelta {
+            name: Default::default(),
+            value: Default::default(),
+            uncertainty: Default::default(),
+            currency: Default::default(),
+            start: today,
+            end: Some(today),
+            rrule: "FREQ=DAILY".parse().unwrap(),
+            exdates: Default::default(),
+            escalation: Default::default(),
+        }
+    }
+}
+
+impl RRuleDelta {
+    pub fn try_new(
+        name: String,
+        value: Money,
+        uncertainty: Option<Uncertainty>,
+        start: NaiveDate,
+        end: impl Into<Option<NaiveDate>>,
+        rrule: RRule,
+        exdates: Vec<NaiveDate>,
+    ) -> Result<Self, MoolahCoreError> {
+        if let Some(uncertainty) = &uncertainty {
+            uncertainty.validate(value)?;
+        }
+
+        let end = end.into();
+        if let Some(end) = end.filter(|end| start > *end) {
+            return Err(MoolahCoreError::StartAfterEnd { start, end });
+        }
+
+        Ok(RRuleDelta {
+            name,
+            value,
+            uncertainty,
+            currency: None,
+            start,
+            end,
+            rrule,
+            exdates,
+            escalation: None,
+        })
+    }
+
+    pub fn start(&self) -> &NaiveDate {
+        &self.start
+    }
+
+    pub fn end(&self) -> Option<&NaiveDate> {
+        self.end.as_ref()
+    }
+
+    pub fn rrule(&self) -> &RRule {
+        &self.rrule
+    }
+
+    pub fn exdates(&self) -> &[NaiveDate] {
+        &self.exdates
+    }
+
+    pub fn escalation(&self) -> Option<&Escalation> {
+        self.escalation.as_ref()
+    }
+
+    pub fn with_escalation(mut self, escalation: Escalation) -> Result<Self, MoolahCoreError> {
+        escalation.validate()?;
+        self.escalation = Some(escalation);
+        Ok(self)
+    }
+
+    pub fn with_currency(mut self, currency: Currency) -> Self {
+        self.currency = Some(currency);
+        self
+    }
+
+    pub fn set_name(&mut self, name: String) {
+        self.name = name;
+    }
+
+    pub fn set_value(&mut self, value: Money) -> Result<(), MoolahCoreError> {
+        super::set_value(&mut self.value, &self.uncertainty, value)
+    }
+
+    pub fn set_uncertainty(
+        &mut self,
+        uncertainty: Option<Uncertainty>,
+    ) -> Result<(), MoolahCoreError> {
+        super::set_uncertainty(&mut self.uncertainty, [self.value], uncertainty)
+    }
+
+    pub fn set_currency(&mut self, currency: Option<Currency>) {
+        self.currency = currency;
+    }
+
+    pub fn set_start(&mut self, start: NaiveDate) -> Result<(), MoolahCoreError> {
+        validate_span(start, self.end)?;
+        self.start = start;
+        Ok(())
+    }
+
+    /// `None` to recur without an end
+    pub fn set_end(&mut self, end: Option<NaiveDate>) -> Result<(), MoolahCoreError> {
+        validate_span(self.start, end)?;
+        self.end = end;
+        Ok(())
+    }
+
+    pub fn set_rrule(&mut self, rrule: RRule) {
+        self.rrule = rrule;
+    }
+
+    pub fn set_exdates(&mut self, exdates: Vec<NaiveDate>) {
+        self.exdates = exdates;
+    }
+}
+
+#[cfg(feature = "serde")]
+fn serialize_rrule<S: serde::Serializer>(rrule: &RRule, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.collect_str(rrule)
+}
+
+#[cfg(feature = "serde")]
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+pub(crate) struct RawRRuleDelta {
+    name: String,
+    value: Money,
+    uncertainty: Option<Uncertainty>,
+    currency: Option<Currency>,
+    start: NaiveDate,
+    end: Option<NaiveDate>,
+    rrule: String,
+    #[serde(default)]
+    exdates: Vec<NaiveDate>,
+    escalation: Option<Escalation>,
+}
+
+#[cfg(feature = "serde")]
+impl TryFrom<RawRRuleDelta> for RRuleDelta {
+    type Error = MoolahCoreError;
+
+    fn try_from(raw: RawRRuleDelta) -> Result<Self, Self::Error> {
+        let mut delta = RRuleDelta::try_new(
+            raw.name,
+            raw.value,
+            raw.uncertainty,
+            raw.start,
+            raw.end,
+            raw.rrule.parse()?,
+            raw.exdates,
+        )?;
+        delta.currency = raw.currency;
+        match raw.escalation {
+            Some(escalation) => delta.with_escalation(escalation),
+            None => Ok(delta),
+        }
+    }
+}
+
+impl Delta for RRuleDelta {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn value(&self) -> Money {
+        self.value
+    }
+
+    fn uncertainty(&self) -> &Option<Uncertainty> {
+        &self.uncertainty
+    }
+
+    fn currency(&self) -> Option<&Currency> {
+        self.currency.as_ref()
+    }
+
+    fn dates(&self) -> Box<dyn Iterator<Item = NaiveDate> + '_> {
+        Box::new(
+            self.rrule
+                .dates(self.start, self.end)
+                .filter(|date| !self.exdates.contains(date)),
+        )
+    }
+
+    // A rule may match no more dates at all, such as `FREQ=YEARLY;BYMONTH=2;BYMONTHDAY=30`, so
+    // the rule is only followed as far as the window's end
+    fn dates_between(
+        &self,
+        start: &NaiveDate,
+        end: &NaiveDate,
+    ) -> Box<dyn Iterator<Item = NaiveDate> + '_> {
+        let (start, last) = (*start, self.end.map_or(*end, |own_end| own_end.min(*end)));
+        Box::new(
+            self.rrule
+                .dates(self.start, Some(last))
+                .filter(|date| !self.exdates.contains(date))
+                .skip_while(move |date| *date < start),
+        )
+    }
+
+    fn is_open_ended(&self) -> bool {
+        self.end.is_none() & self.rrule.count().is_none() & self.rrule.until().is_none()
+    }
+
+    fn escalation_factor(&self, date: &NaiveDate) -> f64 {
+        self.escalation
+            .as_ref()
+            .map_or(1.0, |escalation| escalation.factor(&self.start, date))
+    }
+
+    #[cfg(feature = "serde")]
+    fn tagged(&self) -> TaggedDeltaRef<'_> {
+        TaggedDeltaRef::RRule(self)
+    }
+
+    fn edit(&mut self) -> Option<DeltaMut<'_>> {
+        Some(DeltaMut::RRule(self))
+    }
+}
@@ -0,0 +1,476 @@
+use crate::errors::MoolahCoreError;
+use chrono::{Datelike, Days, Months, NaiveDate, Weekday};
+use std::{fmt, ops::Neg, str::FromStr};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Frequency {
+    Daily,
+    Weekly,
+    Monthly,
+    Yearly,
+}
+
+impl Frequency {
+    fn as_str(&self) -> &'static str {
+        match self {
+            Frequency::Daily => "DAILY",
+            Frequency::Weekly => "WEEKLY",
+            Frequency::Monthly => "MONTHLY",
+            Frequency::Yearly => "YEARLY",
+        }
+    }
+}
+
+/// A `BYDAY` entry such as `FR`, `2FR` (the second Friday) or `-1FR` (the last Friday)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ByDay {
+    nth: Option<i8>,
+    weekday: Weekday,
+}
+
+impl ByDay {
+    pub fn nth(&self) -> Option<i8> {
+        self.nth
+    }
+
+    pub fn weekday(&self) -> Weekday {
+        self.weekday
+    }
+}
+
+fn weekday_code(weekday: Weekday) -> &'static str {
+    match weekday {
+        Weekday::Mon => "MO",
+        Weekday::Tue => "TU",
+        Weekday::Wed => "WE",
+        Weekday::Thu => "TH",
+        Weekday::Fri => "FR",
+        Weekday::Sat => "SA",
+        Weekday::Sun => "SU",
+    }
+}
+
+fn parse_weekday(code: &str) -> Option<Weekday> {
+    match code {
+        "MO" => Some(Weekday::Mon),
+        "TU" => Some(Weekday::Tue),
+        "WE" => Some(Weekday::Wed),
+        "TH" => Some(Weekday::Thu),
+        "FR" => Some(Weekday::Fri),
+        "SA" => Some(Weekday::Sat),
+        "SU" => Some(Weekday::Sun),
+        _ => None,
+    }
+}
+
+/// A recurrence rule from RFC 5545, for whole days. Supports `FREQ` of `DAILY`, `WEEKLY`,
+/// `MONTHLY` or `YEARLY` with `INTERVAL`, `COUNT`, `UNTIL`, `BYDAY`, `BYMONTHDAY`, `BYMONTH`,
+/// `BYSETPOS` and `WKST`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RRule {
+    frequency: Frequency,
+    interval: u32,
+    count: Option<u32>,
+    until: Option<NaiveDate>,
+    by_day: Vec<ByDay>,
+    by_month_day: Vec<i8>,
+    by_month: Vec<u32>,
+    by_set_pos: Vec<i16>,
+    week_start: Weekday,
+}
+
+fn parse_list<T>(values: &str, parse: impl Fn(&str) -> Option<T>) -> Option<Vec<T>> {
+    values.split(',').map(|value| parse(value.trim())).collect()
+}
+
+/// A non-zero number between `-max` and `max`, optionally with a leading `+`
+fn parse_in_range<T>(value: &str, max: T) -> Option<T>
+where
+    T: FromStr + PartialOrd + Default + Neg<Output = T> + Copy,
+{
+    let value: T = value.strip_prefix('+').unwrap_or(value).parse().ok()?;
+    (value != T::default() && -max <= value && value <= max).then_some(value)
+}
+
+fn parse_until(value: &str) -> Option<NaiveDate> {
+    // Only the date of a DATE-TIME matters
+    let date = value.get(..8)?;
+    NaiveDate::parse_from_str(date, "%Y%m%d").ok()
+}
+
+impl FromStr for RRule {
+    type Err = MoolahCoreError;
+
+    fn from_str(rule: &str) -> Result<Self, Self::Err> {
+        let invalid = |reason: String| MoolahCoreError::InvalidRRule {
+            rule: rule.into(),
+            reason,
+        };
+        let body = rule.trim();
+        let body = body.strip_prefix("RRULE:").unwrap_or(body);
+
+        let mut frequency = None;
+        let mut interval = 1;
+        let mut count = None;
+        let mut until = None;
+        let mut by_day = vec![];
+        let mut by_month_day = vec![];
+        let mut by_month = vec![];
+        let mut by_set_pos = vec![];
+        let mut week_start = Weekday::Mon;
+
+        for part in body.split(';').filter(|part| !part.is_empty()) {
+            let (key, value) = part
+                .split_once('=')
+                .ok_or_else(|| invalid(format!("`{part}` is not a KEY=VALUE pair")))?;
+            let bad_value = || invalid(format!("invalid {key} `{value}`"));
+
+            match key.trim().to_ascii_uppercase().as_str() {
+                "FREQ" => {
+                    frequency = Some(match value.trim() {
+                        "DAILY" => Frequency::Daily,
+                        "WEEKLY" => Frequency::Weekly,
+                        "MONTHLY" => Frequency::Monthly,
+                        "YEARLY" => Frequency::Yearly,
+                        _ => return Err(invalid(format!("unsupported FREQ `{value}`"))),
+                    })
+                }
+                "INTERVAL" => {
+                    interval = value
+                        .trim()
+                        .parse()
+                        .ok()
+                        .filter(|interval| *interval > 0)
+                        .ok_or_else(bad_value)?
+                }
+                "COUNT" => count = Some(value.trim().parse().map_err(|_| bad_value())?),
+                "UNTIL" => until = Some(parse_until(value.trim()).ok_or_else(bad_value)?),
+                "BYDAY" => {
+                    by_day = parse_list(value, |entry| {
+                        let split = entry.len().checked_sub(2)?;
+                        let weekday = parse_weekday(entry.get(split..)?)?;
+                        let nth = match entry.get(..split)? {
+                            "" => None,
+                            nth => Some(parse_in_range(nth, 53)?),
+                        };
+                        Some(ByDay { nth, weekday })
+                    })
+                    .ok_or_else(bad_value)?
+                }
+                "BYMONTHDAY" => {
+                    by_month_day =
+                        parse_list(value, |day| parse_in_range(day, 31)).ok_or_else(bad_value)?
+                }
+                "BYMONTH" => {
+                    by_month = parse_list(value, |month| {
+                        month.parse().ok().filter(|month| (1..=12).contains(month))
+                    })
+                    .ok_or_else(bad_value)?
+                }
+                "BYSETPOS" => {
+                    by_set_pos =
+                        parse_list(value, |pos| parse_in_range(pos, 366)).ok_or_else(bad_value)?
+                }
+                "WKST" => week_start = parse_weekday(value.trim()).ok_or_else(bad_value)?,
+                other => return Err(invalid(format!("unsupported rule part `{other}`"))),
+            }
+        }
+
+        let frequency = frequency.ok_or_else(|| invalid("missing FREQ".into()))?;
+        if count.is_some() & until.is_some() {
+            return Err(invalid("COUNT and UNTIL cannot both be set".into()));
+        }
+        if by_day.iter().any(|day| day.nth.is_some())
+            & !matches!(frequency, Frequency::Monthly | Frequency::Yearly)
+        {
+            return Err(invalid(
+                "numbered BYDAY is only allowed with MONTHLY or YEARLY".into(),
+            ));
+        }
+        if !by_month_day.is_empty() & (frequency == Frequency::Weekly) {
+            return Err(invalid("BYMONTHDAY is not allowed with WEEKLY".into()));
+        }
+        if !by_set_pos.is_empty()
+            & by_day.is_empty()
+            & by_month_day.is_empty()
+            & by_month.is_empty()
+        {
+            return Err(invalid("BYSETPOS needs another BYxxx rule part".into()));
+        }
+
+        Ok(RRule {
+            frequency,
+            interval,
+            count,
+            until,
+            by_day,
+            by_month_day,
+            by_month,
+            by_set_pos,
+            week_start,
+        })
+    }
+}
+
+impl fmt::Display for RRule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fn join<T>(values: &[T], format: impl Fn(&T) -> String) -> String {
+            values.iter().map(format).collect::<Vec<_>>().join(",")
+        }
+
+        write!(f, "FREQ={}", self.frequency.as_str())?;
+        if self.interval != 1 {
+            write!(f, ";INTERVAL={}", self.interval)?;
+        }
+        if let Some(count) = self.count {
+            write!(f, ";COUNT={count}")?;
+        }
+        if let Some(until) = self.until {
+            write!(f, ";UNTIL={}", until.format("%Y%m%d"))?;
+        }
+        if !self.by_month.is_empty() {
+            write!(f, ";BYMONTH={}", join(&self.by_month, u32::to_string))?;
+        }
+        if !self.by_month_day.is_empty() {
+            write!(f, ";BYMONTHDAY={}", join(&self.by_month_day, i8::to_string))?;
+        }
+        if !self.by_day.is_empty() {
+            let by_day = join(&self.by_day, |day| match day.nth {
+                Some(nth) => format!("{nth}{}", weekday_code(day.weekday)),
+                None => weekday_code(day.weekday).into(),
+            });
+            write!(f, ";BYDAY={by_day}")?;
+        }
+        if !self.by_set_pos.is_empty() {
+            write!(f, ";BYSETPOS={}", join(&self.by_set_pos, i16::to_string))?;
+        }
+        if self.week_start != Weekday::Mon {
+            write!(f, ";WKST={}", weekday_code(self.week_start))?;
+        }
+        Ok(())
+    }
+}
+
+fn days_in_month(year: i32, month: u32) -> u32 {
+    let first = NaiveDate::from_ymd_opt(year, month, 1).unwrap();
+    let next = first + Months::new(1);
+    (next - first).num_days() as u32
+}
+
+/// The `nth` (negative from the end) entry of `items`, if there is one
+fn nth_item<T: Copy>(items: &[T], nth: i16) -> Option<T> {
+    if nth > 0 {
+        items.get(nth as usize - 1).copied()
+    } else {
+        items
+            .len()
+            .checked_sub(nth.unsigned_abs() as usize)
+            .map(|index| items[index])
+    }
+}
+
+impl RRule {
+    pub fn frequency(&self) -> Frequency {
+        self.frequency
+    }
+
+    pub fn interval(&self) -> u32 {
+        self.interval
+    }
+
+    pub fn count(&self) -> Option<u32> {
+        self.count
+    }
+
+    pub fn until(&self) -> Option<&NaiveDate> {
+        self.until.as_ref()
+    }
+
+    pub fn by_day(&self) -> &[ByDay] {
+        &self.by_day
+    }
+
+    pub fn by_month_day(&self) -> &[i8] {
+        &self.by_month_day
+    }
+
+    pub fn by_month(&self) -> &[u32] {
+        &self.by_month
+    }
+
+    pub fn by_set_pos(&self) -> &[i16] {
+        &self.by_set_pos
+    }
+
+    pub fn week_start(&self) -> Weekday {
+        self.week_start
+    }
+
+    /// Whether `date` falls on one of `BYDAY`'s weekdays, with any `nth` counted within `days`
+    fn matches_by_day(&self, date: &NaiveDate, days: &[NaiveDate]) -> bool {
+        self.by_day.iter().any(|by_day| match by_day.nth {
+            _ if by_day.weekday != date.weekday() => false,
+            None => true,
+            Some(nth) => {
+                let weekdays: Vec<_> = days
+                    .iter()
+                    .filter(|day| day.weekday() == by_day.weekday)
+                    .copied()
+                    .collect();
+                nth_item(&weekdays, nth.into()) == Some(*date)
+            }
+        })
+    }
+
+    fn matches_by_month_day(&self, date: &NaiveDate) -> bool {
+        let length = days_in_month(date.year(), date.month()) as i64;
+        self.by_month_day.iter().any(|day| {
+            let day = i64::from(*day);
+            let day = if day < 0 { length + 1 + day } else { day };
+            day == i64::from(date.day())
+        })
+    }
+
+    /// The candidate days of a month, or of a whole year for yearly rules without `BYMONTH`
+    fn expand_days(&self, days: Vec<NaiveDate>, start: &NaiveDate) -> Vec<NaiveDate> {
+        let all_days = days.clone();
+        days.into_iter()
+            .filter(
+                |day| match (self.by_day.is_empty(), self.by_month_day.is_empty()) {
+                    (true, true) => day.day() == start.day(),
+                    (true, false) => self.matches_by_month_day(day),
+                    (false, true) => self.matches_by_day(day, &all_days),
+                    (false, false) => {
+                        self.matches_by_month_day(day) & self.matches_by_day(day, &all_days)
+                    }
+                },
+            )
+            .collect()
+    }
+
+    fn month_days(year: i32, month: u32) -> Vec<NaiveDate> {
+        (1..=days_in_month(year, month))
+            .filter_map(|day| NaiveDate::from_ymd_opt(year, month, day))
+            .collect()
+    }
+
+    /// The first day of the `period`th period after the one containing `start`
+    fn period_start(&self, start: &NaiveDate, period: u32) -> Option<NaiveDate> {
+        let steps = period.checked_mul(self.interval)?;
+        match self.frequency {
+            Frequency::Daily => start.checked_add_days(Days::new(steps.into())),
+            Frequency::Weekly => {
+                let offset = start.weekday().days_since(self.week_start);
+                start
+                    .checked_sub_days(Days::new(offset.into()))?
+                    .checked_add_days(Days::new(u64::from(steps) * 7))
+            }
+            Frequency::Monthly => start.with_day(1)?.checked_add_months(Months::new(steps)),
+            Frequency::Yearly => start
+                .with_day(1)?
+                .with_month(1)?
+                .checked_add_months(Months::new(steps.checked_mul(12)?)),
+        }
+    }
+
+    /// Every day the rule allows within the period starting on `period_start`, before `BYSETPOS`
+    fn period_days(&self, start: &NaiveDate, period_start: NaiveDate) -> Vec<NaiveDate> {
+        let (year, month) = (period_start.year(), period_start.month());
+        let mut days = match self.frequency {
+            Frequency::Daily => {
+                let matches = (self.by_day.is_empty() || self.matches_by_day(&period_start, &[]))
+                    & (self.by_month_day.is_empty() || self.matches_by_month_day(&period_start));
+                if matches {
+                    vec![period_start]
+                } else {
+                    vec![]
+                }
+            }
+            Frequency::Weekly => period_start
+                .iter_days()
+                .take(7)
+                .filter(|day| {
+                    if self.by_day.is_empty() {
+                        day.weekday() == start.weekday()
+                    } else {
+                        self.matches_by_day(day, &[])
+                    }
+                })
+                .collect(),
+            Frequency::Monthly => self.expand_days(Self::month_days(year, month), start),
+            Frequency::Yearly => {
+                if !self.by_month.is_empty() {
+                    let mut months = self.by_month.clone();
+                    months.sort();
+                    months
+                        .into_iter()
+                        .flat_map(|month| self.expand_days(Self::month_days(year, month), start))
+                        .collect()
+                } else if self.by_month_day.is_empty() & !self.by_day.is_empty() {
+                    // Numbered weekdays count through the whole year
+                    let year_days = (1..=12).flat_map(|month| Self::month_days(year, month));
+                    self.expand_days(year_days.collect(), start)
+                } else if self.by_month_day.is_empty() {
+                    self.expand_days(Self::month_days(year, start.month()), start)
+                } else {
+                    (1..=12)
+                        .flat_map(|month| self.expand_days(Self::month_days(year, month), start))
+                        .collect()
+                }
+            }
+        };
+
+        if !self.by_month.is_empty() {
+            days.retain(|day| self.by_month.contains(&day.month()));
+        }
+        if !self.by_set_pos.is_empty() {
+            let mut positioned: Vec<_> = self
+                .by_set_pos
+                .iter()
+                .filter_map(|pos| nth_item(&days, *pos))
+                .collect();
+            positioned.sort();
+            positioned.dedup();
+            days = positioned;
+        }
+        days
+    }
+
+    /// The rule's dates from `start` until `end`, `UNTIL` or `COUNT` occurrences, whichever
+    /// comes first. Without any of them, the dates go on for as long as the rule still matches.
+    ///
+    /// The calendar repeats every 400 years, so a rule which matches no date in 400 years' worth
+    /// of its intervals never matches again, and the dates stop there.
+    pub fn dates(
+        &self,
+        start: NaiveDate,
+        end: Option<NaiveDate>,
+    ) -> impl Iterator<Item = NaiveDate> + '_ {
+        let last = match (self.until, end) {
+            (Some(until), Some(end)) => Some(until.min(end)),
+            (until, end) => until.or(end),
+        };
+        let until_last = move |date: &NaiveDate| last.is_none_or(|last| *date <= last);
+        let horizon = self.interval.checked_mul(400 * 12).map(Months::new);
+
+        (0..)
+            .map_while(move |period| self.period_start(&start, period))
+            .take_while(until_last)
+            .scan(start, move |last_match, period_start| {
+                let within_horizon = horizon.is_none_or(|horizon| {
+                    last_match
+                        .checked_add_months(horizon)
+                        .is_none_or(|limit| period_start <= limit)
+                });
+                let days = self.period_days(&start, period_start);
+                if let Some(day) = days.last() {
+                    *last_match = (*last_match).max(*day);
+                }
+                within_horizon.then_some(days)
+            })
+            .flatten()
+            .filter(move |day| *day >= start)
+            .take_while(until_last)
+            .take(self.count.map_or(usize::MAX, |count| count as usize))
+    }
+}
@@ -0,0 +1,109 @@
+use super::{
+    custom_delta::RawCustomDelta, daily_delta::RawDailyDelta, interest_delta::RawInterestDelta,
+    loan_delta::RawLoanDelta, monthly_delta::RawMonthlyDelta, one_time_delta::RawOneTimeDelta,
+    rrule_delta::RawRRuleDelta, weekly_delta::RawWeeklyDelta, yearly_delta::RawYearlyDelta,
+};
+use super::{
+    CustomDelta, DailyDelta, Delta, DeltaId, InterestDelta, LoanDelta, MonthlyDelta, OneTimeDelta,
+    RRuleDelta, WeeklyDelta, YearlyDelta,
+};
+use crate::errors::MoolahCoreError;
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum TaggedDeltaRef<'a> {
+    OneTime(&'a OneTimeDelta),
+    Daily(&'a DailyDelta),
+    Weekly(&'a WeeklyDelta),
+    Monthly(&'a MonthlyDelta),
+    Yearly(&'a YearlyDelta),
+    Custom(&'a CustomDelta),
+    Interest(&'a InterestDelta),
+    Loan(&'a LoanDelta),
+    #[serde(rename = "rrule")]
+    RRule(&'a RRuleDelta),
+}
+
+#[derive(Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum TaggedDelta {
+    OneTime(OneTimeDelta),
+    Daily(DailyDelta),
+    Weekly(WeeklyDelta),
+    Monthly(MonthlyDelta),
+    Yearly(YearlyDelta),
+    Custom(CustomDelta),
+    Interest(InterestDelta),
+    Loan(LoanDelta),
+    #[serde(rename = "rrule")]
+    RRule(RRuleDelta),
+}
+
+impl From<TaggedDelta> for Box<dyn Delta> {
+    fn from(value: TaggedDelta) -> Self {
+        match value {
+            TaggedDelta::OneTime(delta) => Box::new(delta),
+            TaggedDelta::Daily(delta) => Box::new(delta),
+            TaggedDelta::Weekly(delta) => Box::new(delta),
+            TaggedDelta::Monthly(delta) => Box::new(delta),
+            TaggedDelta::Yearly(delta) => Box::new(delta),
+            TaggedDelta::Custom(delta) => Box::new(delta),
+            TaggedDelta::Interest(delta) => Box::new(delta),
+            TaggedDelta::Loan(delta) => Box::new(delta),
+            TaggedDelta::RRule(delta) => Box::new(delta),
+        }
+    }
+}
+
+/// Deserialized delta fields that have not yet been validated by the delta's `try_new`
+#[derive(Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub(crate) enum UncheckedDelta {
+    OneTime(RawOneTimeDelta),
+    Daily(RawDailyDelta),
+    Weekly(RawWeeklyDelta),
+    Monthly(RawMonthlyDelta),
+    Yearly(RawYearlyDelta),
+    Custom(RawCustomDelta),
+    Interest(RawInterestDelta),
+    Loan(RawLoanDelta),
+    #[serde(rename = "rrule")]
+    RRule(RawRRuleDelta),
+}
+
+impl TryFrom<UncheckedDelta> for Box<dyn Delta> {
+    type Error = MoolahCoreError;
+
+    fn try_from(value: UncheckedDelta) -> Result<Self, Self::Error> {
+        Ok(match value {
+            UncheckedDelta::OneTime(raw) => Box::new(OneTimeDelta::try_from(raw)?),
+            UncheckedDelta::Daily(raw) => Box::new(DailyDelta::try_from(raw)?),
+            UncheckedDelta::Weekly(raw) => Box::new(WeeklyDelta::try_from(raw)?),
+            UncheckedDelta::Monthly(raw) => Box::new(MonthlyDelta::try_from(raw)?),
+            UncheckedDelta::Yearly(raw) => Box::new(YearlyDelta::try_from(raw)?),
+            UncheckedDelta::Custom(raw) => Box::new(CustomDelta::try_from(raw)?),
+            UncheckedDelta::Interest(raw) => Box::new(InterestDelta::try_from(raw)?),
+            UncheckedDelta::Loan(raw) => Box::new(LoanDelta::try_from(raw)?),
+            UncheckedDelta::RRule(raw) => Box::new(RRuleDelta::try_from(raw)?),
+        })
+    }
+}
+
+/// A delta and the id it has in its prediction, which may be left out where it can be derived
+/// from the delta's name
+#[derive(Serialize)]
+pub(crate) struct IdentifiedDeltaRef<'a> {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<&'a DeltaId>,
+    #[serde(flatten)]
+    pub delta: TaggedDeltaRef<'a>,
+}
+
+#[derive(Deserialize)]
+pub(crate) struct UncheckedIdentifiedDelta {
+    #[serde(default)]
+    pub id: Option<DeltaId>,
+    #[serde(flatten)]
+    pub delta: UncheckedDelta,
+}
@@ -1,9 +1,17 @@
 use super::*;
 
 mod custom_delta;
+#[allow(clippy::useless_vec)]
 mod daily_delta;
+mod distribution;
+mod escalation;
+mod interest_delta;
+mod loan_delta;
 mod monthly_delta;
 mod one_time_delta;
+mod rrule_delta;
+#[cfg(feature = "serde")]
+mod serialization;
 mod weekly_delta;
 mod yearly_delta;
 
@@ -1,11 +1,173 @@
 use super::*;
+use crate::date_helpers::ymd;
 
 #[test]
 fn test_default() {
     let d = CustomDelta::default();
 
     assert_eq!(d.name(), "");
-    assert_eq!(d.value(), 0.0);
+    assert_eq!(d.value(), Money::from(0));
+    assert!(d.uncertainty().is_none());
+    assert!(d.dates().next().is_none());
+}
+
+#[test]
+fn test_occurrences() {
+    let d = CustomDelta::try_new(
+        String::from("test"),
+        Money::from(-50),
+        Some(Uncertainty::Balanced(UncertaintyType::Dollars(
+            Money::from(5).try_into().unwrap(),
+        ))),
+        vec![ymd(2023, 1, 1), ymd(2023, 2, 1)],
+    )
+    .unwrap();
+
+    assert_eq!(
+        d.occurrences(&ymd(2023, 1, 1), &ymd(2023, 2, 1))
+            .collect::<Vec<_>>(),
+        [
+            Occurrence {
+                date: ymd(2023, 1, 1),
+                value: Money::from(-50),
+                min: Money::from(-55),
+                max: Money::from(-45),
+            },
+            Occurrence {
+                date: ymd(2023, 2, 1),
+                value: Money::from(-50),
+                min: Money::from(-55),
+                max: Money::from(-45),
+            },
+        ]
+    );
+}
+
+#[test]
+fn test_unsorted_dates() {
+    let d = CustomDelta::try_new(
+        String::from("test"),
+        Money::from(100),
+        None,
+        vec![ymd(2022, 3, 1), ymd(2022, 1, 1)],
+    )
+    .unwrap();
+
+    assert_eq!(
+        d.dates_between(&ymd(2022, 1, 1), &ymd(2022, 2, 1))
+            .collect::<Vec<_>>(),
+        [ymd(2022, 1, 1)]
+    );
+}
+
+#[test]
+fn test_out_of_order_amounts() {
+    let d = CustomDelta::try_new(
+        String::from("test"),
+        Money::from(100),
+        None,
+        vec![ymd(2022, 1, 1), ymd(2022, 3, 1)],
+    )
+    .unwrap()
+    .with_amounts([(ymd(2022, 2, 1), Money::from(250))])
+    .unwrap();
+
+    assert_eq!(
+        d.occurrences(&ymd(2022, 1, 1), &ymd(2022, 2, 15))
+            .map(|occurrence| (occurrence.date, occurrence.value))
+            .collect::<Vec<_>>(),
+        [
+            (ymd(2022, 1, 1), Money::from(100)),
+            (ymd(2022, 2, 1), Money::from(250))
+        ]
+    );
+}
+
+#[test]
+fn test_payment_plan() {
+    let d = CustomDelta::try_new(
+        String::from("dentist"),
+        Money::from(-100),
+        Some(Uncertainty::Balanced(UncertaintyType::Percent(
+            10.0.try_into().unwrap(),
+        ))),
+        vec![ymd(2023, 1, 15)],
+    )
+    .unwrap()
+    .with_amounts([
+        (ymd(2023, 2, 15), Money::from(-250)),
+        (ymd(2023, 3, 15), Money::from(-400)),
+        (ymd(2023, 1, 15), Money::from(-300)),
+    ])
+    .unwrap();
+
+    assert_eq!(
+        d.dates().collect::<Vec<_>>(),
+        [ymd(2023, 1, 15), ymd(2023, 2, 15), ymd(2023, 3, 15)]
+    );
+    assert_eq!(
+        d.occurrences(&ymd(2023, 1, 1), &ymd(2023, 12, 31))
+            .map(|occurrence| (occurrence.value, occurrence.min, occurrence.max))
+            .collect::<Vec<_>>(),
+        [
+            (Money::from(-300), Money::from(-330), Money::from(-270)),
+            (Money::from(-250), Money::from(-275), Money::from(-225)),
+            (Money::from(-400), Money::from(-440), Money::from(-360)),
+        ]
+    );
+    assert_eq!(d.value(), Money::from(-100));
+    assert!(d.value_dates().is_empty());
+}
+
+#[test]
+fn test_amounts_are_validated() {
+    let d = CustomDelta::try_new(
+        String::from("test"),
+        Money::from(-100),
+        Some(Uncertainty::Bounds {
+            low: Money::from(-150),
+            high: Money::from(-50),
+        }),
+        vec![],
+    )
+    .unwrap();
+
+    assert!(matches!(
+        d.with_amounts([(ymd(2023, 1, 1), Money::from(-200))]),
+        Err(MoolahCoreError::IllogicalUncertaintyBounds { .. })
+    ));
+}
+
+#[test]
+fn test_setters() {
+    let mut d = CustomDelta::try_new(
+        String::from("dentist"),
+        Money::from(-100),
+        None,
+        vec![ymd(2023, 1, 15)],
+    )
+    .unwrap()
+    .with_amounts([(ymd(2023, 2, 15), Money::from(-300))])
+    .unwrap();
+
+    // Dates with their own amount keep it
+    d.set_dates(vec![ymd(2023, 3, 15), ymd(2023, 2, 15)]);
+    assert_eq!(d.value_dates(), [ymd(2023, 3, 15)]);
+    assert_eq!(d.amounts_on(&ymd(2023, 2, 15)).0, Money::from(-300));
+    assert_eq!(d.dates().count(), 2);
+
+    d.set_amounts([(ymd(2023, 4, 15), Money::from(-50))])
+        .unwrap();
+    assert_eq!(
+        d.dates().collect::<Vec<_>>(),
+        [ymd(2023, 3, 15), ymd(2023, 4, 15)]
+    );
+
+    // Bounds must hold every amount, not just the value
+    let bounds = Uncertainty::Bounds {
+        low: Money::from(-120),
+        high: Money::from(-80),
+    };
+    assert!(d.set_uncertainty(Some(bounds)).is_err());
     assert!(d.uncertainty().is_none());
-    assert!(d.dates().is_empty());
 }
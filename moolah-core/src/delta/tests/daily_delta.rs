@@ -8,21 +8,27 @@ fn test_default() {
     let today = Local::now().date_naive();
 
     assert_eq!(d.name(), "");
-    assert_eq!(d.value(), 0.0);
+    assert_eq!(d.value(), Money::from(0));
     assert!(d.uncertainty().is_none());
-    assert_eq!(d.dates(), &[today]);
+    assert_eq!(d.dates().collect::<Vec<_>>(), &[today]);
     assert_eq!(*d.start(), today);
-    assert_eq!(*d.end(), today);
+    assert_eq!(d.end(), Some(&today));
     assert_eq!(d.skip_days(), 0);
 }
 
 #[test]
 fn test_start_cannot_be_later_than_end() {
     let d = NaiveDate::from_ymd_opt(2022, 11, 1).unwrap();
-    assert!(DailyDelta::try_new(String::from("test"), 0.0, None, d, d, 0).is_ok());
-    assert!(
-        DailyDelta::try_new(String::from("test"), 0.0, None, d, d - Duration::days(1), 0).is_err()
-    );
+    assert!(DailyDelta::try_new(String::from("test"), Money::from(0), None, d, d, 0).is_ok());
+    assert!(DailyDelta::try_new(
+        String::from("test"),
+        Money::from(0),
+        None,
+        d,
+        d - Duration::days(1),
+        0
+    )
+    .is_err());
 }
 
 #[test]
@@ -31,10 +37,10 @@ fn test_reasonable_bounds() {
 
     assert!(DailyDelta::try_new(
         String::from("test"),
-        0.0,
+        Money::from(0),
         Some(Uncertainty::Bounds {
-            low: 0.0,
-            high: 0.0
+            low: Money::from(0),
+            high: Money::from(0)
         }),
         date,
         date,
@@ -44,10 +50,10 @@ fn test_reasonable_bounds() {
 
     assert!(DailyDelta::try_new(
         String::from("test"),
-        0.0,
+        Money::from(0),
         Some(Uncertainty::Bounds {
-            low: 0.0,
-            high: 0.1
+            low: Money::from(0),
+            high: Money::from_cents(10)
         }),
         date,
         date,
@@ -57,10 +63,10 @@ fn test_reasonable_bounds() {
 
     assert!(DailyDelta::try_new(
         String::from("test"),
-        0.0,
+        Money::from(0),
         Some(Uncertainty::Bounds {
-            low: -0.1,
-            high: 0.0
+            low: Money::from_cents(-10),
+            high: Money::from(0)
         }),
         date,
         date,
@@ -70,10 +76,10 @@ fn test_reasonable_bounds() {
 
     assert!(DailyDelta::try_new(
         String::from("test"),
-        0.0,
+        Money::from(0),
         Some(Uncertainty::Bounds {
-            low: -0.1,
-            high: 0.1
+            low: Money::from_cents(-10),
+            high: Money::from_cents(10)
         }),
         date,
         date,
@@ -83,10 +89,10 @@ fn test_reasonable_bounds() {
 
     assert!(DailyDelta::try_new(
         String::from("test"),
-        -1.0,
+        Money::from(-1),
         Some(Uncertainty::Bounds {
-            low: -0.1,
-            high: 0.1
+            low: Money::from_cents(-10),
+            high: Money::from_cents(10)
         }),
         date,
         date,
@@ -96,10 +102,10 @@ fn test_reasonable_bounds() {
 
     assert!(DailyDelta::try_new(
         String::from("test"),
-        1.0,
+        Money::from(1),
         Some(Uncertainty::Bounds {
-            low: -0.1,
-            high: 0.1
+            low: Money::from_cents(-10),
+            high: Money::from_cents(10)
         }),
         date,
         date,
@@ -109,10 +115,10 @@ fn test_reasonable_bounds() {
 
     assert!(DailyDelta::try_new(
         String::from("test"),
-        0.0,
+        Money::from(0),
         Some(Uncertainty::Bounds {
-            low: 0.1,
-            high: -0.1
+            low: Money::from_cents(10),
+            high: Money::from_cents(-10)
         }),
         date,
         date,
@@ -125,7 +131,7 @@ fn test_reasonable_bounds() {
 fn skip_days_0() {
     let d = DailyDelta::try_new(
         String::from("test"),
-        1000.0,
+        Money::from(1000),
         None,
         NaiveDate::from_ymd_opt(2022, 10, 1).unwrap(),
         NaiveDate::from_ymd_opt(2022, 10, 5).unwrap(),
@@ -141,7 +147,7 @@ fn skip_days_0() {
         NaiveDate::from_ymd_opt(2022, 10, 5).unwrap(),
     ];
 
-    let dates = d.dates();
+    let dates = d.dates().collect::<Vec<_>>();
     for date in expected_dates.iter() {
         assert!(dates.contains(date), "date = {}", date);
     }
@@ -152,7 +158,7 @@ fn skip_days_0() {
 fn skip_days_1() {
     let d = DailyDelta::try_new(
         String::from("test"),
-        1000.0,
+        Money::from(1000),
         None,
         NaiveDate::from_ymd_opt(2022, 10, 1).unwrap(),
         NaiveDate::from_ymd_opt(2022, 10, 5).unwrap(),
@@ -166,7 +172,7 @@ fn skip_days_1() {
         NaiveDate::from_ymd_opt(2022, 10, 5).unwrap(),
     ];
 
-    let dates = d.dates();
+    let dates = d.dates().collect::<Vec<_>>();
     for date in expected_dates.iter() {
         assert!(dates.contains(date), "date = {}", date);
     }
@@ -177,7 +183,7 @@ fn skip_days_1() {
 fn skip_days_2() {
     let d = DailyDelta::try_new(
         String::from("test"),
-        1000.0,
+        Money::from(1000),
         None,
         NaiveDate::from_ymd_opt(2022, 10, 1).unwrap(),
         NaiveDate::from_ymd_opt(2022, 10, 5).unwrap(),
@@ -190,7 +196,7 @@ fn skip_days_2() {
         NaiveDate::from_ymd_opt(2022, 10, 4).unwrap(),
     ];
 
-    let dates = d.dates();
+    let dates = d.dates().collect::<Vec<_>>();
     for date in expected_dates.iter() {
         assert!(dates.contains(date), "date = {}", date);
     }
@@ -201,7 +207,7 @@ fn skip_days_2() {
 fn skip_days_3() {
     let d = DailyDelta::try_new(
         String::from("test"),
-        1000.0,
+        Money::from(1000),
         None,
         NaiveDate::from_ymd_opt(2022, 10, 1).unwrap(),
         NaiveDate::from_ymd_opt(2022, 10, 5).unwrap(),
@@ -214,7 +220,7 @@ fn skip_days_3() {
         NaiveDate::from_ymd_opt(2022, 10, 5).unwrap(),
     ];
 
-    let dates = d.dates();
+    let dates = d.dates().collect::<Vec<_>>();
     for date in expected_dates.iter() {
         assert!(dates.contains(date), "date = {}", date);
     }
@@ -225,7 +231,7 @@ fn skip_days_3() {
 fn skip_days_4() {
     let d = DailyDelta::try_new(
         String::from("test"),
-        1000.0,
+        Money::from(1000),
         None,
         NaiveDate::from_ymd_opt(2022, 10, 1).unwrap(),
         NaiveDate::from_ymd_opt(2022, 10, 5).unwrap(),
@@ -235,7 +241,7 @@ fn skip_days_4() {
 
     let expected_dates = vec![NaiveDate::from_ymd_opt(2022, 10, 1).unwrap()];
 
-    let dates = d.dates();
+    let dates = d.dates().collect::<Vec<_>>();
     for date in expected_dates.iter() {
         assert!(dates.contains(date), "date = {}", date);
     }
@@ -246,7 +252,7 @@ fn skip_days_4() {
 fn skip_days_100() {
     let d = DailyDelta::try_new(
         String::from("test"),
-        1000.0,
+        Money::from(1000),
         None,
         NaiveDate::from_ymd_opt(2022, 10, 1).unwrap(),
         NaiveDate::from_ymd_opt(2022, 10, 5).unwrap(),
@@ -256,9 +262,63 @@ fn skip_days_100() {
 
     let expected_dates = vec![NaiveDate::from_ymd_opt(2022, 10, 1).unwrap()];
 
-    let dates = d.dates();
+    let dates = d.dates().collect::<Vec<_>>();
     for date in expected_dates.iter() {
         assert!(dates.contains(date), "date = {}", date);
     }
     assert_eq!(dates.len(), expected_dates.len());
 }
+
+#[test]
+fn test_open_ended() {
+    let start = NaiveDate::from_ymd_opt(2023, 1, 1).unwrap();
+    let d =
+        DailyDelta::try_new(String::from("test"), Money::from(1), None, start, None, 1).unwrap();
+
+    assert!(d.end().is_none());
+    assert!(d.is_open_ended());
+    assert_eq!(
+        d.dates().take(3).collect::<Vec<_>>(),
+        [start, start + Duration::days(2), start + Duration::days(4)]
+    );
+    assert_eq!(
+        d.dates_between(
+            &NaiveDate::from_ymd_opt(2030, 1, 1).unwrap(),
+            &NaiveDate::from_ymd_opt(2030, 1, 5).unwrap()
+        )
+        .collect::<Vec<_>>(),
+        [
+            NaiveDate::from_ymd_opt(2030, 1, 2).unwrap(),
+            NaiveDate::from_ymd_opt(2030, 1, 4).unwrap(),
+        ]
+    );
+}
+
+#[test]
+fn test_setters() {
+    let start = Local::now().date_naive();
+    let mut d = DailyDelta::try_new(
+        "coffee".into(),
+        Money::from(-5),
+        None,
+        start,
+        start + Duration::days(9),
+        0,
+    )
+    .unwrap();
+
+    assert!(matches!(
+        d.set_start(start + Duration::days(10)),
+        Err(MoolahCoreError::StartAfterEnd { .. })
+    ));
+    assert_eq!(*d.start(), start);
+
+    d.set_end(None).unwrap();
+    d.set_start(start + Duration::days(10)).unwrap();
+    d.set_skip_days(6);
+    assert!(d.is_open_ended());
+    assert_eq!(
+        d.dates().take(2).collect::<Vec<_>>(),
+        [start + Duration::days(10), start + Duration::days(17)]
+    );
+}
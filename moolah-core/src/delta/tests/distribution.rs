@@ -0,0 +1,269 @@
+use super::*;
+use chrono::NaiveDate;
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+
+fn bill(value: i32, uncertainty: Uncertainty) -> Result<OneTimeDelta, MoolahCoreError> {
+    OneTimeDelta::try_new(
+        "bill".into(),
+        Money::from(value),
+        Some(uncertainty),
+        NaiveDate::from_ymd_opt(2023, 1, 1).unwrap(),
+    )
+}
+
+fn bands(d: &dyn Delta) -> (Money, Money) {
+    (d.min_uncertainty_value(), d.max_uncertainty_value())
+}
+
+#[test]
+fn test_quantiles() {
+    assert_eq!(
+        Quantiles::default(),
+        Quantiles::try_new(0.05, 0.95).unwrap()
+    );
+    for (low, high) in [(0.0, 0.9), (0.1, 1.0), (0.9, 0.1), (f64::NAN, 0.5)] {
+        assert!(matches!(
+            Quantiles::try_new(low, high),
+            Err(MoolahCoreError::InvalidQuantiles { .. })
+        ));
+    }
+}
+
+#[test]
+fn test_validation() {
+    assert!(matches!(
+        bill(
+            -100,
+            Uncertainty::Triangular {
+                low: Money::from(-90),
+                high: Money::from(-50),
+                quantiles: Default::default()
+            }
+        ),
+        Err(MoolahCoreError::IllogicalUncertaintyBounds { .. })
+    ));
+    assert!(matches!(
+        bill(
+            -100,
+            Uncertainty::Uniform {
+                low: Money::from(-50),
+                high: Money::from(-150),
+                quantiles: Default::default()
+            }
+        ),
+        Err(MoolahCoreError::IllogicalUncertaintyBounds { .. })
+    ));
+    assert!(matches!(
+        bill(
+            -100,
+            Uncertainty::Empirical {
+                amounts: vec![],
+                quantiles: Default::default()
+            }
+        ),
+        Err(MoolahCoreError::EmptyDistribution)
+    ));
+    assert!(matches!(
+        bill(
+            -100,
+            Uncertainty::Empirical {
+                amounts: vec![Money::from(-90), Money::from(-80)],
+                quantiles: Default::default()
+            }
+        ),
+        Err(MoolahCoreError::IllogicalUncertaintyBounds { .. })
+    ));
+    assert!(matches!(
+        bill(
+            -100,
+            Uncertainty::LogNormal {
+                sigma: f64::INFINITY.try_into().unwrap(),
+                quantiles: Default::default()
+            }
+        ),
+        Err(MoolahCoreError::InvalidSigma(_))
+    ));
+}
+
+#[test]
+fn test_normal_band() {
+    let d = bill(
+        100,
+        Uncertainty::Normal {
+            std_dev: 10.0.try_into().unwrap(),
+            quantiles: Default::default(),
+        },
+    )
+    .unwrap();
+    assert_eq!(
+        bands(&d),
+        (Money::from_cents(8355), Money::from_cents(11645))
+    );
+}
+
+#[test]
+fn test_quantile_ends() {
+    let normal = Uncertainty::Normal {
+        std_dev: 10.0.try_into().unwrap(),
+        quantiles: Default::default(),
+    };
+    let lognormal = Uncertainty::LogNormal {
+        sigma: 0.2.try_into().unwrap(),
+        quantiles: Default::default(),
+    };
+    for unbounded in [&normal, &lognormal] {
+        assert_eq!(unbounded.quantile(Money::from(100), 0.0), None);
+        assert_eq!(unbounded.quantile(Money::from(100), 1.0), None);
+    }
+    assert!(normal.quantile(Money::from(100), 0.001).unwrap() < Money::from(70));
+
+    // Bounded distributions end at their bounds
+    let uniform = Uncertainty::Uniform {
+        low: Money::from(-200),
+        high: Money::from(-100),
+        quantiles: Default::default(),
+    };
+    assert_eq!(
+        uniform.quantile(Money::from(-150), 0.0),
+        Some(Money::from(-200))
+    );
+    assert_eq!(
+        uniform.quantile(Money::from(-150), 1.0),
+        Some(Money::from(-100))
+    );
+}
+
+#[test]
+fn test_lognormal_band() {
+    let sigma = 0.2.try_into().unwrap();
+    let income = bill(
+        100,
+        Uncertainty::LogNormal {
+            sigma,
+            quantiles: Default::default(),
+        },
+    )
+    .unwrap();
+    assert_eq!(
+        bands(&income),
+        (Money::from_cents(7197), Money::from_cents(13895))
+    );
+
+    // The long tail of an expense is towards larger outflows
+    let expense = bill(
+        -100,
+        Uncertainty::LogNormal {
+            sigma,
+            quantiles: Default::default(),
+        },
+    )
+    .unwrap();
+    assert_eq!(
+        bands(&expense),
+        (Money::from_cents(-13895), Money::from_cents(-7197))
+    );
+}
+
+#[test]
+fn test_triangular_band() {
+    let d = bill(
+        50,
+        Uncertainty::Triangular {
+            low: Money::from(0),
+            high: Money::from(100),
+            quantiles: Default::default(),
+        },
+    )
+    .unwrap();
+    assert_eq!(
+        bands(&d),
+        (Money::from_cents(1581), Money::from_cents(8419))
+    );
+}
+
+#[test]
+fn test_uniform_band() {
+    let d = bill(
+        -150,
+        Uncertainty::Uniform {
+            low: Money::from(-200),
+            high: Money::from(-100),
+            quantiles: Quantiles::try_new(0.1, 0.9).unwrap(),
+        },
+    )
+    .unwrap();
+    assert_eq!(bands(&d), (Money::from(-190), Money::from(-110)));
+}
+
+#[test]
+fn test_empirical_band() {
+    let d = bill(
+        -100,
+        Uncertainty::Empirical {
+            amounts: [-120, -95, -80, -140, -100]
+                .into_iter()
+                .map(Money::from)
+                .collect(),
+            quantiles: Quantiles::try_new(0.25, 0.75).unwrap(),
+        },
+    )
+    .unwrap();
+    assert_eq!(bands(&d), (Money::from(-120), Money::from(-95)));
+    assert_eq!(
+        d.uncertainty().as_ref().unwrap().quantile(d.value(), 0.5),
+        Some(Money::from(-100))
+    );
+    assert_eq!(
+        d.uncertainty().as_ref().unwrap().quantile(d.value(), 1.5),
+        None
+    );
+}
+
+#[test]
+fn test_sampling() {
+    let date = NaiveDate::from_ymd_opt(2023, 1, 1).unwrap();
+    let mut rng = ChaCha8Rng::seed_from_u64(0);
+
+    let amounts: Vec<Money> = [-120, -95, -80].into_iter().map(Money::from).collect();
+    let empirical = bill(
+        -95,
+        Uncertainty::Empirical {
+            amounts: amounts.clone(),
+            quantiles: Default::default(),
+        },
+    )
+    .unwrap();
+    let uniform = bill(
+        -150,
+        Uncertainty::Uniform {
+            low: Money::from(-200),
+            high: Money::from(-100),
+            quantiles: Default::default(),
+        },
+    )
+    .unwrap();
+    for _ in 0..100 {
+        assert!(amounts.contains(&empirical.sample_on(&date, &mut rng)));
+        let amount = uniform.sample_on(&date, &mut rng);
+        assert!((Money::from(-200) <= amount) & (amount <= Money::from(-100)));
+    }
+
+    let normal = bill(
+        100,
+        Uncertainty::Normal {
+            std_dev: 10.0.try_into().unwrap(),
+            quantiles: Default::default(),
+        },
+    )
+    .unwrap();
+    let samples: Vec<Money> = (0..1000)
+        .map(|_| normal.sample_on(&date, &mut rng))
+        .collect();
+    let mean = samples.iter().sum::<Money>().to_f64() / 1000.0;
+    assert!((mean - 100.0).abs() < 1.0);
+    // A normal distribution is unbounded, unlike the deterministic band
+    assert!(samples
+        .iter()
+        .any(|amount| *amount > Money::from_cents(11645)));
+}
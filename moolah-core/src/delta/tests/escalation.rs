@@ -0,0 +1,150 @@
+use super::*;
+use crate::date_helpers::ymd;
+use chrono::Weekday;
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+
+fn rent(uncertainty: Option<Uncertainty>) -> MonthlyDelta {
+    MonthlyDelta::try_new(
+        "rent".into(),
+        Money::from(-1000),
+        uncertainty,
+        ymd(2023, 3, 1),
+        ymd(2026, 12, 31),
+        1.try_into().unwrap(),
+        0,
+    )
+    .unwrap()
+}
+
+#[test]
+fn test_annual() {
+    let escalation = Escalation::Annual(3.0);
+    let start = ymd(2023, 3, 15);
+
+    assert_eq!(escalation.factor(&start, &ymd(2023, 1, 1)), 1.0);
+    assert_eq!(escalation.factor(&start, &ymd(2024, 3, 14)), 1.0);
+    assert_eq!(escalation.factor(&start, &ymd(2024, 3, 15)), 1.03);
+    assert!((escalation.factor(&start, &ymd(2026, 12, 31)) - 1.03f64.powi(3)).abs() < 1e-12);
+}
+
+#[test]
+fn test_steps() {
+    let escalation = Escalation::Steps(vec![
+        EscalationStep {
+            date: ymd(2024, 6, 1),
+            percent: 5.0,
+        },
+        EscalationStep {
+            date: ymd(2024, 1, 1),
+            percent: -10.0,
+        },
+    ]);
+    let start = ymd(2023, 1, 1);
+
+    assert_eq!(escalation.factor(&start, &ymd(2023, 12, 31)), 1.0);
+    assert_eq!(escalation.factor(&start, &ymd(2024, 1, 1)), 0.9);
+    assert!((escalation.factor(&start, &ymd(2024, 6, 1)) - 0.945).abs() < 1e-12);
+}
+
+#[test]
+fn test_index() {
+    let escalation = Escalation::Index(vec![
+        IndexLevel {
+            date: ymd(2023, 1, 1),
+            level: 300.0,
+        },
+        IndexLevel {
+            date: ymd(2023, 7, 1),
+            level: 306.0,
+        },
+        IndexLevel {
+            date: ymd(2024, 1, 1),
+            level: 312.0,
+        },
+    ]);
+
+    // Relative to the level in force when the delta starts
+    let start = ymd(2023, 2, 1);
+    assert_eq!(escalation.factor(&start, &ymd(2023, 6, 30)), 1.0);
+    assert_eq!(escalation.factor(&start, &ymd(2023, 7, 1)), 1.02);
+    assert_eq!(escalation.factor(&start, &ymd(2030, 1, 1)), 1.04);
+
+    // Before the index begins, its first level is used
+    assert_eq!(escalation.factor(&ymd(2022, 1, 1), &ymd(2023, 7, 1)), 1.02);
+}
+
+#[test]
+fn test_invalid() {
+    for escalation in [
+        Escalation::Annual(f64::NAN),
+        Escalation::Annual(-100.0),
+        Escalation::Steps(vec![EscalationStep {
+            date: ymd(2023, 1, 1),
+            percent: f64::INFINITY,
+        }]),
+        Escalation::Index(vec![]),
+        Escalation::Index(vec![IndexLevel {
+            date: ymd(2023, 1, 1),
+            level: 0.0,
+        }]),
+    ] {
+        assert!(matches!(
+            rent(None).with_escalation(escalation),
+            Err(MoolahCoreError::InvalidEscalation(_))
+        ));
+    }
+}
+
+#[test]
+fn test_escalated_amounts() {
+    let d = rent(Some(Uncertainty::Balanced(UncertaintyType::Percent(
+        10.0.try_into().unwrap(),
+    ))))
+    .with_escalation(Escalation::Annual(3.0))
+    .unwrap();
+
+    // The value itself is unchanged, only each occurrence escalates
+    assert_eq!(d.value(), Money::from(-1000));
+    assert_eq!(
+        d.amounts_on(&ymd(2024, 2, 1)),
+        (Money::from(-1000), Money::from(-1100), Money::from(-900))
+    );
+    assert_eq!(
+        d.amounts_on(&ymd(2024, 3, 1)),
+        (Money::from(-1030), Money::from(-1133), Money::from(-927))
+    );
+    assert_eq!(
+        d.amounts_on(&ymd(2025, 3, 1)),
+        (
+            Money::from_cents(-106090),
+            Money::from_cents(-116699),
+            Money::from_cents(-95481)
+        )
+    );
+}
+
+#[test]
+fn test_escalated_samples() {
+    let escalated = WeeklyDelta::try_new(
+        "groceries".into(),
+        Money::from(-150),
+        Some(Uncertainty::Normal {
+            std_dev: Money::from(20).try_into().unwrap(),
+            quantiles: Quantiles::default(),
+        }),
+        ymd(2023, 1, 1),
+        ymd(2024, 12, 31),
+        Some(Weekday::Sat),
+        0,
+    )
+    .unwrap()
+    .with_escalation(Escalation::Annual(10.0))
+    .unwrap();
+
+    let mut rng = ChaCha8Rng::seed_from_u64(7);
+    let sample = escalated.sample_on(&ymd(2024, 6, 1), &mut rng);
+    let mut rng = ChaCha8Rng::seed_from_u64(7);
+    let unescalated = escalated.sample_on(&ymd(2023, 6, 3), &mut rng);
+    assert_eq!(sample, unescalated.scale(1.1));
+}
@@ -0,0 +1,172 @@
+use super::*;
+use crate::date_helpers::naive_ymd;
+use crate::prediction::PredictionState;
+
+#[test]
+fn test_default() {
+    let d = InterestDelta::default();
+
+    assert_eq!(d.name(), "");
+    assert_eq!(d.value(), Money::from(0));
+    assert!(d.uncertainty().is_none());
+    assert!(d.currency().is_none());
+    assert!(d.dates().next().is_none());
+    assert_eq!(*d.compounding(), Compounding::Monthly);
+    assert!(d.is_balance_dependent());
+}
+
+#[test]
+fn test_compounding_dates() {
+    let start = naive_ymd(2023, 1, 31).unwrap();
+    let end = naive_ymd(2023, 4, 30).unwrap();
+
+    let monthly =
+        InterestDelta::try_new("i".into(), 5.0, None, Compounding::Monthly, start, end).unwrap();
+    assert_eq!(
+        monthly.dates().collect::<Vec<_>>(),
+        &[
+            naive_ymd(2023, 2, 28).unwrap(),
+            naive_ymd(2023, 3, 31).unwrap(),
+            naive_ymd(2023, 4, 30).unwrap()
+        ]
+    );
+
+    let daily =
+        InterestDelta::try_new("i".into(), 5.0, None, Compounding::Daily, start, end).unwrap();
+    assert_eq!(daily.dates().count(), 89);
+    assert_eq!(
+        daily.dates().next().unwrap(),
+        naive_ymd(2023, 2, 1).unwrap()
+    );
+
+    let yearly = InterestDelta::try_new(
+        "i".into(),
+        5.0,
+        None,
+        Compounding::Yearly,
+        naive_ymd(2020, 2, 29).unwrap(),
+        naive_ymd(2024, 3, 1).unwrap(),
+    )
+    .unwrap();
+    assert_eq!(
+        yearly.dates().collect::<Vec<_>>(),
+        &[
+            naive_ymd(2021, 2, 28).unwrap(),
+            naive_ymd(2022, 2, 28).unwrap(),
+            naive_ymd(2023, 2, 28).unwrap(),
+            naive_ymd(2024, 2, 29).unwrap()
+        ]
+    );
+}
+
+#[test]
+fn test_open_ended() {
+    let start = naive_ymd(2023, 1, 31).unwrap();
+    let d =
+        InterestDelta::try_new("i".into(), 5.0, None, Compounding::Yearly, start, None).unwrap();
+
+    assert!(d.is_open_ended());
+    assert_eq!(
+        d.dates_between(&start, &naive_ymd(2033, 1, 31).unwrap())
+            .count(),
+        10
+    );
+}
+
+#[test]
+fn test_invalid() {
+    let date = naive_ymd(2023, 1, 1).unwrap();
+
+    assert!(matches!(
+        InterestDelta::try_new("i".into(), f64::NAN, None, Compounding::Monthly, date, date),
+        Err(MoolahCoreError::InvalidInterestRate(_))
+    ));
+    assert!(matches!(
+        InterestDelta::try_new(
+            "i".into(),
+            5.0,
+            Some(RateUncertainty::Bounds {
+                low: 5.5,
+                high: 6.0
+            }),
+            Compounding::Monthly,
+            date,
+            date
+        ),
+        Err(MoolahCoreError::IllogicalRateBounds { .. })
+    ));
+    assert!(matches!(
+        InterestDelta::try_new(
+            "i".into(),
+            5.0,
+            None,
+            Compounding::Monthly,
+            date,
+            date - chrono::Duration::days(1)
+        ),
+        Err(MoolahCoreError::StartAfterEnd { .. })
+    ));
+}
+
+#[test]
+fn test_evaluate() {
+    let date = naive_ymd(2023, 1, 1).unwrap();
+    let state = PredictionState::new(
+        Money::from(1000),
+        Money::from(-100),
+        Money::from(2000),
+        [].into(),
+    );
+
+    let fixed =
+        InterestDelta::try_new("i".into(), 6.0, None, Compounding::Monthly, date, date).unwrap();
+    assert_eq!(
+        fixed.evaluate(&state),
+        (Money::from(5), Money::from_cents(-50), Money::from(10))
+    );
+
+    let uncertain = InterestDelta::try_new(
+        "i".into(),
+        6.0,
+        Some(RateUncertainty::Balanced(2.4.try_into().unwrap())),
+        Compounding::Yearly,
+        date,
+        date,
+    )
+    .unwrap();
+    assert_eq!(uncertain.rate_range(), (3.6, 8.4));
+    // A negative balance is hurt most by the highest rate
+    assert_eq!(
+        uncertain.evaluate(&state),
+        (Money::from(60), Money::from_cents(-840), Money::from(168))
+    );
+}
+
+#[test]
+fn test_setters() {
+    let start = naive_ymd(2023, 1, 31).unwrap();
+    let end = naive_ymd(2023, 4, 30).unwrap();
+    let mut d =
+        InterestDelta::try_new("i".into(), 5.0, None, Compounding::Monthly, start, end).unwrap();
+
+    d.set_compounding(Compounding::Daily).unwrap();
+    assert_eq!(d.dates().count(), 89);
+    d.set_start(naive_ymd(2023, 3, 31).unwrap()).unwrap();
+    assert_eq!(d.dates().count(), 30);
+
+    assert!(matches!(
+        d.set_end(naive_ymd(2023, 1, 1).ok()),
+        Err(MoolahCoreError::StartAfterEnd { .. })
+    ));
+    assert!(matches!(
+        d.set_rate_uncertainty(Some(RateUncertainty::Bounds {
+            low: 6.0,
+            high: 7.0
+        })),
+        Err(MoolahCoreError::IllogicalRateBounds { .. })
+    ));
+    assert_eq!((d.end(), d.rate_range()), (Some(&end), (5.0, 5.0)));
+
+    d.set_rate(6.5).unwrap();
+    assert_eq!(d.rate(), 6.5);
+}
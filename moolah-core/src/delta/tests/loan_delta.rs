@@ -0,0 +1,200 @@
+use super::*;
+use crate::date_helpers::naive_ymd;
+
+fn mortgage(extra_payments: Vec<ExtraPayment>) -> LoanDelta {
+    LoanDelta::try_new(
+        "mortgage".into(),
+        Money::from(300_000),
+        6.0,
+        360,
+        naive_ymd(2023, 1, 15).unwrap(),
+        1.try_into().unwrap(),
+        extra_payments,
+    )
+    .unwrap()
+}
+
+#[test]
+fn test_default() {
+    let d = LoanDelta::default();
+
+    assert_eq!(d.name(), "");
+    assert_eq!(d.value(), Money::from(0));
+    assert!(d.uncertainty().is_none());
+    assert!(d.dates().next().is_none());
+    assert!(d.schedule().is_empty());
+}
+
+#[test]
+fn test_invalid() {
+    let start = naive_ymd(2023, 1, 15).unwrap();
+    let new = |principal, rate, term, extra| {
+        LoanDelta::try_new(
+            "loan".into(),
+            principal,
+            rate,
+            term,
+            start,
+            1.try_into().unwrap(),
+            extra,
+        )
+    };
+
+    assert!(matches!(
+        new(Money::from(-1), 5.0, 12, vec![]),
+        Err(MoolahCoreError::UnexpectedNegativeMoney(_))
+    ));
+    assert!(matches!(
+        new(Money::from(1000), f64::INFINITY, 12, vec![]),
+        Err(MoolahCoreError::InvalidInterestRate(_))
+    ));
+    assert!(matches!(
+        new(Money::from(1000), 5.0, 0, vec![]),
+        Err(MoolahCoreError::InvalidLoanTerm(0))
+    ));
+    assert!(matches!(
+        new(
+            Money::from(1000),
+            5.0,
+            12,
+            vec![ExtraPayment::OneTime {
+                amount: Money::from(-5),
+                date: start
+            }]
+        ),
+        Err(MoolahCoreError::UnexpectedNegativeMoney(_))
+    ));
+}
+
+#[test]
+fn test_mortgage_schedule() {
+    let d = mortgage(vec![]);
+
+    assert_eq!(d.payment(), Money::from_cents(179865));
+    assert_eq!(d.value(), Money::from_cents(-179865));
+    assert_eq!(d.dates().count(), 360);
+    assert_eq!(d.dates().next().unwrap(), naive_ymd(2023, 2, 1).unwrap());
+    assert_eq!(d.dates().nth(359).unwrap(), naive_ymd(2053, 1, 1).unwrap());
+
+    let first = &d.schedule()[0];
+    assert_eq!(first.interest(), Money::from(1500));
+    assert_eq!(first.principal(), Money::from_cents(29865));
+    assert_eq!(first.balance(), Money::from_cents(29_970_135));
+
+    let last = d.schedule().last().unwrap();
+    assert_eq!(last.balance(), Money::ZERO);
+    // Rounding the regular payment to the cent leaves the final one a little larger
+    assert_eq!(last.amount(), Money::from_cents(180009));
+
+    let principal: Money = d.schedule().iter().map(|p| p.principal()).sum();
+    assert_eq!(principal, Money::from(300_000));
+}
+
+#[test]
+fn test_zero_rate() {
+    let d = LoanDelta::try_new(
+        "car".into(),
+        Money::from(20_000),
+        0.0,
+        48,
+        naive_ymd(2023, 12, 20).unwrap(),
+        5.try_into().unwrap(),
+        vec![],
+    )
+    .unwrap();
+
+    assert_eq!(d.payment(), Money::from_cents(41667));
+    assert_eq!(d.dates().next().unwrap(), naive_ymd(2024, 1, 5).unwrap());
+    assert_eq!(d.schedule().len(), 48);
+    assert_eq!(d.schedule()[47].amount(), Money::from_cents(41651));
+    assert!(d.schedule().iter().all(|p| p.interest() == Money::ZERO));
+}
+
+#[test]
+fn test_extra_payments_shorten_schedule() {
+    let recurring = mortgage(vec![ExtraPayment::Recurring {
+        amount: Money::from(200),
+        from: naive_ymd(2023, 1, 1).unwrap(),
+    }]);
+    assert_eq!(recurring.schedule().len(), 279);
+    assert_eq!(
+        recurring.schedule()[0].principal(),
+        Money::from_cents(49865)
+    );
+    assert_eq!(recurring.schedule().last().unwrap().balance(), Money::ZERO);
+
+    let lump_sum = mortgage(vec![ExtraPayment::OneTime {
+        amount: Money::from(100_000),
+        date: naive_ymd(2023, 6, 10).unwrap(),
+    }]);
+    let july = &lump_sum.schedule()[5];
+    assert_eq!(*july.date(), naive_ymd(2023, 7, 1).unwrap());
+    assert_eq!(july.amount(), Money::from(100_000) + lump_sum.payment());
+    assert!(lump_sum.schedule().len() < 360);
+    assert_eq!(
+        lump_sum.amounts_on(&naive_ymd(2023, 7, 1).unwrap()).0,
+        -july.amount()
+    );
+    assert_eq!(
+        lump_sum.amounts_on(&naive_ymd(2023, 8, 1).unwrap()).0,
+        lump_sum.value()
+    );
+}
+
+#[test]
+fn test_paid_off_early() {
+    let d = LoanDelta::try_new(
+        "loan".into(),
+        Money::from(1000),
+        12.0,
+        12,
+        naive_ymd(2023, 1, 1).unwrap(),
+        1.try_into().unwrap(),
+        vec![ExtraPayment::OneTime {
+            amount: Money::from(5000),
+            date: naive_ymd(2023, 3, 1).unwrap(),
+        }],
+    )
+    .unwrap();
+
+    assert_eq!(d.dates().count(), 2);
+    let last = &d.schedule()[1];
+    assert_eq!(last.balance(), Money::ZERO);
+    assert_eq!(last.principal(), d.schedule()[0].balance());
+}
+
+#[test]
+fn test_remaining_balance() {
+    let d = mortgage(vec![]);
+    let balance = d.remaining_balance();
+
+    assert_eq!(balance.len(), 361);
+    assert_eq!(
+        balance[&naive_ymd(2023, 1, 15).unwrap()],
+        Money::from(300_000)
+    );
+    assert_eq!(
+        balance[&naive_ymd(2023, 2, 1).unwrap()],
+        Money::from_cents(29_970_135)
+    );
+    assert_eq!(*balance.values().last().unwrap(), Money::ZERO);
+}
+
+#[test]
+fn test_setters() {
+    let usd = Currency::try_new("USD").unwrap();
+    let mut d = mortgage(vec![]).with_currency(usd);
+
+    d.set_term_months(180).unwrap();
+    assert_eq!(d.schedule().len(), 180);
+    assert_eq!(d.dates().count(), 180);
+    d.set_principal(Money::from(150_000)).unwrap();
+    assert_eq!(d.schedule().last().unwrap().balance(), Money::ZERO);
+
+    assert!(matches!(
+        d.set_principal(Money::from(-1)),
+        Err(MoolahCoreError::UnexpectedNegativeMoney(_))
+    ));
+    assert_eq!(d.principal(), Money::from(150_000));
+    assert_eq!(d.currency(), Some(&usd));
+}
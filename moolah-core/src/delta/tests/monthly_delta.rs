@@ -1,5 +1,6 @@
 use super::*;
-use chrono::{Duration, Local};
+use crate::calendar::{Calendar, RollConvention};
+use chrono::{Datelike, Duration, Local, Weekday};
 
 #[test]
 fn test_default() {
@@ -7,11 +8,13 @@ fn test_default() {
     let today = Local::now().date_naive();
 
     assert_eq!(d.name(), "");
-    assert_eq!(d.value(), 0.0);
+    assert_eq!(d.value(), Money::from(0));
     assert!(d.uncertainty().is_none());
-    assert_eq!(d.dates(), &[today]);
+    // The first of the month only falls between today and today on the first
+    let dates: Vec<_> = (today.day() == 1).then_some(today).into_iter().collect();
+    assert_eq!(d.dates().collect::<Vec<_>>(), dates);
     assert_eq!(*d.start(), today);
-    assert_eq!(*d.end(), today);
+    assert_eq!(d.end(), Some(&today));
     assert_eq!(*d.on_month_day(), 1u8.try_into().unwrap());
     assert_eq!(d.skip_months(), 0);
 }
@@ -21,7 +24,7 @@ fn test_start_cannot_be_later_than_end() {
     let d = NaiveDate::from_ymd_opt(2022, 11, 1).unwrap();
     assert!(MonthlyDelta::try_new(
         String::from("test"),
-        0.0,
+        Money::from(0),
         None,
         d,
         d,
@@ -31,7 +34,7 @@ fn test_start_cannot_be_later_than_end() {
     .is_ok());
     assert!(MonthlyDelta::try_new(
         String::from("test"),
-        0.0,
+        Money::from(0),
         None,
         d,
         d - Duration::days(1),
@@ -47,10 +50,10 @@ fn test_reasonable_bounds() {
 
     assert!(MonthlyDelta::try_new(
         String::from("test"),
-        0.0,
+        Money::from(0),
         Some(Uncertainty::Bounds {
-            low: 0.0,
-            high: 0.0
+            low: Money::from(0),
+            high: Money::from(0)
         }),
         date,
         date,
@@ -61,10 +64,10 @@ fn test_reasonable_bounds() {
 
     assert!(MonthlyDelta::try_new(
         String::from("test"),
-        0.0,
+        Money::from(0),
         Some(Uncertainty::Bounds {
-            low: 0.0,
-            high: 0.1
+            low: Money::from(0),
+            high: Money::from_cents(10)
         }),
         date,
         date,
@@ -75,10 +78,10 @@ fn test_reasonable_bounds() {
 
     assert!(MonthlyDelta::try_new(
         String::from("test"),
-        0.0,
+        Money::from(0),
         Some(Uncertainty::Bounds {
-            low: -0.1,
-            high: 0.0
+            low: Money::from_cents(-10),
+            high: Money::from(0)
         }),
         date,
         date,
@@ -89,10 +92,10 @@ fn test_reasonable_bounds() {
 
     assert!(MonthlyDelta::try_new(
         String::from("test"),
-        0.0,
+        Money::from(0),
         Some(Uncertainty::Bounds {
-            low: -0.1,
-            high: 0.1
+            low: Money::from_cents(-10),
+            high: Money::from_cents(10)
         }),
         date,
         date,
@@ -103,10 +106,10 @@ fn test_reasonable_bounds() {
 
     assert!(MonthlyDelta::try_new(
         String::from("test"),
-        -1.0,
+        Money::from(-1),
         Some(Uncertainty::Bounds {
-            low: -0.1,
-            high: 0.1
+            low: Money::from_cents(-10),
+            high: Money::from_cents(10)
         }),
         date,
         date,
@@ -117,10 +120,10 @@ fn test_reasonable_bounds() {
 
     assert!(MonthlyDelta::try_new(
         String::from("test"),
-        1.0,
+        Money::from(1),
         Some(Uncertainty::Bounds {
-            low: -0.1,
-            high: 0.1
+            low: Money::from_cents(-10),
+            high: Money::from_cents(10)
         }),
         date,
         date,
@@ -131,10 +134,10 @@ fn test_reasonable_bounds() {
 
     assert!(MonthlyDelta::try_new(
         String::from("test"),
-        0.0,
+        Money::from(0),
         Some(Uncertainty::Bounds {
-            low: 0.1,
-            high: -0.1
+            low: Money::from_cents(10),
+            high: Money::from_cents(-10)
         }),
         date,
         date,
@@ -153,7 +156,7 @@ fn test_monthly_dates(
 ) {
     let d = MonthlyDelta::try_new(
         String::from("test"),
-        0.0,
+        Money::from(0),
         None,
         start,
         end,
@@ -162,8 +165,8 @@ fn test_monthly_dates(
     )
     .unwrap();
 
-    let dates = d.dates();
-    dbg!(dates);
+    let dates = d.dates().collect::<Vec<_>>();
+    dbg!(&dates);
     for date in expected_dates.iter() {
         assert!(dates.contains(date), "date = {}", date);
     }
@@ -546,3 +549,243 @@ fn test_falls_before_month_day_skip_months_100() {
         &expected_dates,
     );
 }
+
+#[test]
+fn test_crosses_year_boundary() {
+    let expected_dates = vec![
+        NaiveDate::from_ymd_opt(2023, 1, 5).unwrap(),
+        NaiveDate::from_ymd_opt(2023, 2, 5).unwrap(),
+        NaiveDate::from_ymd_opt(2023, 3, 5).unwrap(),
+        NaiveDate::from_ymd_opt(2023, 4, 5).unwrap(),
+        NaiveDate::from_ymd_opt(2023, 5, 5).unwrap(),
+        NaiveDate::from_ymd_opt(2023, 6, 5).unwrap(),
+        NaiveDate::from_ymd_opt(2023, 7, 5).unwrap(),
+        NaiveDate::from_ymd_opt(2023, 8, 5).unwrap(),
+        NaiveDate::from_ymd_opt(2023, 9, 5).unwrap(),
+        NaiveDate::from_ymd_opt(2023, 10, 5).unwrap(),
+        NaiveDate::from_ymd_opt(2023, 11, 5).unwrap(),
+        NaiveDate::from_ymd_opt(2023, 12, 5).unwrap(),
+    ];
+
+    test_monthly_dates(
+        NaiveDate::from_ymd_opt(2022, 12, 20).unwrap(),
+        NaiveDate::from_ymd_opt(2024, 1, 2).unwrap(),
+        5.try_into().unwrap(),
+        0,
+        &expected_dates,
+    );
+}
+
+#[test]
+fn test_paycheck_rolls_to_preceding_business_day() {
+    let d = MonthlyDelta::try_new(
+        String::from("paycheck"),
+        Money::from(2000),
+        None,
+        NaiveDate::from_ymd_opt(2023, 1, 1).unwrap(),
+        NaiveDate::from_ymd_opt(2023, 7, 31).unwrap(),
+        15.try_into().unwrap(),
+        0,
+    )
+    .unwrap()
+    .with_roll(RollConvention::Preceding, Calendar::default());
+
+    assert_eq!(
+        d.dates().collect::<Vec<_>>(),
+        [
+            NaiveDate::from_ymd_opt(2023, 1, 13).unwrap(),
+            NaiveDate::from_ymd_opt(2023, 2, 15).unwrap(),
+            NaiveDate::from_ymd_opt(2023, 3, 15).unwrap(),
+            NaiveDate::from_ymd_opt(2023, 4, 14).unwrap(),
+            NaiveDate::from_ymd_opt(2023, 5, 15).unwrap(),
+            NaiveDate::from_ymd_opt(2023, 6, 15).unwrap(),
+            NaiveDate::from_ymd_opt(2023, 7, 14).unwrap(),
+        ]
+    );
+    assert_eq!(*d.roll(), RollConvention::Preceding);
+}
+
+#[test]
+fn test_autopay_rolls_past_holidays() {
+    let d = MonthlyDelta::try_new(
+        String::from("autopay"),
+        Money::from(-100),
+        None,
+        NaiveDate::from_ymd_opt(2023, 6, 1).unwrap(),
+        NaiveDate::from_ymd_opt(2023, 9, 30).unwrap(),
+        4.try_into().unwrap(),
+        0,
+    )
+    .unwrap()
+    .with_roll(RollConvention::Following, Calendar::us_federal());
+
+    assert_eq!(
+        d.dates().collect::<Vec<_>>(),
+        [
+            NaiveDate::from_ymd_opt(2023, 6, 5).unwrap(),
+            NaiveDate::from_ymd_opt(2023, 7, 5).unwrap(),
+            NaiveDate::from_ymd_opt(2023, 8, 4).unwrap(),
+            NaiveDate::from_ymd_opt(2023, 9, 5).unwrap(),
+        ]
+    );
+}
+
+#[test]
+fn test_occurrences_rolling_onto_one_day_are_kept() {
+    let d = MonthlyDelta::try_new_on_days(
+        String::from("bills"),
+        Money::from(-100),
+        None,
+        NaiveDate::from_ymd_opt(2022, 10, 1).unwrap(),
+        NaiveDate::from_ymd_opt(2022, 10, 31).unwrap(),
+        vec![1.try_into().unwrap(), 2.try_into().unwrap()],
+        0,
+    )
+    .unwrap()
+    .with_roll(RollConvention::Following, Calendar::default());
+
+    let monday = NaiveDate::from_ymd_opt(2022, 10, 3).unwrap();
+    assert_eq!(d.dates().collect::<Vec<_>>(), [monday, monday]);
+    assert_eq!(
+        d.occurrences(&monday, &monday)
+            .map(|occurrence| occurrence.value)
+            .sum::<Money>(),
+        Money::from(-200)
+    );
+}
+
+#[test]
+fn test_semi_monthly() {
+    let d = MonthlyDelta::try_new_on_days(
+        String::from("payroll"),
+        Money::from(2000),
+        None,
+        NaiveDate::from_ymd_opt(2024, 1, 1).unwrap(),
+        NaiveDate::from_ymd_opt(2024, 4, 20).unwrap(),
+        vec![15.try_into().unwrap(), (-1).try_into().unwrap()],
+        0,
+    )
+    .unwrap();
+
+    assert_eq!(
+        d.dates().collect::<Vec<_>>(),
+        [
+            NaiveDate::from_ymd_opt(2024, 1, 15).unwrap(),
+            NaiveDate::from_ymd_opt(2024, 1, 31).unwrap(),
+            NaiveDate::from_ymd_opt(2024, 2, 15).unwrap(),
+            NaiveDate::from_ymd_opt(2024, 2, 29).unwrap(),
+            NaiveDate::from_ymd_opt(2024, 3, 15).unwrap(),
+            NaiveDate::from_ymd_opt(2024, 3, 31).unwrap(),
+            NaiveDate::from_ymd_opt(2024, 4, 15).unwrap(),
+        ]
+    );
+}
+
+#[test]
+fn test_nth_weekday_skip_months() {
+    let d = MonthlyDelta::try_new(
+        String::from("test"),
+        Money::from(100),
+        None,
+        NaiveDate::from_ymd_opt(2023, 1, 3).unwrap(),
+        NaiveDate::from_ymd_opt(2023, 7, 31).unwrap(),
+        MonthDay::nth_weekday(1, Weekday::Mon).unwrap(),
+        1,
+    )
+    .unwrap();
+
+    // The first Monday of January is before the start, so the schedule starts in February
+    assert_eq!(
+        d.dates().collect::<Vec<_>>(),
+        [
+            NaiveDate::from_ymd_opt(2023, 2, 6).unwrap(),
+            NaiveDate::from_ymd_opt(2023, 4, 3).unwrap(),
+            NaiveDate::from_ymd_opt(2023, 6, 5).unwrap(),
+        ]
+    );
+}
+
+#[test]
+fn test_rounds_down_in_short_months() {
+    let d = MonthlyDelta::try_new(
+        String::from("test"),
+        Money::from(100),
+        None,
+        NaiveDate::from_ymd_opt(2023, 2, 1).unwrap(),
+        NaiveDate::from_ymd_opt(2023, 4, 30).unwrap(),
+        31.try_into().unwrap(),
+        0,
+    )
+    .unwrap();
+
+    assert_eq!(
+        d.dates().collect::<Vec<_>>(),
+        [
+            NaiveDate::from_ymd_opt(2023, 2, 28).unwrap(),
+            NaiveDate::from_ymd_opt(2023, 3, 31).unwrap(),
+            NaiveDate::from_ymd_opt(2023, 4, 30).unwrap(),
+        ]
+    );
+}
+
+#[test]
+fn test_needs_month_days() {
+    assert!(matches!(
+        MonthlyDelta::try_new_on_days(
+            String::from("test"),
+            Money::from(100),
+            None,
+            NaiveDate::from_ymd_opt(2023, 1, 1).unwrap(),
+            NaiveDate::from_ymd_opt(2023, 12, 31).unwrap(),
+            vec![],
+            0,
+        ),
+        Err(MoolahCoreError::NoMonthDays)
+    ));
+}
+
+#[test]
+fn test_setters() {
+    let start = NaiveDate::from_ymd_opt(2023, 1, 1).unwrap();
+    let mut d = MonthlyDelta::try_new(
+        "pay".into(),
+        Money::from(2000),
+        None,
+        start,
+        NaiveDate::from_ymd_opt(2023, 6, 30).unwrap(),
+        1.try_into().unwrap(),
+        0,
+    )
+    .unwrap();
+
+    assert!(matches!(
+        d.set_on_month_days(vec![]),
+        Err(MoolahCoreError::NoMonthDays)
+    ));
+    d.set_on_month_days(vec![15.try_into().unwrap(), (-1).try_into().unwrap()])
+        .unwrap();
+    d.set_skip_months(2);
+    assert_eq!(
+        d.dates().collect::<Vec<_>>(),
+        [
+            NaiveDate::from_ymd_opt(2023, 1, 15).unwrap(),
+            NaiveDate::from_ymd_opt(2023, 1, 31).unwrap(),
+            NaiveDate::from_ymd_opt(2023, 4, 15).unwrap(),
+            NaiveDate::from_ymd_opt(2023, 4, 30).unwrap()
+        ]
+    );
+}
+
+#[test]
+fn test_month_day_into_u32() {
+    let fifteenth: MonthDay = 15.try_into().unwrap();
+    assert_eq!(u32::from(fifteenth), 15);
+    assert_eq!(u32::from(&fifteenth), 15);
+
+    let last: MonthDay = (-1).try_into().unwrap();
+    assert_eq!(u32::from(last), 0);
+    assert_eq!(
+        u32::from(MonthDay::nth_weekday(1, Weekday::Mon).unwrap()),
+        0
+    );
+}
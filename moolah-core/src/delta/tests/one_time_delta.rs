@@ -1,4 +1,5 @@
 use super::*;
+use crate::date_helpers::ymd;
 use chrono::{Local, NaiveDate};
 
 #[test]
@@ -6,20 +7,20 @@ fn test_default() {
     let d = OneTimeDelta::default();
 
     assert_eq!(d.name(), "");
-    assert_eq!(d.value(), 0.0);
+    assert_eq!(d.value(), Money::from(0));
     assert!(d.uncertainty().is_none());
     assert_eq!(*d.date(), Local::now().date_naive());
-    assert_eq!(d.dates(), &[Local::now().date_naive()]);
+    assert_eq!(d.dates().collect::<Vec<_>>(), &[Local::now().date_naive()]);
 }
 
 #[test]
 fn test_dates() {
     let date = NaiveDate::from_ymd_opt(2022, 10, 30).unwrap();
-    let d = OneTimeDelta::try_new(String::from("test"), 1000.0, None, date)
+    let d = OneTimeDelta::try_new(String::from("test"), Money::from(1000), None, date)
         .expect("Could not build OneTimeDelta");
 
-    assert_eq!(d.dates().len(), 1);
-    assert!(d.dates().contains(&date));
+    assert_eq!(d.dates().count(), 1);
+    assert!(d.dates().collect::<Vec<_>>().contains(&date));
 }
 
 #[test]
@@ -28,10 +29,10 @@ fn test_reasonable_bounds() {
 
     assert!(OneTimeDelta::try_new(
         String::from("test"),
-        0.0,
+        Money::from(0),
         Some(Uncertainty::Bounds {
-            low: 0.0,
-            high: 0.0
+            low: Money::from(0),
+            high: Money::from(0)
         }),
         date
     )
@@ -39,10 +40,10 @@ fn test_reasonable_bounds() {
 
     assert!(OneTimeDelta::try_new(
         String::from("test"),
-        0.0,
+        Money::from(0),
         Some(Uncertainty::Bounds {
-            low: 0.0,
-            high: 0.1
+            low: Money::from(0),
+            high: Money::from_cents(10)
         }),
         date
     )
@@ -50,10 +51,10 @@ fn test_reasonable_bounds() {
 
     assert!(OneTimeDelta::try_new(
         String::from("test"),
-        0.0,
+        Money::from(0),
         Some(Uncertainty::Bounds {
-            low: -0.1,
-            high: 0.0
+            low: Money::from_cents(-10),
+            high: Money::from(0)
         }),
         date
     )
@@ -61,10 +62,10 @@ fn test_reasonable_bounds() {
 
     assert!(OneTimeDelta::try_new(
         String::from("test"),
-        0.0,
+        Money::from(0),
         Some(Uncertainty::Bounds {
-            low: -0.1,
-            high: 0.1
+            low: Money::from_cents(-10),
+            high: Money::from_cents(10)
         }),
         date
     )
@@ -72,10 +73,10 @@ fn test_reasonable_bounds() {
 
     assert!(OneTimeDelta::try_new(
         String::from("test"),
-        -1.0,
+        Money::from(-1),
         Some(Uncertainty::Bounds {
-            low: -0.1,
-            high: 0.1
+            low: Money::from_cents(-10),
+            high: Money::from_cents(10)
         }),
         date
     )
@@ -83,10 +84,10 @@ fn test_reasonable_bounds() {
 
     assert!(OneTimeDelta::try_new(
         String::from("test"),
-        1.0,
+        Money::from(1),
         Some(Uncertainty::Bounds {
-            low: -0.1,
-            high: 0.1
+            low: Money::from_cents(-10),
+            high: Money::from_cents(10)
         }),
         date
     )
@@ -94,10 +95,10 @@ fn test_reasonable_bounds() {
 
     assert!(OneTimeDelta::try_new(
         String::from("test"),
-        0.0,
+        Money::from(0),
         Some(Uncertainty::Bounds {
-            low: 0.1,
-            high: -0.1
+            low: Money::from_cents(10),
+            high: Money::from_cents(-10)
         }),
         date
     )
@@ -111,52 +112,99 @@ fn test_balanced_uncertainty() {
     // dollar uncertainty w/ positive value
     let d = OneTimeDelta::try_new(
         String::from("test"),
-        1000.0,
+        Money::from(1000),
         Some(Uncertainty::Balanced(UncertaintyType::Dollars(
             3.0.try_into().unwrap(),
         ))),
         date,
     )
     .expect("Could not build OneTimeDelta");
-    assert_eq!(d.max_uncertainty_value(), 1003.0);
-    assert_eq!(d.min_uncertainty_value(), 997.0);
+    assert_eq!(d.max_uncertainty_value(), Money::from(1003));
+    assert_eq!(d.min_uncertainty_value(), Money::from(997));
 
     // dollar uncertainty w/ negative value
     let d = OneTimeDelta::try_new(
         String::from("test"),
-        -1000.0,
+        Money::from(-1000),
         Some(Uncertainty::Balanced(UncertaintyType::Dollars(
             3.0.try_into().unwrap(),
         ))),
         date,
     )
     .expect("Could not build OneTimeDelta");
-    assert_eq!(d.max_uncertainty_value(), -997.0);
-    assert_eq!(d.min_uncertainty_value(), -1003.0);
+    assert_eq!(d.max_uncertainty_value(), Money::from(-997));
+    assert_eq!(d.min_uncertainty_value(), Money::from(-1003));
 
     // percent uncertainty w/ positive value
     let d = OneTimeDelta::try_new(
         String::from("test"),
-        1000.0,
+        Money::from(1000),
         Some(Uncertainty::Balanced(UncertaintyType::Percent(
             3.0.try_into().unwrap(),
         ))),
         date,
     )
     .expect("Could not build OneTimeDelta");
-    assert_eq!(d.max_uncertainty_value(), 1030.0);
-    assert_eq!(d.min_uncertainty_value(), 970.0);
+    assert_eq!(d.max_uncertainty_value(), Money::from(1030));
+    assert_eq!(d.min_uncertainty_value(), Money::from(970));
 
     // percent uncertainty w/ negative value
     let d = OneTimeDelta::try_new(
         String::from("test"),
-        -1000.0,
+        Money::from(-1000),
         Some(Uncertainty::Balanced(UncertaintyType::Percent(
             3.0.try_into().unwrap(),
         ))),
         date,
     )
     .expect("Could not build OneTimeDelta");
-    assert_eq!(d.max_uncertainty_value(), -970.0);
-    assert_eq!(d.min_uncertainty_value(), -1030.0);
+    assert_eq!(d.max_uncertainty_value(), Money::from(-970));
+    assert_eq!(d.min_uncertainty_value(), Money::from(-1030));
+}
+
+#[test]
+fn test_percent_uncertainty_rounds_to_cents() {
+    let date = NaiveDate::from_ymd_opt(2022, 10, 30).unwrap();
+
+    let d = OneTimeDelta::try_new(
+        String::from("test"),
+        Money::from_cents(-1001),
+        Some(Uncertainty::Unbalanced {
+            low: UncertaintyType::Percent(12.5.try_into().unwrap()),
+            high: UncertaintyType::Percent(0.05.try_into().unwrap()),
+        }),
+        date,
+    )
+    .expect("Could not build OneTimeDelta");
+    assert_eq!(d.min_uncertainty_value(), Money::from_cents(-1126));
+    assert_eq!(d.max_uncertainty_value(), Money::from_cents(-1000));
+}
+
+#[test]
+fn test_setters() {
+    let mut d = OneTimeDelta::try_new(
+        "gift".into(),
+        Money::from(100),
+        Some(Uncertainty::Bounds {
+            low: Money::from(90),
+            high: Money::from(110),
+        }),
+        ymd(2023, 1, 1),
+    )
+    .unwrap();
+
+    d.set_date(ymd(2023, 6, 1));
+    assert_eq!(d.dates().collect::<Vec<_>>(), [ymd(2023, 6, 1)]);
+
+    // The bounds no longer hold the new value, so nothing changes
+    assert!(matches!(
+        d.set_value(Money::from(200)),
+        Err(MoolahCoreError::IllogicalUncertaintyBounds { .. })
+    ));
+    assert_eq!(d.value(), Money::from(100));
+
+    d.set_uncertainty(None).unwrap();
+    d.set_value(Money::from(200)).unwrap();
+    d.set_name("bonus".into());
+    assert_eq!((d.name(), d.value()), ("bonus", Money::from(200)));
 }
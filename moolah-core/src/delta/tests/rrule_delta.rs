@@ -0,0 +1,379 @@
+use chrono::{Local, NaiveDate};
+
+use super::*;
+use crate::date_helpers::ymd;
+
+fn rrule_dates(
+    rule: &str,
+    start: NaiveDate,
+    end: NaiveDate,
+    exdates: Vec<NaiveDate>,
+) -> Vec<NaiveDate> {
+    RRuleDelta::try_new(
+        String::from("test"),
+        Money::from(100),
+        None,
+        start,
+        end,
+        rule.parse().unwrap(),
+        exdates,
+    )
+    .unwrap()
+    .dates()
+    .collect()
+}
+
+#[test]
+fn test_default() {
+    let d = RRuleDelta::default();
+    let today = Local::now().date_naive();
+
+    assert_eq!(d.name(), "");
+    assert_eq!(d.value(), Money::from(0));
+    assert!(d.uncertainty().is_none());
+    assert_eq!(d.dates().collect::<Vec<_>>(), &[today]);
+    assert_eq!(d.rrule().to_string(), "FREQ=DAILY");
+    assert!(d.exdates().is_empty());
+}
+
+#[test]
+fn test_start_cannot_be_later_than_end() {
+    assert!(matches!(
+        RRuleDelta::try_new(
+            String::from("test"),
+            Money::from(0),
+            None,
+            ymd(2023, 2, 1),
+            ymd(2023, 1, 1),
+            "FREQ=DAILY".parse().unwrap(),
+            vec![],
+        ),
+        Err(MoolahCoreError::StartAfterEnd { .. })
+    ));
+}
+
+#[test]
+fn test_invalid_rules() {
+    for rule in [
+        "",
+        "INTERVAL=2",
+        "FREQ=HOURLY",
+        "FREQ=DAILY;BYHOUR=9",
+        "FREQ=DAILY;COUNT=3;UNTIL=20230101",
+        "FREQ=DAILY;INTERVAL=0",
+        "FREQ=WEEKLY;BYDAY=2FR",
+        "FREQ=WEEKLY;BYMONTHDAY=1",
+        "FREQ=MONTHLY;BYMONTHDAY=0",
+        "FREQ=MONTHLY;BYMONTHDAY=32",
+        "FREQ=MONTHLY;BYMONTH=13",
+        "FREQ=MONTHLY;BYDAY=XX",
+        "FREQ=MONTHLY;BYSETPOS=1",
+        "FREQ=MONTHLY;UNTIL=2023-01-01",
+    ] {
+        assert!(
+            matches!(
+                rule.parse::<RRule>(),
+                Err(MoolahCoreError::InvalidRRule { .. })
+            ),
+            "{rule} should not parse"
+        );
+    }
+}
+
+#[test]
+fn test_display() {
+    let rule: RRule = "RRULE:FREQ=MONTHLY;INTERVAL=2;BYDAY=-1FR,+2MO;WKST=SU"
+        .parse()
+        .unwrap();
+    assert_eq!(
+        rule.to_string(),
+        "FREQ=MONTHLY;INTERVAL=2;BYDAY=-1FR,2MO;WKST=SU"
+    );
+    assert_eq!(rule.to_string().parse::<RRule>().unwrap(), rule);
+}
+
+#[test]
+fn test_nth_weekdays() {
+    assert_eq!(
+        rrule_dates(
+            "FREQ=MONTHLY;BYDAY=2FR,4FR",
+            ymd(2023, 1, 1),
+            ymd(2023, 3, 31),
+            vec![]
+        ),
+        [
+            ymd(2023, 1, 13),
+            ymd(2023, 1, 27),
+            ymd(2023, 2, 10),
+            ymd(2023, 2, 24),
+            ymd(2023, 3, 10),
+            ymd(2023, 3, 24),
+        ]
+    );
+}
+
+#[test]
+fn test_last_business_day() {
+    assert_eq!(
+        rrule_dates(
+            "FREQ=MONTHLY;BYDAY=MO,TU,WE,TH,FR;BYSETPOS=-1",
+            ymd(2023, 1, 1),
+            ymd(2023, 4, 30),
+            vec![]
+        ),
+        [
+            ymd(2023, 1, 31),
+            ymd(2023, 2, 28),
+            ymd(2023, 3, 31),
+            ymd(2023, 4, 28),
+        ]
+    );
+}
+
+#[test]
+fn test_except_december() {
+    assert_eq!(
+        rrule_dates(
+            "FREQ=MONTHLY;BYDAY=3TU;BYMONTH=1,2,3,4,5,6,7,8,9,10,11",
+            ymd(2023, 10, 1),
+            ymd(2024, 2, 29),
+            vec![]
+        ),
+        [
+            ymd(2023, 10, 17),
+            ymd(2023, 11, 21),
+            ymd(2024, 1, 16),
+            ymd(2024, 2, 20),
+        ]
+    );
+}
+
+#[test]
+fn test_last_day_of_month() {
+    assert_eq!(
+        rrule_dates(
+            "FREQ=MONTHLY;BYMONTHDAY=-1",
+            ymd(2024, 1, 1),
+            ymd(2024, 4, 30),
+            vec![]
+        ),
+        [
+            ymd(2024, 1, 31),
+            ymd(2024, 2, 29),
+            ymd(2024, 3, 31),
+            ymd(2024, 4, 30),
+        ]
+    );
+}
+
+#[test]
+fn test_skips_short_months() {
+    assert_eq!(
+        rrule_dates("FREQ=MONTHLY", ymd(2023, 1, 31), ymd(2023, 5, 31), vec![]),
+        [ymd(2023, 1, 31), ymd(2023, 3, 31), ymd(2023, 5, 31)]
+    );
+}
+
+#[test]
+fn test_start_and_end_are_inclusive_bounds() {
+    assert_eq!(
+        rrule_dates(
+            "FREQ=MONTHLY;BYMONTHDAY=1",
+            ymd(2023, 1, 10),
+            ymd(2023, 3, 1),
+            vec![]
+        ),
+        [ymd(2023, 2, 1), ymd(2023, 3, 1)]
+    );
+}
+
+#[test]
+fn test_count() {
+    assert_eq!(
+        rrule_dates(
+            "FREQ=WEEKLY;BYDAY=MO,TH;COUNT=5",
+            ymd(2023, 1, 4),
+            ymd(2023, 12, 31),
+            vec![]
+        ),
+        [
+            ymd(2023, 1, 5),
+            ymd(2023, 1, 9),
+            ymd(2023, 1, 12),
+            ymd(2023, 1, 16),
+            ymd(2023, 1, 19),
+        ]
+    );
+}
+
+#[test]
+fn test_until() {
+    assert_eq!(
+        rrule_dates(
+            "FREQ=DAILY;INTERVAL=2;UNTIL=20230110T000000Z",
+            ymd(2023, 1, 1),
+            ymd(2023, 12, 31),
+            vec![]
+        ),
+        [
+            ymd(2023, 1, 1),
+            ymd(2023, 1, 3),
+            ymd(2023, 1, 5),
+            ymd(2023, 1, 7),
+            ymd(2023, 1, 9),
+        ]
+    );
+}
+
+#[test]
+fn test_exdates() {
+    // Excluded dates still count towards COUNT
+    assert_eq!(
+        rrule_dates(
+            "FREQ=MONTHLY;COUNT=3",
+            ymd(2023, 1, 15),
+            ymd(2023, 12, 31),
+            vec![ymd(2023, 2, 15)]
+        ),
+        [ymd(2023, 1, 15), ymd(2023, 3, 15)]
+    );
+}
+
+#[test]
+fn test_biweekly() {
+    assert_eq!(
+        rrule_dates(
+            "FREQ=WEEKLY;INTERVAL=2;BYDAY=MO,FR",
+            ymd(2023, 1, 4),
+            ymd(2023, 1, 31),
+            vec![]
+        ),
+        [
+            ymd(2023, 1, 6),
+            ymd(2023, 1, 16),
+            ymd(2023, 1, 20),
+            ymd(2023, 1, 30),
+        ]
+    );
+}
+
+#[test]
+fn test_yearly() {
+    assert_eq!(
+        rrule_dates(
+            "FREQ=YEARLY;BYMONTH=11;BYDAY=4TH",
+            ymd(2023, 1, 1),
+            ymd(2025, 12, 31),
+            vec![]
+        ),
+        [ymd(2023, 11, 23), ymd(2024, 11, 28), ymd(2025, 11, 27)]
+    );
+    assert_eq!(
+        rrule_dates(
+            "FREQ=YEARLY;BYDAY=-1FR",
+            ymd(2023, 1, 1),
+            ymd(2024, 12, 31),
+            vec![]
+        ),
+        [ymd(2023, 12, 29), ymd(2024, 12, 27)]
+    );
+    assert_eq!(
+        rrule_dates("FREQ=YEARLY", ymd(2024, 2, 29), ymd(2028, 12, 31), vec![]),
+        [ymd(2024, 2, 29), ymd(2028, 2, 29)]
+    );
+}
+
+#[test]
+fn test_open_ended() {
+    let d = RRuleDelta::try_new(
+        String::from("test"),
+        Money::from(100),
+        None,
+        ymd(2023, 1, 1),
+        None,
+        "FREQ=MONTHLY;BYDAY=-1FR".parse().unwrap(),
+        vec![ymd(2023, 2, 24)],
+    )
+    .unwrap();
+    assert!(d.is_open_ended());
+    assert_eq!(
+        d.dates().take(3).collect::<Vec<_>>(),
+        [ymd(2023, 1, 27), ymd(2023, 3, 31), ymd(2023, 4, 28)]
+    );
+    assert_eq!(
+        d.dates_between(&ymd(2040, 1, 1), &ymd(2040, 2, 29))
+            .collect::<Vec<_>>(),
+        [ymd(2040, 1, 27), ymd(2040, 2, 24)]
+    );
+
+    let counted = RRuleDelta::try_new(
+        String::from("test"),
+        Money::from(100),
+        None,
+        ymd(2023, 1, 1),
+        None,
+        "FREQ=MONTHLY;COUNT=3".parse().unwrap(),
+        vec![],
+    )
+    .unwrap();
+    assert!(!counted.is_open_ended());
+    assert_eq!(counted.dates().count(), 3);
+}
+
+#[test]
+fn test_open_ended_without_matches() {
+    let d = RRuleDelta::try_new(
+        String::from("test"),
+        Money::from(100),
+        None,
+        ymd(2023, 1, 1),
+        None,
+        "FREQ=YEARLY;BYMONTH=2;BYMONTHDAY=30".parse().unwrap(),
+        vec![],
+    )
+    .unwrap();
+    assert_eq!(
+        d.dates_between(&ymd(2023, 1, 1), &ymd(2100, 12, 31))
+            .count(),
+        0
+    );
+    assert!(d.dates().next().is_none());
+
+    // Rare dates are still found however long the gap
+    let leap_days = RRuleDelta::try_new(
+        String::from("test"),
+        Money::from(100),
+        None,
+        ymd(2097, 3, 1),
+        None,
+        "FREQ=DAILY;BYMONTH=2;BYMONTHDAY=29".parse().unwrap(),
+        vec![],
+    )
+    .unwrap();
+    assert_eq!(
+        leap_days.dates().take(2).collect::<Vec<_>>(),
+        [ymd(2104, 2, 29), ymd(2108, 2, 29)]
+    );
+}
+
+#[test]
+fn test_setters() {
+    let mut d = RRuleDelta::try_new(
+        "rent".into(),
+        Money::from(-1500),
+        None,
+        ymd(2023, 1, 1),
+        ymd(2023, 3, 31),
+        "FREQ=MONTHLY".parse().unwrap(),
+        vec![],
+    )
+    .unwrap();
+
+    d.set_rrule("FREQ=MONTHLY;BYMONTHDAY=-1".parse().unwrap());
+    d.set_exdates(vec![ymd(2023, 2, 28)]);
+    assert_eq!(
+        d.dates().collect::<Vec<_>>(),
+        [ymd(2023, 1, 31), ymd(2023, 3, 31)]
+    );
+    assert!(d.set_end(Some(ymd(2022, 12, 31))).is_err());
+}
@@ -0,0 +1,559 @@
+use super::*;
+use chrono::Weekday;
+use serde_json::json;
+
+fn round_trip(delta: &dyn Delta) -> Box<dyn Delta> {
+    let serialized = serde_json::to_string(&delta.tagged()).expect("could not serialize delta");
+    let tagged: TaggedDelta =
+        serde_json::from_str(&serialized).expect("could not deserialize delta");
+    tagged.into()
+}
+
+fn assert_deltas_eq(expected: &dyn Delta, calculated: &dyn Delta) {
+    assert_eq!(expected.name(), calculated.name());
+    assert_eq!(expected.value(), calculated.value());
+    assert_eq!(expected.uncertainty(), calculated.uncertainty());
+    assert_eq!(
+        expected.dates().collect::<Vec<_>>(),
+        calculated.dates().collect::<Vec<_>>()
+    );
+}
+
+#[test]
+fn test_round_trip_all_delta_types() {
+    let deltas: Vec<Box<dyn Delta>> = vec![
+        Box::new(
+            OneTimeDelta::try_new(
+                "one time".into(),
+                Money::from(200),
+                Some(Uncertainty::Unbalanced {
+                    low: UncertaintyType::Dollars(15.0.try_into().unwrap()),
+                    high: UncertaintyType::Percent(10.0.try_into().unwrap()),
+                }),
+                NaiveDate::from_ymd_opt(2022, 10, 31).unwrap(),
+            )
+            .unwrap(),
+        ),
+        Box::new(
+            DailyDelta::try_new(
+                "daily".into(),
+                Money::from(1),
+                None,
+                NaiveDate::from_ymd_opt(2022, 11, 22).unwrap(),
+                NaiveDate::from_ymd_opt(2022, 12, 7).unwrap(),
+                2,
+            )
+            .unwrap(),
+        ),
+        Box::new(
+            WeeklyDelta::try_new(
+                "weekly".into(),
+                Money::from(-13),
+                None,
+                NaiveDate::from_ymd_opt(2022, 11, 3).unwrap(),
+                NaiveDate::from_ymd_opt(2022, 12, 12).unwrap(),
+                Some(Weekday::Wed),
+                1,
+            )
+            .unwrap(),
+        ),
+        Box::new(
+            MonthlyDelta::try_new(
+                "monthly".into(),
+                Money::from(1234),
+                Some(Uncertainty::Balanced(UncertaintyType::Dollars(
+                    12.0.try_into().unwrap(),
+                ))),
+                NaiveDate::from_ymd_opt(2022, 10, 28).unwrap(),
+                NaiveDate::from_ymd_opt(2022, 12, 31).unwrap(),
+                3.try_into().unwrap(),
+                1,
+            )
+            .unwrap(),
+        ),
+        Box::new(
+            YearlyDelta::try_new(
+                "yearly".into(),
+                Money::from(-155),
+                None,
+                NaiveDate::from_ymd_opt(2022, 7, 1).unwrap(),
+                NaiveDate::from_ymd_opt(2024, 12, 14).unwrap(),
+                0,
+            )
+            .unwrap(),
+        ),
+        Box::new(
+            CustomDelta::try_new(
+                "custom".into(),
+                Money::from(-15),
+                Some(Uncertainty::Bounds {
+                    low: Money::from(-20),
+                    high: Money::from(-12),
+                }),
+                vec![
+                    NaiveDate::from_ymd_opt(2022, 11, 12).unwrap(),
+                    NaiveDate::from_ymd_opt(2022, 12, 1).unwrap(),
+                ],
+            )
+            .unwrap(),
+        ),
+        Box::new(
+            RRuleDelta::try_new(
+                "rrule".into(),
+                Money::from(1500),
+                None,
+                NaiveDate::from_ymd_opt(2022, 1, 1).unwrap(),
+                NaiveDate::from_ymd_opt(2022, 12, 31).unwrap(),
+                "FREQ=MONTHLY;BYDAY=2FR,4FR".parse().unwrap(),
+                vec![NaiveDate::from_ymd_opt(2022, 12, 23).unwrap()],
+            )
+            .unwrap(),
+        ),
+    ];
+
+    for delta in deltas.iter() {
+        assert_deltas_eq(&**delta, &*round_trip(&**delta));
+    }
+}
+
+#[test]
+fn test_tagged_representation() {
+    let d = MonthlyDelta::try_new(
+        "rent".into(),
+        Money::from(-1800),
+        Some(Uncertainty::Balanced(UncertaintyType::Percent(
+            5.0.try_into().unwrap(),
+        ))),
+        NaiveDate::from_ymd_opt(2023, 1, 1).unwrap(),
+        NaiveDate::from_ymd_opt(2023, 12, 31).unwrap(),
+        1.try_into().unwrap(),
+        0,
+    )
+    .unwrap();
+
+    assert_eq!(
+        serde_json::to_value(d.tagged()).unwrap(),
+        json!({
+            "kind": "monthly",
+            "name": "rent",
+            "value": "-1800.00",
+            "uncertainty": {"balanced": {"percent": 5.0}},
+            "start": "2023-01-01",
+            "end": "2023-12-31",
+            "on_month_day": 1,
+            "skip_months": 0,
+        })
+    );
+}
+
+#[test]
+fn test_optional_fields_default() {
+    let tagged: TaggedDelta = serde_json::from_value(json!({
+        "kind": "weekly",
+        "name": "groceries",
+        "value": -150.0,
+        "start": "2022-11-02",
+        "end": "2022-11-16",
+    }))
+    .unwrap();
+    let d: Box<dyn Delta> = tagged.into();
+
+    assert!(d.uncertainty().is_none());
+    assert_eq!(
+        d.dates().collect::<Vec<_>>(),
+        &[
+            NaiveDate::from_ymd_opt(2022, 11, 2).unwrap(),
+            NaiveDate::from_ymd_opt(2022, 11, 9).unwrap(),
+            NaiveDate::from_ymd_opt(2022, 11, 16).unwrap(),
+        ]
+    );
+}
+
+#[test]
+fn test_deserialize_validates() {
+    let start_after_end = serde_json::from_value::<TaggedDelta>(json!({
+        "kind": "daily",
+        "name": "coffee",
+        "value": -5.0,
+        "start": "2022-11-02",
+        "end": "2022-11-01",
+    }));
+    assert!(start_after_end
+        .err()
+        .unwrap()
+        .to_string()
+        .contains("cannot be after end"));
+
+    let bad_bounds = serde_json::from_value::<TaggedDelta>(json!({
+        "kind": "one_time",
+        "name": "bonus",
+        "value": 100.0,
+        "uncertainty": {"bounds": {"low": 110.0, "high": 120.0}},
+        "date": "2022-11-02",
+    }));
+    assert!(bad_bounds.is_err());
+
+    let bad_month_day = serde_json::from_value::<TaggedDelta>(json!({
+        "kind": "monthly",
+        "name": "rent",
+        "value": -1800.0,
+        "start": "2022-11-01",
+        "end": "2023-11-01",
+        "on_month_day": 32,
+    }));
+    assert!(bad_month_day.is_err());
+
+    let negative_uncertainty =
+        serde_json::from_value::<Uncertainty>(json!({"balanced": {"dollars": -5.0}}));
+    assert!(negative_uncertainty.is_err());
+
+    let unknown_kind = serde_json::from_value::<TaggedDelta>(json!({
+        "kind": "hourly",
+        "name": "rent",
+        "value": -1800.0,
+    }));
+    assert!(unknown_kind.is_err());
+}
+
+#[test]
+fn test_money_representations() {
+    let tagged: TaggedDelta = serde_json::from_value(json!({
+        "kind": "custom",
+        "name": "utilities",
+        "value": "-80.125",
+        "uncertainty": {"bounds": {"low": -95, "high": -70.5}},
+        "dates": ["2022-11-02"],
+    }))
+    .unwrap();
+    let d: Box<dyn Delta> = tagged.into();
+
+    assert_eq!(d.value(), Money::from_cents(-8013));
+    assert_eq!(d.min_uncertainty_value(), Money::from(-95));
+    assert_eq!(d.max_uncertainty_value(), Money::from_cents(-7050));
+    assert_eq!(
+        serde_json::to_value(d.tagged()).unwrap()["value"],
+        json!("-80.13")
+    );
+}
+
+#[test]
+fn test_interest_representation() {
+    let d = InterestDelta::try_new(
+        "savings interest".into(),
+        4.5,
+        Some(RateUncertainty::Balanced(0.5.try_into().unwrap())),
+        Compounding::Daily,
+        NaiveDate::from_ymd_opt(2023, 1, 1).unwrap(),
+        NaiveDate::from_ymd_opt(2023, 12, 31).unwrap(),
+    )
+    .unwrap();
+
+    let value = serde_json::to_value(d.tagged()).unwrap();
+    assert_eq!(
+        value,
+        json!({
+            "kind": "interest",
+            "name": "savings interest",
+            "rate": 4.5,
+            "rate_uncertainty": {"balanced": 0.5},
+            "compounding": "daily",
+            "start": "2023-01-01",
+            "end": "2023-12-31",
+        })
+    );
+
+    let round_tripped: Box<dyn Delta> =
+        serde_json::from_value::<TaggedDelta>(value).unwrap().into();
+    assert_deltas_eq(&d, &*round_tripped);
+    assert!(round_tripped.is_balance_dependent());
+
+    let tagged: TaggedDelta = serde_json::from_value(json!({
+        "kind": "interest",
+        "name": "brokerage",
+        "rate": 7,
+        "start": "2023-01-01",
+        "end": "2023-12-31",
+    }))
+    .unwrap();
+    let delta: Box<dyn Delta> = tagged.into();
+    assert_eq!(delta.dates().count(), 11);
+}
+
+#[test]
+fn test_loan_round_trip() {
+    let d = LoanDelta::try_new(
+        "mortgage".into(),
+        Money::from(250_000),
+        5.5,
+        360,
+        NaiveDate::from_ymd_opt(2023, 1, 10).unwrap(),
+        1.try_into().unwrap(),
+        vec![
+            ExtraPayment::Recurring {
+                amount: Money::from(200),
+                from: NaiveDate::from_ymd_opt(2024, 1, 1).unwrap(),
+            },
+            ExtraPayment::OneTime {
+                amount: Money::from(10_000),
+                date: NaiveDate::from_ymd_opt(2025, 6, 1).unwrap(),
+            },
+        ],
+    )
+    .unwrap();
+
+    let value = serde_json::to_value(d.tagged()).unwrap();
+    assert_eq!(value["kind"], "loan");
+    assert_eq!(
+        value["extra_payments"][0],
+        json!({"recurring": {"amount": "200.00", "from": "2024-01-01"}})
+    );
+
+    let round_tripped: Box<dyn Delta> =
+        serde_json::from_value::<TaggedDelta>(value).unwrap().into();
+    assert_deltas_eq(&d, &*round_tripped);
+    let date = d.dates().nth(17).unwrap();
+    assert_eq!(round_tripped.amounts_on(&date), d.amounts_on(&date));
+}
+
+#[test]
+fn test_distribution_representation() {
+    let tagged: TaggedDelta = serde_json::from_value(json!({
+        "kind": "monthly",
+        "name": "electricity",
+        "value": -100,
+        "uncertainty": {"empirical": {
+            "amounts": [-140, -120, -100, -95, -80],
+            "quantiles": {"low": 0.25, "high": 0.75},
+        }},
+        "start": "2023-01-01",
+        "end": "2023-12-31",
+    }))
+    .unwrap();
+    let d: Box<dyn Delta> = tagged.into();
+    assert_eq!(d.min_uncertainty_value(), Money::from(-120));
+    assert_eq!(d.max_uncertainty_value(), Money::from(-95));
+    assert_deltas_eq(&*d, &*round_trip(&*d));
+
+    // Default quantiles are left out
+    let normal = Uncertainty::Normal {
+        std_dev: 10.0.try_into().unwrap(),
+        quantiles: Default::default(),
+    };
+    let value = serde_json::to_value(&normal).unwrap();
+    assert_eq!(value, json!({"normal": {"std_dev": "10.00"}}));
+    assert_eq!(
+        serde_json::from_value::<Uncertainty>(value).unwrap(),
+        normal
+    );
+
+    let bad_quantiles = serde_json::from_value::<Uncertainty>(json!({
+        "uniform": {"low": -10, "high": 10, "quantiles": {"low": 0.9, "high": 0.1}},
+    }));
+    assert!(bad_quantiles.is_err());
+
+    let empty = serde_json::from_value::<TaggedDelta>(json!({
+        "kind": "one_time",
+        "name": "bill",
+        "value": -100,
+        "uncertainty": {"empirical": {"amounts": []}},
+        "date": "2023-01-01",
+    }));
+    assert!(empty.is_err());
+}
+
+#[test]
+fn test_rrule_representation() {
+    let value = json!({
+        "kind": "rrule",
+        "name": "paycheck",
+        "value": "1500.00",
+        "start": "2023-01-01",
+        "end": "2023-12-31",
+        "rrule": "FREQ=MONTHLY;BYDAY=MO,TU,WE,TH,FR;BYSETPOS=-1",
+        "exdates": ["2023-03-31"],
+    });
+    let d: Box<dyn Delta> = serde_json::from_value::<TaggedDelta>(value.clone())
+        .unwrap()
+        .into();
+    assert_eq!(d.dates().count(), 11);
+    assert_eq!(serde_json::to_value(d.tagged()).unwrap(), value);
+
+    let bad_rule = serde_json::from_value::<TaggedDelta>(json!({
+        "kind": "rrule",
+        "name": "paycheck",
+        "value": 1500.0,
+        "start": "2023-01-01",
+        "end": "2023-12-31",
+        "rrule": "FREQ=FORTNIGHTLY",
+    }));
+    assert!(bad_rule
+        .err()
+        .unwrap()
+        .to_string()
+        .contains("unsupported FREQ"));
+}
+
+#[test]
+fn test_roll_representation() {
+    let value = json!({
+        "kind": "weekly",
+        "name": "autopay",
+        "value": "-50.00",
+        "start": "2023-01-01",
+        "end": "2023-01-31",
+        "on_weekday": "Mon",
+        "skip_weeks": 0,
+        "roll": "modified_following",
+        "calendar": {"us_federal": true, "holidays": ["2023-01-09"]},
+    });
+    let d: Box<dyn Delta> = serde_json::from_value::<TaggedDelta>(value.clone())
+        .unwrap()
+        .into();
+    assert_eq!(
+        d.dates().collect::<Vec<_>>(),
+        [
+            NaiveDate::from_ymd_opt(2023, 1, 3).unwrap(),
+            NaiveDate::from_ymd_opt(2023, 1, 10).unwrap(),
+            NaiveDate::from_ymd_opt(2023, 1, 17).unwrap(),
+            NaiveDate::from_ymd_opt(2023, 1, 23).unwrap(),
+            NaiveDate::from_ymd_opt(2023, 1, 30).unwrap(),
+        ]
+    );
+    assert_eq!(serde_json::to_value(d.tagged()).unwrap(), value);
+
+    let bad_roll = serde_json::from_value::<TaggedDelta>(json!({
+        "kind": "monthly",
+        "name": "autopay",
+        "value": -50.0,
+        "start": "2023-01-01",
+        "end": "2023-01-31",
+        "roll": "nearest",
+    }));
+    assert!(bad_roll.is_err());
+}
+
+#[test]
+fn test_month_days_representation() {
+    let value = json!({
+        "kind": "monthly",
+        "name": "payroll",
+        "value": "2000.00",
+        "start": "2023-01-01",
+        "end": "2023-03-31",
+        "on_month_day": [15, -1, "first Mon"],
+        "skip_months": 0,
+    });
+    let d: Box<dyn Delta> = serde_json::from_value::<TaggedDelta>(value.clone())
+        .unwrap()
+        .into();
+    assert_eq!(d.dates().count(), 9);
+    assert_eq!(serde_json::to_value(d.tagged()).unwrap(), value);
+
+    let last_friday = serde_json::from_value::<TaggedDelta>(json!({
+        "kind": "monthly",
+        "name": "payroll",
+        "value": 2000.0,
+        "start": "2023-01-01",
+        "end": "2023-03-31",
+        "on_month_day": "last Fri",
+    }));
+    let d: Box<dyn Delta> = last_friday.unwrap().into();
+    assert_eq!(
+        d.dates().collect::<Vec<_>>(),
+        [
+            NaiveDate::from_ymd_opt(2023, 1, 27).unwrap(),
+            NaiveDate::from_ymd_opt(2023, 2, 24).unwrap(),
+            NaiveDate::from_ymd_opt(2023, 3, 31).unwrap(),
+        ]
+    );
+
+    let bad_rule = serde_json::from_value::<TaggedDelta>(json!({
+        "kind": "monthly",
+        "name": "payroll",
+        "value": 2000.0,
+        "start": "2023-01-01",
+        "end": "2023-03-31",
+        "on_month_day": "every Mon",
+    }));
+    assert!(bad_rule
+        .err()
+        .unwrap()
+        .to_string()
+        .contains("invalid month day `every Mon`"));
+}
+
+#[test]
+fn test_escalation_representation() {
+    let value = json!({
+        "kind": "yearly",
+        "name": "insurance",
+        "value": "-1200.00",
+        "start": "2023-01-01",
+        "end": "2025-12-31",
+        "skip_years": 0,
+        "escalation": {"steps": [{"date": "2024-01-01", "percent": 8.0}]},
+    });
+    let d: Box<dyn Delta> = serde_json::from_value::<TaggedDelta>(value.clone())
+        .unwrap()
+        .into();
+    assert_eq!(
+        d.amounts_on(&NaiveDate::from_ymd_opt(2025, 1, 1).unwrap())
+            .0,
+        Money::from(-1296)
+    );
+    assert_eq!(serde_json::to_value(d.tagged()).unwrap(), value);
+
+    let bad_index = serde_json::from_value::<TaggedDelta>(json!({
+        "kind": "daily",
+        "name": "coffee",
+        "value": -5.0,
+        "start": "2023-01-01",
+        "end": "2023-12-31",
+        "escalation": {"index": []},
+    }));
+    assert!(bad_index
+        .err()
+        .unwrap()
+        .to_string()
+        .contains("invalid escalation"));
+}
+
+#[test]
+fn test_custom_amounts_representation() {
+    let value = json!({
+        "kind": "custom",
+        "name": "payment plan",
+        "value": "-100.00",
+        "dates": ["2023-01-10"],
+        "amounts": {"2023-02-10": "-250.00", "2023-03-10": "-75.50"},
+    });
+    let d: Box<dyn Delta> = serde_json::from_value::<TaggedDelta>(value.clone())
+        .unwrap()
+        .into();
+    assert_eq!(
+        d.occurrences(
+            &NaiveDate::from_ymd_opt(2023, 1, 1).unwrap(),
+            &NaiveDate::from_ymd_opt(2023, 12, 31).unwrap()
+        )
+        .map(|occurrence| occurrence.value)
+        .collect::<Vec<_>>(),
+        [
+            Money::from(-100),
+            Money::from(-250),
+            Money::from_cents(-7550)
+        ]
+    );
+    assert_eq!(serde_json::to_value(d.tagged()).unwrap(), value);
+
+    let amounts_only: Box<dyn Delta> = serde_json::from_value::<TaggedDelta>(json!({
+        "kind": "custom",
+        "name": "payment plan",
+        "amounts": {"2023-02-10": -250.0},
+    }))
+    .unwrap()
+    .into();
+    assert_eq!(
+        amounts_only.dates().collect::<Vec<_>>(),
+        [NaiveDate::from_ymd_opt(2023, 2, 10).unwrap()]
+    );
+}
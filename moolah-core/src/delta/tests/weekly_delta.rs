@@ -1,5 +1,6 @@
 use super::*;
-use chrono::{Duration, Local, Weekday};
+use crate::calendar::{Calendar, RollConvention};
+use chrono::{Datelike, Duration, Local, Weekday};
 
 #[test]
 fn test_default() {
@@ -7,24 +8,31 @@ fn test_default() {
     let today = Local::now().date_naive();
 
     assert_eq!(d.name(), "");
-    assert_eq!(d.value(), 0.0);
+    assert_eq!(d.value(), Money::from(0));
     assert!(d.uncertainty().is_none());
-    assert_eq!(d.dates(), &[today]);
+    assert_eq!(d.dates().collect::<Vec<_>>(), &[today]);
     assert_eq!(*d.start(), today);
-    assert_eq!(*d.end(), today);
-    assert_eq!(*d.on_weekday(), Weekday::Mon);
+    assert_eq!(d.end(), Some(&today));
+    assert_eq!(*d.on_weekday(), today.weekday());
     assert_eq!(d.skip_weeks(), 0);
 }
 
 #[test]
 fn test_start_cannot_be_later_than_end() {
     let d = NaiveDate::from_ymd_opt(2022, 11, 1).unwrap();
-    assert!(
-        WeeklyDelta::try_new(String::from("test"), 0.0, None, d, d, Some(Weekday::Mon), 0).is_ok()
-    );
     assert!(WeeklyDelta::try_new(
         String::from("test"),
-        0.0,
+        Money::from(0),
+        None,
+        d,
+        d,
+        Some(Weekday::Mon),
+        0
+    )
+    .is_ok());
+    assert!(WeeklyDelta::try_new(
+        String::from("test"),
+        Money::from(0),
         None,
         d,
         d - Duration::days(1),
@@ -40,10 +48,10 @@ fn test_reasonable_bounds() {
 
     assert!(WeeklyDelta::try_new(
         String::from("test"),
-        0.0,
+        Money::from(0),
         Some(Uncertainty::Bounds {
-            low: 0.0,
-            high: 0.0
+            low: Money::from(0),
+            high: Money::from(0)
         }),
         date,
         date,
@@ -54,10 +62,10 @@ fn test_reasonable_bounds() {
 
     assert!(WeeklyDelta::try_new(
         String::from("test"),
-        0.0,
+        Money::from(0),
         Some(Uncertainty::Bounds {
-            low: 0.0,
-            high: 0.1
+            low: Money::from(0),
+            high: Money::from_cents(10)
         }),
         date,
         date,
@@ -68,10 +76,10 @@ fn test_reasonable_bounds() {
 
     assert!(WeeklyDelta::try_new(
         String::from("test"),
-        0.0,
+        Money::from(0),
         Some(Uncertainty::Bounds {
-            low: -0.1,
-            high: 0.0
+            low: Money::from_cents(-10),
+            high: Money::from(0)
         }),
         date,
         date,
@@ -82,10 +90,10 @@ fn test_reasonable_bounds() {
 
     assert!(WeeklyDelta::try_new(
         String::from("test"),
-        0.0,
+        Money::from(0),
         Some(Uncertainty::Bounds {
-            low: -0.1,
-            high: 0.1
+            low: Money::from_cents(-10),
+            high: Money::from_cents(10)
         }),
         date,
         date,
@@ -96,10 +104,10 @@ fn test_reasonable_bounds() {
 
     assert!(WeeklyDelta::try_new(
         String::from("test"),
-        -1.0,
+        Money::from(-1),
         Some(Uncertainty::Bounds {
-            low: -0.1,
-            high: 0.1
+            low: Money::from_cents(-10),
+            high: Money::from_cents(10)
         }),
         date,
         date,
@@ -110,10 +118,10 @@ fn test_reasonable_bounds() {
 
     assert!(WeeklyDelta::try_new(
         String::from("test"),
-        1.0,
+        Money::from(1),
         Some(Uncertainty::Bounds {
-            low: -0.1,
-            high: 0.1
+            low: Money::from_cents(-10),
+            high: Money::from_cents(10)
         }),
         date,
         date,
@@ -124,10 +132,10 @@ fn test_reasonable_bounds() {
 
     assert!(WeeklyDelta::try_new(
         String::from("test"),
-        0.0,
+        Money::from(0),
         Some(Uncertainty::Bounds {
-            low: 0.1,
-            high: -0.1
+            low: Money::from_cents(10),
+            high: Money::from_cents(-10)
         }),
         date,
         date,
@@ -146,7 +154,7 @@ fn test_weekly_dates(
 ) {
     let d = WeeklyDelta::try_new(
         String::from("test"),
-        0.0,
+        Money::from(0),
         None,
         start,
         end,
@@ -155,8 +163,8 @@ fn test_weekly_dates(
     )
     .unwrap();
 
-    let dates = d.dates();
-    dbg!(dates);
+    let dates = d.dates().collect::<Vec<_>>();
+    dbg!(&dates);
     for date in expected_dates.iter() {
         assert!(dates.contains(date), "date = {}", date);
     }
@@ -411,3 +419,56 @@ fn test_falls_after_weekday_skip_weeks_100() {
         &expected_dates,
     );
 }
+
+#[test]
+fn test_rolls_past_holidays() {
+    let d = WeeklyDelta::try_new(
+        String::from("test"),
+        Money::from(100),
+        None,
+        NaiveDate::from_ymd_opt(2023, 1, 1).unwrap(),
+        NaiveDate::from_ymd_opt(2023, 1, 31).unwrap(),
+        Some(Weekday::Mon),
+        0,
+    )
+    .unwrap()
+    .with_roll(RollConvention::Following, Calendar::us_federal());
+
+    assert_eq!(
+        d.dates().collect::<Vec<_>>(),
+        [
+            NaiveDate::from_ymd_opt(2023, 1, 3).unwrap(),
+            NaiveDate::from_ymd_opt(2023, 1, 9).unwrap(),
+            NaiveDate::from_ymd_opt(2023, 1, 17).unwrap(),
+            NaiveDate::from_ymd_opt(2023, 1, 23).unwrap(),
+            NaiveDate::from_ymd_opt(2023, 1, 30).unwrap(),
+        ]
+    );
+}
+
+#[test]
+fn test_setters() {
+    let start = NaiveDate::from_ymd_opt(2023, 1, 2).unwrap();
+    let mut d = WeeklyDelta::try_new(
+        "lessons".into(),
+        Money::from(-40),
+        None,
+        start,
+        NaiveDate::from_ymd_opt(2023, 1, 31).unwrap(),
+        None,
+        0,
+    )
+    .unwrap();
+
+    d.set_on_weekday(Weekday::Thu);
+    d.set_skip_weeks(1);
+    assert_eq!(
+        d.dates().collect::<Vec<_>>(),
+        [
+            NaiveDate::from_ymd_opt(2023, 1, 5).unwrap(),
+            NaiveDate::from_ymd_opt(2023, 1, 19).unwrap()
+        ]
+    );
+    assert!(d.set_end(Some(start - Duration::days(1))).is_err());
+    assert_eq!(d.dates().count(), 2);
+}
@@ -1,4 +1,5 @@
 use super::*;
+use crate::calendar::{Calendar, RollConvention};
 use chrono::{Duration, Local};
 
 #[test]
@@ -7,21 +8,27 @@ fn test_default() {
     let today = Local::now().date_naive();
 
     assert_eq!(d.name(), "");
-    assert_eq!(d.value(), 0.0);
+    assert_eq!(d.value(), Money::from(0));
     assert!(d.uncertainty().is_none());
-    assert_eq!(d.dates(), &[today]);
+    assert_eq!(d.dates().collect::<Vec<_>>(), &[today]);
     assert_eq!(*d.start(), today);
-    assert_eq!(*d.end(), today);
+    assert_eq!(d.end(), Some(&today));
     assert_eq!(d.skip_years(), 0);
 }
 
 #[test]
 fn test_start_cannot_be_later_than_end() {
     let d = NaiveDate::from_ymd_opt(2022, 11, 1).unwrap();
-    assert!(YearlyDelta::try_new(String::from("test"), 0.0, None, d, d, 0).is_ok());
-    assert!(
-        YearlyDelta::try_new(String::from("test"), 0.0, None, d, d - Duration::days(1), 0).is_err()
-    );
+    assert!(YearlyDelta::try_new(String::from("test"), Money::from(0), None, d, d, 0).is_ok());
+    assert!(YearlyDelta::try_new(
+        String::from("test"),
+        Money::from(0),
+        None,
+        d,
+        d - Duration::days(1),
+        0
+    )
+    .is_err());
 }
 
 #[test]
@@ -30,10 +37,10 @@ fn test_reasonable_bounds() {
 
     assert!(YearlyDelta::try_new(
         String::from("test"),
-        0.0,
+        Money::from(0),
         Some(Uncertainty::Bounds {
-            low: 0.0,
-            high: 0.0
+            low: Money::from(0),
+            high: Money::from(0)
         }),
         date,
         date,
@@ -43,10 +50,10 @@ fn test_reasonable_bounds() {
 
     assert!(YearlyDelta::try_new(
         String::from("test"),
-        0.0,
+        Money::from(0),
         Some(Uncertainty::Bounds {
-            low: 0.0,
-            high: 0.1
+            low: Money::from(0),
+            high: Money::from_cents(10)
         }),
         date,
         date,
@@ -56,10 +63,10 @@ fn test_reasonable_bounds() {
 
     assert!(YearlyDelta::try_new(
         String::from("test"),
-        0.0,
+        Money::from(0),
         Some(Uncertainty::Bounds {
-            low: -0.1,
-            high: 0.0
+            low: Money::from_cents(-10),
+            high: Money::from(0)
         }),
         date,
         date,
@@ -69,10 +76,10 @@ fn test_reasonable_bounds() {
 
     assert!(YearlyDelta::try_new(
         String::from("test"),
-        0.0,
+        Money::from(0),
         Some(Uncertainty::Bounds {
-            low: -0.1,
-            high: 0.1
+            low: Money::from_cents(-10),
+            high: Money::from_cents(10)
         }),
         date,
         date,
@@ -82,10 +89,10 @@ fn test_reasonable_bounds() {
 
     assert!(YearlyDelta::try_new(
         String::from("test"),
-        -1.0,
+        Money::from(-1),
         Some(Uncertainty::Bounds {
-            low: -0.1,
-            high: 0.1
+            low: Money::from_cents(-10),
+            high: Money::from_cents(10)
         }),
         date,
         date,
@@ -95,10 +102,10 @@ fn test_reasonable_bounds() {
 
     assert!(YearlyDelta::try_new(
         String::from("test"),
-        1.0,
+        Money::from(1),
         Some(Uncertainty::Bounds {
-            low: -0.1,
-            high: 0.1
+            low: Money::from_cents(-10),
+            high: Money::from_cents(10)
         }),
         date,
         date,
@@ -108,10 +115,10 @@ fn test_reasonable_bounds() {
 
     assert!(YearlyDelta::try_new(
         String::from("test"),
-        0.0,
+        Money::from(0),
         Some(Uncertainty::Bounds {
-            low: 0.1,
-            high: -0.1
+            low: Money::from_cents(10),
+            high: Money::from_cents(-10)
         }),
         date,
         date,
@@ -126,10 +133,18 @@ fn test_yearly_dates(
     skip_years: u16,
     expected_dates: &[NaiveDate],
 ) {
-    let d = YearlyDelta::try_new(String::from("test"), 0.0, None, start, end, skip_years).unwrap();
+    let d = YearlyDelta::try_new(
+        String::from("test"),
+        Money::from(0),
+        None,
+        start,
+        end,
+        skip_years,
+    )
+    .unwrap();
 
-    let dates = d.dates();
-    dbg!(dates);
+    let dates = d.dates().collect::<Vec<_>>();
+    dbg!(&dates);
     for date in expected_dates.iter() {
         assert!(dates.contains(date), "date = {}", date);
     }
@@ -387,3 +402,51 @@ fn test_falls_after_year_day_skip_years_100() {
         &expected_dates,
     );
 }
+
+#[test]
+fn test_rolls_past_holidays() {
+    let d = YearlyDelta::try_new(
+        String::from("test"),
+        Money::from(100),
+        None,
+        NaiveDate::from_ymd_opt(2021, 12, 25).unwrap(),
+        NaiveDate::from_ymd_opt(2023, 12, 31).unwrap(),
+        0,
+    )
+    .unwrap()
+    .with_roll(RollConvention::Preceding, Calendar::us_federal());
+
+    // Christmas is observed on the Friday before in 2021 and the Monday after in 2022
+    assert_eq!(
+        d.dates().collect::<Vec<_>>(),
+        [
+            NaiveDate::from_ymd_opt(2021, 12, 23).unwrap(),
+            NaiveDate::from_ymd_opt(2022, 12, 23).unwrap(),
+            NaiveDate::from_ymd_opt(2023, 12, 22).unwrap(),
+        ]
+    );
+}
+
+#[test]
+fn test_setters() {
+    let start = NaiveDate::from_ymd_opt(2023, 3, 1).unwrap();
+    let mut d =
+        YearlyDelta::try_new("insurance".into(), Money::from(-900), None, start, None, 0).unwrap();
+
+    d.set_skip_years(1);
+    d.set_end(NaiveDate::from_ymd_opt(2027, 12, 31)).unwrap();
+    assert!(!d.is_open_ended());
+    assert_eq!(
+        d.dates().collect::<Vec<_>>(),
+        [
+            start,
+            NaiveDate::from_ymd_opt(2025, 3, 1).unwrap(),
+            NaiveDate::from_ymd_opt(2027, 3, 1).unwrap()
+        ]
+    );
+    assert!(matches!(
+        d.set_start(NaiveDate::from_ymd_opt(2028, 1, 1).unwrap()),
+        Err(MoolahCoreError::StartAfterEnd { .. })
+    ));
+    assert_eq!(*d.start(), start);
+}
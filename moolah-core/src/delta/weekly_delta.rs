@@ -1,16 +1,43 @@
-use super::{reasonable_bounds, Delta, Uncertainty};
-use crate::errors::MoolahCoreError;
+#[cfg(feature = "serde")]
+use super::TaggedDeltaRef;
+use super::{validate_span, Delta, DeltaMut, Escalation, Uncertainty};
+use crate::{
+    calendar::{Calendar, RollConvention},
+    currency::Currency,
+    errors::MoolahCoreError,
+    money::Money,
+};
 use chrono::{Datelike, Duration, Local, NaiveDate, Weekday};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::iter;
 
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(try_from = "RawWeeklyDelta")
+)]
 pub struct WeeklyDelta {
     name: String,
-    value: f64,
+    value: Money,
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
     uncertainty: Option<Uncertainty>,
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    currency: Option<Currency>,
     start: NaiveDate,
-    end: NaiveDate,
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    end: Option<NaiveDate>,
     on_weekday: Weekday,
     skip_weeks: u32,
-    dates: Vec<NaiveDate>,
+    #[cfg_attr(
+        feature = "serde",
+        serde(skip_serializing_if = "RollConvention::is_none")
+    )]
+    roll: RollConvention,
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Calendar::is_default"))]
+    calendar: Calendar,
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    escalation: Option<Escalation>,
 }
 
 impl Default for WeeklyDelta {
@@ -21,11 +48,14 @@ impl Default for WeeklyDelta {
             name: Default::default(),
             value: Default::default(),
             uncertainty: Default::default(),
+            currency: Default::default(),
             start: today,
-            end: today,
-            on_weekday: Weekday::Mon,
+            end: Some(today),
+            on_weekday: today.weekday(),
             skip_weeks: Default::default(),
-            dates: vec![today],
+            roll: Default::default(),
+            calendar: Default::default(),
+            escalation: Default::default(),
         }
     }
 }
@@ -52,36 +82,38 @@ fn round_back_to_prev_weekday(date: &NaiveDate, weekday: &Weekday) -> NaiveDate
     *date - Duration::days(days)
 }
 
-fn build_dates(
+fn build_dates<'a>(
     start: &NaiveDate,
-    end: &NaiveDate,
+    end: Option<&NaiveDate>,
     on_weekday: &Weekday,
     every_weeks: i64,
-) -> Vec<NaiveDate> {
+    roll: &'a RollConvention,
+    calendar: &'a Calendar,
+) -> impl Iterator<Item = NaiveDate> + 'a {
     let start = round_up_to_next_weekday(start, on_weekday);
-    let end = round_back_to_prev_weekday(end, on_weekday);
+    let end = end.map(|end| round_back_to_prev_weekday(end, on_weekday));
     let duration = Duration::weeks(every_weeks);
-    let n_weeks: i32 = ((end - start).num_weeks() / every_weeks)
-        .try_into()
-        .expect("Too many weeks");
-    (0..=n_weeks).map(|date| start + duration * date).collect()
+    let dates = iter::successors(Some(start), move |date| date.checked_add_signed(duration))
+        .take_while(move |date| end.is_none_or(|end| *date <= end));
+    roll.roll_all(dates, calendar)
 }
 
 impl WeeklyDelta {
     pub fn try_new(
         name: String,
-        value: f64,
+        value: Money,
         uncertainty: Option<Uncertainty>,
         start: NaiveDate,
-        end: NaiveDate,
+        end: impl Into<Option<NaiveDate>>,
         on_weekday: Option<Weekday>,
         skip_weeks: u32,
     ) -> Result<Self, MoolahCoreError> {
-        if let Some(Uncertainty::Bounds { low, high }) = uncertainty {
-            reasonable_bounds(low, high, value)?;
+        if let Some(uncertainty) = &uncertainty {
+            uncertainty.validate(value)?;
         }
 
-        if start > end {
+        let end = end.into();
+        if let Some(end) = end.filter(|end| start > *end) {
             return Err(MoolahCoreError::StartAfterEnd { start, end });
         }
 
@@ -94,11 +126,14 @@ impl WeeklyDelta {
             name,
             value,
             uncertainty,
+            currency: None,
             start,
             end,
             on_weekday: weekday,
             skip_weeks,
-            dates: build_dates(&start, &end, &weekday, (skip_weeks + 1).into()),
+            roll: RollConvention::None,
+            calendar: Calendar::default(),
+            escalation: None,
         })
     }
 
@@ -106,8 +141,8 @@ impl WeeklyDelta {
         &self.start
     }
 
-    pub fn end(&self) -> &NaiveDate {
-        &self.end
+    pub fn end(&self) -> Option<&NaiveDate> {
+        self.end.as_ref()
     }
 
     pub fn on_weekday(&self) -> &Weekday {
@@ -117,6 +152,119 @@ impl WeeklyDelta {
     pub fn skip_weeks(&self) -> u32 {
         self.skip_weeks
     }
+
+    pub fn roll(&self) -> &RollConvention {
+        &self.roll
+    }
+
+    pub fn calendar(&self) -> &Calendar {
+        &self.calendar
+    }
+
+    /// Move dates falling on a weekend or one of `calendar`'s holidays to a business day
+    pub fn with_roll(mut self, roll: RollConvention, calendar: Calendar) -> Self {
+        self.roll = roll;
+        self.calendar = calendar;
+        self
+    }
+
+    pub fn escalation(&self) -> Option<&Escalation> {
+        self.escalation.as_ref()
+    }
+
+    pub fn with_escalation(mut self, escalation: Escalation) -> Result<Self, MoolahCoreError> {
+        escalation.validate()?;
+        self.escalation = Some(escalation);
+        Ok(self)
+    }
+
+    pub fn with_currency(mut self, currency: Currency) -> Self {
+        self.currency = Some(currency);
+        self
+    }
+
+    pub fn set_name(&mut self, name: String) {
+        self.name = name;
+    }
+
+    pub fn set_value(&mut self, value: Money) -> Result<(), MoolahCoreError> {
+        super::set_value(&mut self.value, &self.uncertainty, value)
+    }
+
+    pub fn set_uncertainty(
+        &mut self,
+        uncertainty: Option<Uncertainty>,
+    ) -> Result<(), MoolahCoreError> {
+        super::set_uncertainty(&mut self.uncertainty, [self.value], uncertainty)
+    }
+
+    pub fn set_currency(&mut self, currency: Option<Currency>) {
+        self.currency = currency;
+    }
+
+    pub fn set_start(&mut self, start: NaiveDate) -> Result<(), MoolahCoreError> {
+        validate_span(start, self.end)?;
+        self.start = start;
+        Ok(())
+    }
+
+    /// `None` to recur without an end
+    pub fn set_end(&mut self, end: Option<NaiveDate>) -> Result<(), MoolahCoreError> {
+        validate_span(self.start, end)?;
+        self.end = end;
+        Ok(())
+    }
+
+    pub fn set_on_weekday(&mut self, on_weekday: Weekday) {
+        self.on_weekday = on_weekday;
+    }
+
+    pub fn set_skip_weeks(&mut self, skip_weeks: u32) {
+        self.skip_weeks = skip_weeks;
+    }
+}
+
+#[cfg(feature = "serde")]
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+pub(crate) struct RawWeeklyDelta {
+    name: String,
+    value: Money,
+    uncertainty: Option<Uncertainty>,
+    currency: Option<Currency>,
+    start: NaiveDate,
+    end: Option<NaiveDate>,
+    on_weekday: Option<Weekday>,
+    #[serde(default)]
+    skip_weeks: u32,
+    #[serde(default)]
+    roll: RollConvention,
+    #[serde(default)]
+    calendar: Calendar,
+    escalation: Option<Escalation>,
+}
+
+#[cfg(feature = "serde")]
+impl TryFrom<RawWeeklyDelta> for WeeklyDelta {
+    type Error = MoolahCoreError;
+
+    fn try_from(raw: RawWeeklyDelta) -> Result<Self, Self::Error> {
+        let mut delta = WeeklyDelta::try_new(
+            raw.name,
+            raw.value,
+            raw.uncertainty,
+            raw.start,
+            raw.end,
+            raw.on_weekday,
+            raw.skip_weeks,
+        )?
+        .with_roll(raw.roll, raw.calendar);
+        delta.currency = raw.currency;
+        match raw.escalation {
+            Some(escalation) => delta.with_escalation(escalation),
+            None => Ok(delta),
+        }
+    }
 }
 
 impl Delta for WeeklyDelta {
@@ -124,7 +272,7 @@ impl Delta for WeeklyDelta {
         &self.name
     }
 
-    fn value(&self) -> f64 {
+    fn value(&self) -> Money {
         self.value
     }
 
@@ -132,8 +280,38 @@ impl Delta for WeeklyDelta {
         &self.uncertainty
     }
 
-    fn dates(&self) -> &[NaiveDate] {
-        &self.dates
+    fn currency(&self) -> Option<&Currency> {
+        self.currency.as_ref()
+    }
+
+    fn dates(&self) -> Box<dyn Iterator<Item = NaiveDate> + '_> {
+        Box::new(build_dates(
+            &self.start,
+            self.end.as_ref(),
+            &self.on_weekday,
+            (self.skip_weeks + 1).into(),
+            &self.roll,
+            &self.calendar,
+        ))
+    }
+
+    fn is_open_ended(&self) -> bool {
+        self.end.is_none()
+    }
+
+    fn escalation_factor(&self, date: &NaiveDate) -> f64 {
+        self.escalation
+            .as_ref()
+            .map_or(1.0, |escalation| escalation.factor(&self.start, date))
+    }
+
+    #[cfg(feature = "serde")]
+    fn tagged(&self) -> TaggedDeltaRef<'_> {
+        TaggedDeltaRef::Weekly(self)
+    }
+
+    fn edit(&mut self) -> Option<DeltaMut<'_>> {
+        Some(DeltaMut::Weekly(self))
     }
 }
 
@@ -1,18 +1,44 @@
-use super::{reasonable_bounds, Delta, Uncertainty};
-use crate::errors::MoolahCoreError;
+#[cfg(feature = "serde")]
+use super::TaggedDeltaRef;
+use super::{validate_span, Delta, DeltaMut, Escalation, Uncertainty};
+use crate::{
+    calendar::{Calendar, RollConvention},
+    currency::Currency,
+    errors::MoolahCoreError,
+    money::Money,
+};
 use add_years::MultiYearDuration;
-use chrono::{Datelike, Local, NaiveDate};
+use chrono::{Local, NaiveDate};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
 
 mod add_years;
 
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(try_from = "RawYearlyDelta")
+)]
 pub struct YearlyDelta {
     name: String,
-    value: f64,
+    value: Money,
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
     uncertainty: Option<Uncertainty>,
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    currency: Option<Currency>,
     start: NaiveDate,
-    end: NaiveDate,
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    end: Option<NaiveDate>,
     skip_years: u16,
-    dates: Vec<NaiveDate>,
+    #[cfg_attr(
+        feature = "serde",
+        serde(skip_serializing_if = "RollConvention::is_none")
+    )]
+    roll: RollConvention,
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Calendar::is_default"))]
+    calendar: Calendar,
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    escalation: Option<Escalation>,
 }
 
 impl Default for YearlyDelta {
@@ -23,48 +49,49 @@ impl Default for YearlyDelta {
             name: Default::default(),
             value: Default::default(),
             uncertainty: Default::default(),
+            currency: Default::default(),
             start: today,
-            end: today,
+            end: Some(today),
             skip_years: Default::default(),
-            dates: vec![today],
+            roll: Default::default(),
+            calendar: Default::default(),
+            escalation: Default::default(),
         }
     }
 }
 
-fn build_dates(
-    start: &NaiveDate,
-    end: &NaiveDate,
+fn build_dates<'a>(
+    start: NaiveDate,
+    end: Option<NaiveDate>,
     every_years: u16,
-) -> Result<Vec<NaiveDate>, MoolahCoreError> {
-    let date_offset = if (end.month() < start.month())
-        | ((end.month() == start.month()) & (end.day() < start.day()))
-    {
-        -1
-    } else {
-        0
-    };
-    let n_years = (end.year() - start.year() + date_offset) / i32::from(every_years);
-    let n_years: u32 = n_years.try_into().unwrap_or_default();
-
-    (0..=n_years)
-        .map(|year_num| MultiYearDuration::new(every_years as u32 * year_num).try_add(*start))
-        .collect()
+    roll: &'a RollConvention,
+    calendar: &'a Calendar,
+) -> impl Iterator<Item = NaiveDate> + 'a {
+    let dates = (0..)
+        .map_while(move |year_num| {
+            MultiYearDuration::new(u32::from(every_years) * year_num)
+                .try_add(start)
+                .ok()
+        })
+        .take_while(move |date| end.is_none_or(|end| *date <= end));
+    roll.roll_all(dates, calendar)
 }
 
 impl YearlyDelta {
     pub fn try_new(
         name: String,
-        value: f64,
+        value: Money,
         uncertainty: Option<Uncertainty>,
         start: NaiveDate,
-        end: NaiveDate,
+        end: impl Into<Option<NaiveDate>>,
         skip_years: u16,
     ) -> Result<Self, MoolahCoreError> {
-        if let Some(Uncertainty::Bounds { low, high }) = uncertainty {
-            reasonable_bounds(low, high, value)?;
+        if let Some(uncertainty) = &uncertainty {
+            uncertainty.validate(value)?;
         }
 
-        if start > end {
+        let end = end.into();
+        if let Some(end) = end.filter(|end| start > *end) {
             return Err(MoolahCoreError::StartAfterEnd { start, end });
         }
 
@@ -72,10 +99,13 @@ impl YearlyDelta {
             name,
             value,
             uncertainty,
+            currency: None,
             start,
             end,
             skip_years,
-            dates: build_dates(&start, &end, skip_years + 1)?,
+            roll: RollConvention::None,
+            calendar: Calendar::default(),
+            escalation: None,
         })
     }
 
@@ -83,13 +113,120 @@ impl YearlyDelta {
         &self.start
     }
 
-    pub fn end(&self) -> &NaiveDate {
-        &self.end
+    pub fn end(&self) -> Option<&NaiveDate> {
+        self.end.as_ref()
     }
 
     pub fn skip_years(&self) -> u16 {
         self.skip_years
     }
+
+    pub fn roll(&self) -> &RollConvention {
+        &self.roll
+    }
+
+    pub fn calendar(&self) -> &Calendar {
+        &self.calendar
+    }
+
+    /// Move dates falling on a weekend or one of `calendar`'s holidays to a business day
+    pub fn with_roll(mut self, roll: RollConvention, calendar: Calendar) -> Self {
+        self.roll = roll;
+        self.calendar = calendar;
+        self
+    }
+
+    pub fn escalation(&self) -> Option<&Escalation> {
+        self.escalation.as_ref()
+    }
+
+    pub fn with_escalation(mut self, escalation: Escalation) -> Result<Self, MoolahCoreError> {
+        escalation.validate()?;
+        self.escalation = Some(escalation);
+        Ok(self)
+    }
+
+    pub fn with_currency(mut self, currency: Currency) -> Self {
+        self.currency = Some(currency);
+        self
+    }
+
+    pub fn set_name(&mut self, name: String) {
+        self.name = name;
+    }
+
+    pub fn set_value(&mut self, value: Money) -> Result<(), MoolahCoreError> {
+        super::set_value(&mut self.value, &self.uncertainty, value)
+    }
+
+    pub fn set_uncertainty(
+        &mut self,
+        uncertainty: Option<Uncertainty>,
+    ) -> Result<(), MoolahCoreError> {
+        super::set_uncertainty(&mut self.uncertainty, [self.value], uncertainty)
+    }
+
+    pub fn set_currency(&mut self, currency: Option<Currency>) {
+        self.currency = currency;
+    }
+
+    pub fn set_start(&mut self, start: NaiveDate) -> Result<(), MoolahCoreError> {
+        validate_span(start, self.end)?;
+        self.start = start;
+        Ok(())
+    }
+
+    /// `None` to recur without an end
+    pub fn set_end(&mut self, end: Option<NaiveDate>) -> Result<(), MoolahCoreError> {
+        validate_span(self.start, end)?;
+        self.end = end;
+        Ok(())
+    }
+
+    pub fn set_skip_years(&mut self, skip_years: u16) {
+        self.skip_years = skip_years;
+    }
+}
+
+#[cfg(feature = "serde")]
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+pub(crate) struct RawYearlyDelta {
+    name: String,
+    value: Money,
+    uncertainty: Option<Uncertainty>,
+    currency: Option<Currency>,
+    start: NaiveDate,
+    end: Option<NaiveDate>,
+    #[serde(default)]
+    skip_years: u16,
+    #[serde(default)]
+    roll: RollConvention,
+    #[serde(default)]
+    calendar: Calendar,
+    escalation: Option<Escalation>,
+}
+
+#[cfg(feature = "serde")]
+impl TryFrom<RawYearlyDelta> for YearlyDelta {
+    type Error = MoolahCoreError;
+
+    fn try_from(raw: RawYearlyDelta) -> Result<Self, Self::Error> {
+        let mut delta = YearlyDelta::try_new(
+            raw.name,
+            raw.value,
+            raw.uncertainty,
+            raw.start,
+            raw.end,
+            raw.skip_years,
+        )?
+        .with_roll(raw.roll, raw.calendar);
+        delta.currency = raw.currency;
+        match raw.escalation {
+            Some(escalation) => delta.with_escalation(escalation),
+            None => Ok(delta),
+        }
+    }
 }
 
 impl Delta for YearlyDelta {
@@ -97,7 +234,7 @@ impl Delta for YearlyDelta {
         &self.name
     }
 
-    fn value(&self) -> f64 {
+    fn value(&self) -> Money {
         self.value
     }
 
@@ -105,7 +242,36 @@ impl Delta for YearlyDelta {
         &self.uncertainty
     }
 
-    fn dates(&self) -> &[NaiveDate] {
-        &self.dates
+    fn currency(&self) -> Option<&Currency> {
+        self.currency.as_ref()
+    }
+
+    fn dates(&self) -> Box<dyn Iterator<Item = NaiveDate> + '_> {
+        Box::new(build_dates(
+            self.start,
+            self.end,
+            self.skip_years + 1,
+            &self.roll,
+            &self.calendar,
+        ))
+    }
+
+    fn is_open_ended(&self) -> bool {
+        self.end.is_none()
+    }
+
+    fn escalation_factor(&self, date: &NaiveDate) -> f64 {
+        self.escalation
+            .as_ref()
+            .map_or(1.0, |escalation| escalation.factor(&self.start, date))
+    }
+
+    #[cfg(feature = "serde")]
+    fn tagged(&self) -> TaggedDeltaRef<'_> {
+        TaggedDeltaRef::Yearly(self)
+    }
+
+    fn edit(&mut self) -> Option<DeltaMut<'_>> {
+        Some(DeltaMut::Yearly(self))
     }
 }
@@ -1,3 +1,4 @@
+use crate::{currency::Currency, money::Money};
 use chrono::NaiveDate;
 use thiserror::Error;
 
@@ -6,15 +7,198 @@ pub enum MoolahCoreError {
     #[error("cannot create a `PositiveF64` from negative value {0}")]
     UnexpectedNegative(f64),
 
+    #[error("cannot create a `PositiveMoney` from negative amount {0}")]
+    UnexpectedNegativeMoney(Money),
+
+    #[error("invalid amount of money `{0}`")]
+    InvalidMoney(String),
+
+    #[error("amount of money is too large to represent")]
+    MoneyOverflow,
+
     #[error("illogical bounded uncertainty [{low}, {high}] for value {value}")]
-    IllogicalUncertaintyBounds { low: f64, high: f64, value: f64 },
+    IllogicalUncertaintyBounds {
+        low: Money,
+        high: Money,
+        value: Money,
+    },
+
+    #[error("lognormal sigma {0} must be finite")]
+    InvalidSigma(f64),
+
+    #[error("an empirical distribution needs at least one amount")]
+    EmptyDistribution,
+
+    #[error("quantiles [{low}, {high}] must be in order and strictly between 0 and 1")]
+    InvalidQuantiles { low: f64, high: f64 },
+
+    #[error("interest rate {0} must be finite")]
+    InvalidInterestRate(f64),
+
+    #[error("rate {rate} is not within its bounds {low} to {high}")]
+    IllogicalRateBounds { low: f64, high: f64, rate: f64 },
+
+    #[error("invalid escalation: {0}")]
+    InvalidEscalation(String),
+
+    #[error("loan term of {0} months is not possible")]
+    InvalidLoanTerm(u32),
+
+    #[error("checkpoint weight {0} must be above 0 and at most 1")]
+    InvalidCheckpointWeight(f64),
+
+    #[error("a simulation needs at least one trial")]
+    NoTrials,
+
+    #[error("percentile {0} must be in range [0, 100]")]
+    PercentileOutOfRange(f64),
+
+    #[error("invalid RRULE `{rule}`: {reason}")]
+    InvalidRRule { rule: String, reason: String },
 
     #[error("start ({start}) cannot be after end ({end})")]
     StartAfterEnd { start: NaiveDate, end: NaiveDate },
 
-    #[error("month day `{0}` must be in range [1, 31]")]
+    #[error("month day `{0}` must be in range [1, 31], or [-31, -1] counting from month end")]
     MonthDayOutOfRange(i64),
 
+    #[error("invalid month day `{0}`, expected a day such as `15` or `-1`, or a rule such as `first Mon`")]
+    InvalidMonthDay(String),
+
+    #[error("a monthly delta needs at least one month day")]
+    NoMonthDays,
+
     #[error("tried to form invalid date `{0}`")]
     InvalidDate(String),
+
+    #[error("invalid currency code `{0}`, expected three uppercase letters")]
+    InvalidCurrencyCode(String),
+
+    #[error("exchange rate {0} must be finite and positive")]
+    InvalidExchangeRate(f64),
+
+    #[error("dated exchange rates need at least one rate")]
+    EmptyRateSchedule,
+
+    #[error("exchange rate from {from} to {to} needs either a `rate` or dated `rates`")]
+    MalformedExchangeRate { from: Currency, to: Currency },
+
+    #[error("no exchange rate from {from} to {to} on {date}")]
+    MissingExchangeRate {
+        from: Currency,
+        to: Currency,
+        date: NaiveDate,
+    },
+
+    #[error("delta `{delta}` is in {currency} but the prediction has no currency to convert into")]
+    NoReportingCurrency { delta: String, currency: Currency },
+
+    #[error(
+        "account `{account}` is in {currency} but the portfolio has no currency to convert into"
+    )]
+    NoPortfolioCurrency { account: String, currency: Currency },
+
+    #[error("more than one account is named `{0}`")]
+    DuplicateAccount(String),
+
+    #[error("no account named `{0}`")]
+    UnknownAccount(String),
+
+    #[error("account `{0}` cannot transfer to itself")]
+    TransferToSameAccount(String),
+
+    #[error("transfer `{0}` depends on the balance, so it has no amount to move")]
+    BalanceDependentTransfer(String),
+
+    #[error("no delta with id `{0}`")]
+    UnknownDelta(String),
+
+    #[error("more than one delta has id `{0}`")]
+    DuplicateDeltaId(String),
+
+    #[error("a delta id cannot be empty")]
+    EmptyDeltaId,
+
+    #[error("scale factor {0} must be finite and not negative")]
+    InvalidScaleFactor(f64),
+
+    #[error("malformed delta: {0}")]
+    MalformedDelta(String),
+
+    #[error("scenario file line {line}, column {column}: {message}")]
+    ScenarioFileSyntax {
+        line: usize,
+        column: usize,
+        message: String,
+    },
+
+    #[error("invalid delta `{name}` at scenario file line {line}, column {column}")]
+    ScenarioFileDelta {
+        name: String,
+        line: usize,
+        column: usize,
+        #[source]
+        source: Box<MoolahCoreError>,
+    },
+
+    #[error("invalid exchange rate at scenario file line {line}, column {column}")]
+    ScenarioFileExchangeRate {
+        line: usize,
+        column: usize,
+        #[source]
+        source: Box<MoolahCoreError>,
+    },
+
+    #[error("could not write scenario file: {0}")]
+    ScenarioFileWrite(String),
+
+    #[error("could not read CSV: {0}")]
+    CsvRead(String),
+
+    #[error("CSV line {line}: {message}")]
+    CsvSyntax { line: usize, message: String },
+
+    #[error("CSV line {line} has no `{column}` column")]
+    CsvMissingColumn { line: usize, column: String },
+
+    #[error("CSV line {line}: `{value}` is not a date in the format `{format}`")]
+    CsvInvalidDate {
+        line: usize,
+        value: String,
+        format: String,
+    },
+
+    #[error("CSV line {line}: `{value}` is not an amount")]
+    CsvInvalidAmount { line: usize, value: String },
+
+    #[error("malformed OFX: {0}")]
+    OfxSyntax(String),
+
+    #[error("OFX <{aggregate}> has no <{tag}>")]
+    OfxMissingField { aggregate: String, tag: String },
+
+    #[error("OFX <{tag}> has invalid value `{value}`")]
+    OfxInvalidField { tag: String, value: String },
+
+    #[error("QIF line {line}: unsupported account type `{kind}`")]
+    QifUnsupportedType { line: usize, kind: String },
+
+    #[error("QIF line {line}: transaction has no `{field}` field")]
+    QifMissingField { line: usize, field: char },
+
+    #[error("QIF line {line}: `{value}` is not a valid `{field}` field")]
+    QifInvalidField {
+        line: usize,
+        field: char,
+        value: String,
+    },
+
+    #[error("a Beancount journal needs the prediction to have a currency")]
+    JournalMissingCurrency,
+
+    #[error("journal line {line}: {message}")]
+    JournalSyntax { line: usize, message: String },
+
+    #[error("journal line {line}: unsupported period `{period}`")]
+    JournalUnsupportedPeriod { line: usize, period: String },
 }
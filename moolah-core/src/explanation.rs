@@ -0,0 +1,150 @@
+#[cfg(test)]
+mod tests;
+
+use crate::{
+    errors::MoolahCoreError,
+    money::Money,
+    prediction::{accumulate, Prediction, PredictionState, Valuation},
+};
+use chrono::NaiveDate;
+use std::collections::BTreeMap;
+
+/// What one delta added on a date, in the prediction's currency
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Contribution<'a> {
+    /// Position of the delta in [`Prediction::deltas`]
+    pub delta: usize,
+    pub id: &'a str,
+    pub name: &'a str,
+    pub value: Money,
+    pub min: Money,
+    pub max: Money,
+}
+
+/// A prediction's timeline alongside what each delta contributed to it
+#[derive(Debug, Clone, PartialEq)]
+pub struct Explanation<'a> {
+    timeline: BTreeMap<NaiveDate, PredictionState>,
+    contributions: BTreeMap<NaiveDate, Vec<Contribution<'a>>>,
+}
+
+impl<'a> Explanation<'a> {
+    /// The same timeline as [`Prediction::predict`]
+    pub fn timeline(&self) -> &BTreeMap<NaiveDate, PredictionState> {
+        &self.timeline
+    }
+
+    /// Every date's contributions, in the order of the prediction's deltas
+    pub fn contributions(&self) -> &BTreeMap<NaiveDate, Vec<Contribution<'a>>> {
+        &self.contributions
+    }
+
+    /// The contributions on `date`, if any delta landed then
+    pub fn on(&self, date: &NaiveDate) -> &[Contribution<'a>] {
+        self.contributions.get(date).map_or(&[], Vec::as_slice)
+    }
+
+    /// How much each delta paid in and out from `start` to `end` inclusive, and its share of all
+    /// inflows and outflows in that time
+    pub fn attribution(&self, start: &NaiveDate, end: &NaiveDate) -> Attribution<'a> {
+        let mut deltas: BTreeMap<usize, Attributed<'a>> = BTreeMap::new();
+        if start <= end {
+            for contribution in self.contributions.range(start..=end).flat_map(|(_, c)| c) {
+                let attributed = deltas
+                    .entry(contribution.delta)
+                    .or_insert_with(|| Attributed {
+                        delta: contribution.delta,
+                        id: contribution.id,
+                        name: contribution.name,
+                        inflow: Money::ZERO,
+                        outflow: Money::ZERO,
+                    });
+                if contribution.value.is_negative() {
+                    attributed.outflow += contribution.value.abs();
+                } else {
+                    attributed.inflow += contribution.value;
+                }
+            }
+        }
+
+        let deltas: Vec<_> = deltas.into_values().collect();
+        Attribution {
+            inflows: deltas.iter().map(|attributed| attributed.inflow).sum(),
+            outflows: deltas.iter().map(|attributed| attributed.outflow).sum(),
+            deltas,
+        }
+    }
+}
+
+/// What one delta paid in and out over a period. Outflows are positive amounts.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Attributed<'a> {
+    pub delta: usize,
+    pub id: &'a str,
+    pub name: &'a str,
+    pub inflow: Money,
+    pub outflow: Money,
+}
+
+/// Every delta's part in a period's inflows and outflows
+#[derive(Debug, Clone, PartialEq)]
+pub struct Attribution<'a> {
+    deltas: Vec<Attributed<'a>>,
+    inflows: Money,
+    outflows: Money,
+}
+
+impl<'a> Attribution<'a> {
+    /// The deltas that landed in the period, in the order of the prediction's deltas
+    pub fn deltas(&self) -> &[Attributed<'a>] {
+        &self.deltas
+    }
+
+    pub fn inflows(&self) -> Money {
+        self.inflows
+    }
+
+    /// All outflows as a positive amount
+    pub fn outflows(&self) -> Money {
+        self.outflows
+    }
+
+    /// The percent of the period's inflows that came from `attributed`, or 0 without any
+    pub fn percent_of_inflows(&self, attributed: &Attributed) -> f64 {
+        percent(attributed.inflow, self.inflows)
+    }
+
+    /// The percent of the period's outflows that went to `attributed`, e.g. rent's 41% of
+    /// spending, or 0 without any
+    pub fn percent_of_outflows(&self, attributed: &Attributed) -> f64 {
+        percent(attributed.outflow, self.outflows)
+    }
+}
+
+fn percent(part: Money, whole: Money) -> f64 {
+    if whole == Money::ZERO {
+        0.0
+    } else {
+        part.to_f64() / whole.to_f64() * 100.0
+    }
+}
+
+impl Prediction {
+    /// Predict to `end` like [`Prediction::predict`], keeping what each delta added on each date.
+    /// Amounts are converted into the prediction's currency and balance-dependent deltas are
+    /// evaluated as they land, so the contributions on a date add up to its change in value
+    /// before any checkpoint.
+    pub fn explain(&self, end: &NaiveDate) -> Result<Explanation<'_>, MoolahCoreError> {
+        let mut aggregation = self.aggregate_deltas(end)?;
+        let timeline = accumulate(
+            self.initial_value(),
+            self.checkpoints(),
+            &mut aggregation,
+            &mut Valuation::Expected,
+        )?;
+        Ok(Explanation {
+            timeline,
+            contributions: aggregation.contributions,
+        })
+    }
+}
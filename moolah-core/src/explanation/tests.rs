@@ -0,0 +1,124 @@
+use super::*;
+use crate::{
+    date_helpers::ymd,
+    delta::{Delta, InterestDelta, MonthlyDelta, OneTimeDelta, Uncertainty, UncertaintyType},
+};
+
+fn prediction() -> Prediction {
+    let monthly = |name: &str, value: i32, day: i64, uncertainty| {
+        MonthlyDelta::try_new(
+            name.into(),
+            Money::from(value),
+            uncertainty,
+            ymd(2023, 1, 1),
+            None,
+            day.try_into().unwrap(),
+            0,
+        )
+        .unwrap()
+    };
+    let bonus = |value: i32| {
+        OneTimeDelta::try_new("bonus".into(), Money::from(value), None, ymd(2023, 1, 20)).unwrap()
+    };
+    let interest = InterestDelta::try_new(
+        "interest".into(),
+        5.0,
+        None,
+        Default::default(),
+        ymd(2023, 1, 1),
+        ymd(2023, 12, 31),
+    )
+    .unwrap();
+
+    let deltas: Vec<Box<dyn Delta>> = vec![
+        Box::new(monthly("rent", -1000, 1, None)),
+        Box::new(monthly(
+            "groceries",
+            -400,
+            15,
+            Some(Uncertainty::Balanced(UncertaintyType::Percent(
+                10.0.try_into().unwrap(),
+            ))),
+        )),
+        Box::new(bonus(500)),
+        Box::new(bonus(250)),
+        Box::new(interest),
+    ];
+    Prediction::new("test".into(), ymd(2023, 1, 1), Money::from(10000), deltas)
+}
+
+#[test]
+fn test_contributions() {
+    let p = prediction();
+    let end = ymd(2023, 3, 31);
+    let explanation = p.explain(&end).unwrap();
+    assert_eq!(explanation.timeline(), &p.predict(&end).unwrap());
+
+    assert_eq!(
+        explanation.on(&ymd(2023, 1, 15)),
+        [Contribution {
+            delta: 1,
+            id: "groceries",
+            name: "groceries",
+            value: Money::from(-400),
+            min: Money::from(-440),
+            max: Money::from(-360),
+        }]
+    );
+
+    // Deltas that share a name stay apart
+    let bonuses: Vec<_> = explanation
+        .on(&ymd(2023, 1, 20))
+        .iter()
+        .map(|contribution| (contribution.delta, contribution.id, contribution.value))
+        .collect();
+    assert_eq!(
+        bonuses,
+        [
+            (2, "bonus", Money::from(500)),
+            (3, "bonus-2", Money::from(250))
+        ]
+    );
+    assert!(explanation.on(&ymd(2023, 1, 16)).is_empty());
+
+    // Contributions add up to each date's change in value, interest included
+    let mut previous = p.initial_value();
+    for (date, state) in explanation.timeline() {
+        let total: Money = explanation.on(date).iter().map(|c| c.value).sum();
+        assert_eq!(total, state.value() - previous, "on {date}");
+        previous = state.value();
+    }
+    assert!(explanation
+        .contributions()
+        .values()
+        .flatten()
+        .any(|contribution| contribution.name == "interest"));
+}
+
+#[test]
+fn test_attribution() {
+    let p = prediction();
+    let explanation = p.explain(&ymd(2023, 12, 31)).unwrap();
+    let attribution = explanation.attribution(&ymd(2023, 1, 1), &ymd(2023, 3, 31));
+
+    // Three months of rent and groceries
+    assert_eq!(attribution.outflows(), Money::from(4200));
+    let rent = attribution.deltas()[0];
+    assert_eq!((rent.name, rent.outflow), ("rent", Money::from(3000)));
+    assert!((attribution.percent_of_outflows(&rent) - 300.0 / 4.2).abs() < 1e-9);
+
+    let bonuses: Vec<_> = attribution
+        .deltas()
+        .iter()
+        .filter(|attributed| attributed.name == "bonus")
+        .map(|attributed| attributed.inflow)
+        .collect();
+    assert_eq!(bonuses, [Money::from(500), Money::from(250)]);
+    let interest = attribution.deltas().last().unwrap();
+    assert_eq!(interest.name, "interest");
+    assert_eq!(attribution.inflows(), Money::from(750) + interest.inflow);
+
+    let empty = explanation.attribution(&ymd(2023, 3, 31), &ymd(2023, 3, 1));
+    assert!(empty.deltas().is_empty());
+    assert_eq!(empty.outflows(), Money::ZERO);
+}
@@ -0,0 +1,74 @@
+//! Reading bank and card statements into [`Transaction`]s
+
+pub mod csv;
+pub mod ofx;
+pub mod qif;
+
+use crate::{
+    currency::Currency,
+    delta::{Delta, OneTimeDelta},
+    errors::MoolahCoreError,
+    prediction::{Checkpoint, Prediction},
+    transaction::{Ledger, Transaction},
+};
+
+/// One account's transactions, and its balance when the statement was made if the statement
+/// says
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Statement {
+    account: Option<String>,
+    currency: Option<Currency>,
+    balance: Option<Checkpoint>,
+    transactions: Vec<Transaction>,
+}
+
+impl Statement {
+    pub fn account(&self) -> Option<&str> {
+        self.account.as_deref()
+    }
+
+    pub fn currency(&self) -> Option<&Currency> {
+        self.currency.as_ref()
+    }
+
+    /// The ledger balance at the end of its date
+    pub fn balance(&self) -> Option<&Checkpoint> {
+        self.balance.as_ref()
+    }
+
+    /// The transactions in statement order
+    pub fn transactions(&self) -> &[Transaction] {
+        &self.transactions
+    }
+
+    pub fn into_ledger(self) -> Ledger {
+        Ledger::new(self.transactions)
+    }
+
+    /// Every transaction as a one-time delta named after its description
+    pub fn deltas(&self) -> Result<Vec<OneTimeDelta>, MoolahCoreError> {
+        self.transactions.iter().map(one_time_delta).collect()
+    }
+
+    /// A prediction starting from the statement's balance, in its currency. It starts the day
+    /// after the balance's date, since the balance already includes that date's transactions.
+    /// `None` when the statement has no balance.
+    pub fn prediction(&self, name: String, deltas: Vec<Box<dyn Delta>>) -> Option<Prediction> {
+        let balance = self.balance.as_ref()?;
+        let start = balance.date().succ_opt()?;
+        let prediction = Prediction::new(name, start, balance.balance(), deltas);
+        Some(match self.currency {
+            Some(currency) => prediction.with_currency(currency),
+            None => prediction,
+        })
+    }
+}
+
+fn one_time_delta(transaction: &Transaction) -> Result<OneTimeDelta, MoolahCoreError> {
+    OneTimeDelta::try_new(
+        transaction.description().into(),
+        transaction.amount(),
+        None,
+        *transaction.date(),
+    )
+}
@@ -0,0 +1,414 @@
+#[cfg(test)]
+mod tests;
+
+use super::one_time_delta;
+use crate::{
+    delta::OneTimeDelta,
+    errors::MoolahCoreError,
+    money::Money,
+    transaction::{Ledger, Transaction},
+};
+use chrono::NaiveDate;
+use std::{fmt, io};
+
+/// Where a field sits in each row
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Column {
+    /// Zero-based position in the row
+    Index(usize),
+    /// Header name, matched ignoring case and surrounding whitespace
+    Name(String),
+}
+
+impl From<usize> for Column {
+    fn from(index: usize) -> Self {
+        Column::Index(index)
+    }
+}
+
+impl From<&str> for Column {
+    fn from(name: &str) -> Self {
+        Column::Name(name.into())
+    }
+}
+
+impl fmt::Display for Column {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Column::Index(index) => write!(f, "{index}"),
+            Column::Name(name) => write!(f, "{name}"),
+        }
+    }
+}
+
+/// How a row says how much money moved, and in which direction
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Amount {
+    /// One column, negative when money went out
+    Signed(Column),
+    /// One column, positive when money went out, as on many credit card statements
+    Inverted(Column),
+    /// A column for money out and a column for money in, either of which may be blank
+    DebitCredit { debit: Column, credit: Column },
+    /// One column of amounts, and another that reads `debit` (e.g. `DR`) when money went out
+    Indicator {
+        amount: Column,
+        indicator: Column,
+        debit: String,
+    },
+}
+
+/// The layout of a CSV export, used to read it into transactions
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CsvFormat {
+    date: Column,
+    description: Column,
+    amount: Amount,
+    category: Option<Column>,
+    date_format: String,
+    delimiter: u8,
+    has_headers: bool,
+    decimal_comma: bool,
+}
+
+impl CsvFormat {
+    /// A comma separated layout with a header row and ISO 8601 (`%Y-%m-%d`) dates
+    pub fn new(date: Column, description: Column, amount: Amount) -> Self {
+        CsvFormat {
+            date,
+            description,
+            amount,
+            category: None,
+            date_format: "%Y-%m-%d".into(),
+            delimiter: b',',
+            has_headers: true,
+            decimal_comma: false,
+        }
+    }
+
+    /// `Date,Description,Amount` with ISO 8601 dates and negative amounts for money out
+    pub fn simple() -> Self {
+        CsvFormat::new(
+            "Date".into(),
+            "Description".into(),
+            Amount::Signed("Amount".into()),
+        )
+    }
+
+    /// `Date,Description,Debit,Credit` with day-first dates such as `31/01/2023`
+    pub fn debit_credit() -> Self {
+        CsvFormat::new(
+            "Date".into(),
+            "Description".into(),
+            Amount::DebitCredit {
+                debit: "Debit".into(),
+                credit: "Credit".into(),
+            },
+        )
+        .with_date_format("%d/%m/%Y")
+    }
+
+    /// `Transaction Date,Description,Category,Amount` with month-first dates such as
+    /// `01/31/2023` and positive amounts for charges
+    pub fn credit_card() -> Self {
+        CsvFormat::new(
+            "Transaction Date".into(),
+            "Description".into(),
+            Amount::Inverted("Amount".into()),
+        )
+        .with_category("Category".into())
+        .with_date_format("%m/%d/%Y")
+    }
+
+    /// `Date;Description;Amount` with dates such as `31.01.2023` and amounts such as `-1.234,56`
+    pub fn semicolon() -> Self {
+        CsvFormat::simple()
+            .with_delimiter(b';')
+            .with_date_format("%d.%m.%Y")
+            .with_decimal_comma()
+    }
+
+    pub fn with_category(mut self, category: Column) -> Self {
+        self.category = Some(category);
+        self
+    }
+
+    /// A [`chrono` format string](chrono::format::strftime) for the date column
+    pub fn with_date_format(mut self, date_format: impl Into<String>) -> Self {
+        self.date_format = date_format.into();
+        self
+    }
+
+    pub fn with_delimiter(mut self, delimiter: u8) -> Self {
+        self.delimiter = delimiter;
+        self
+    }
+
+    /// The first line is a row, so every column must be a [`Column::Index`]
+    pub fn without_headers(mut self) -> Self {
+        self.has_headers = false;
+        self
+    }
+
+    /// Amounts use a comma before the cents and dots between thousands, e.g. `1.234,56`
+    pub fn with_decimal_comma(mut self) -> Self {
+        self.decimal_comma = true;
+        self
+    }
+
+    pub fn date(&self) -> &Column {
+        &self.date
+    }
+
+    pub fn description(&self) -> &Column {
+        &self.description
+    }
+
+    pub fn amount(&self) -> &Amount {
+        &self.amount
+    }
+
+    pub fn category(&self) -> Option<&Column> {
+        self.category.as_ref()
+    }
+
+    pub fn date_format(&self) -> &str {
+        &self.date_format
+    }
+
+    pub fn delimiter(&self) -> u8 {
+        self.delimiter
+    }
+
+    pub fn has_headers(&self) -> bool {
+        self.has_headers
+    }
+
+    pub fn decimal_comma(&self) -> bool {
+        self.decimal_comma
+    }
+
+    /// Read every row of `reader` as a transaction, in file order
+    pub fn read_transactions<R: io::Read>(
+        &self,
+        reader: R,
+    ) -> Result<Vec<Transaction>, MoolahCoreError> {
+        let mut reader = ::csv::ReaderBuilder::new()
+            .delimiter(self.delimiter)
+            .has_headers(self.has_headers)
+            .flexible(true)
+            .from_reader(reader);
+
+        let headers = if self.has_headers {
+            Some(reader.headers().map_err(csv_error)?.clone())
+        } else {
+            None
+        };
+        let resolve = |column: &Column| match column {
+            Column::Index(index) => Ok(*index),
+            Column::Name(name) => headers
+                .as_ref()
+                .and_then(|headers| {
+                    headers
+                        .iter()
+                        .position(|header| header.trim().eq_ignore_ascii_case(name.trim()))
+                })
+                .ok_or_else(|| MoolahCoreError::CsvMissingColumn {
+                    line: 1,
+                    column: name.clone(),
+                }),
+        };
+        let columns = Columns {
+            date: resolve(&self.date)?,
+            description: resolve(&self.description)?,
+            amount: match &self.amount {
+                Amount::Signed(column) => AmountColumns::Signed(resolve(column)?),
+                Amount::Inverted(column) => AmountColumns::Inverted(resolve(column)?),
+                Amount::DebitCredit { debit, credit } => AmountColumns::DebitCredit {
+                    debit: resolve(debit)?,
+                    credit: resolve(credit)?,
+                },
+                Amount::Indicator {
+                    amount,
+                    indicator,
+                    debit,
+                } => AmountColumns::Indicator {
+                    amount: resolve(amount)?,
+                    indicator: resolve(indicator)?,
+                    debit,
+                },
+            },
+            category: self.category.as_ref().map(resolve).transpose()?,
+            headers: headers.as_ref(),
+        };
+
+        reader
+            .records()
+            .map(|record| self.read_row(&record.map_err(csv_error)?, &columns))
+            .collect()
+    }
+
+    /// Read every row of `reader` into a ledger
+    pub fn read_ledger<R: io::Read>(&self, reader: R) -> Result<Ledger, MoolahCoreError> {
+        self.read_transactions(reader).map(Ledger::new)
+    }
+
+    /// Read every row of `reader` as a one-time delta named after its description
+    pub fn read_deltas<R: io::Read>(
+        &self,
+        reader: R,
+    ) -> Result<Vec<OneTimeDelta>, MoolahCoreError> {
+        self.read_transactions(reader)?
+            .iter()
+            .map(one_time_delta)
+            .collect()
+    }
+
+    fn read_row(
+        &self,
+        record: &::csv::StringRecord,
+        columns: &Columns<'_>,
+    ) -> Result<Transaction, MoolahCoreError> {
+        let line = record
+            .position()
+            .map_or(0, |position| position.line() as usize);
+        let field = |index: usize| {
+            record
+                .get(index)
+                .map(str::trim)
+                .ok_or_else(|| MoolahCoreError::CsvMissingColumn {
+                    line,
+                    column: columns.name(index),
+                })
+        };
+        let amount = |index: usize| {
+            let value = field(index)?;
+            parse_amount(value, self.decimal_comma).ok_or_else(|| {
+                MoolahCoreError::CsvInvalidAmount {
+                    line,
+                    value: value.into(),
+                }
+            })
+        };
+
+        let date = field(columns.date)?;
+        let date = NaiveDate::parse_from_str(date, &self.date_format).map_err(|_| {
+            MoolahCoreError::CsvInvalidDate {
+                line,
+                value: date.into(),
+                format: self.date_format.clone(),
+            }
+        })?;
+
+        let amount = match &columns.amount {
+            AmountColumns::Signed(index) => amount(*index)?,
+            AmountColumns::Inverted(index) => -amount(*index)?,
+            AmountColumns::DebitCredit { debit, credit } => {
+                let optional = |index: usize| match field(index)? {
+                    "" => Ok(None),
+                    _ => amount(index).map(Some),
+                };
+                match (optional(*debit)?, optional(*credit)?) {
+                    (None, None) => {
+                        return Err(MoolahCoreError::CsvInvalidAmount {
+                            line,
+                            value: String::new(),
+                        })
+                    }
+                    (debit, credit) => {
+                        credit.unwrap_or_default().abs() - debit.unwrap_or_default().abs()
+                    }
+                }
+            }
+            AmountColumns::Indicator {
+                amount: index,
+                indicator,
+                debit,
+            } => {
+                let value = amount(*index)?.abs();
+                if field(*indicator)?.eq_ignore_ascii_case(debit.trim()) {
+                    -value
+                } else {
+                    value
+                }
+            }
+        };
+
+        let mut transaction = Transaction::new(date, field(columns.description)?.into(), amount);
+        if let Some(category) = columns.category {
+            match field(category)? {
+                "" => {}
+                category => transaction = transaction.with_category(category.into()),
+            }
+        }
+        Ok(transaction)
+    }
+}
+
+/// A [`CsvFormat`]'s columns, resolved to their indexes
+struct Columns<'a> {
+    date: usize,
+    description: usize,
+    amount: AmountColumns<'a>,
+    category: Option<usize>,
+    headers: Option<&'a ::csv::StringRecord>,
+}
+
+impl Columns<'_> {
+    /// The header of the column at `index`, for errors
+    fn name(&self, index: usize) -> String {
+        self.headers
+            .and_then(|headers| headers.get(index))
+            .map_or_else(|| index.to_string(), |header| header.trim().into())
+    }
+}
+
+enum AmountColumns<'a> {
+    Signed(usize),
+    Inverted(usize),
+    DebitCredit {
+        debit: usize,
+        credit: usize,
+    },
+    Indicator {
+        amount: usize,
+        indicator: usize,
+        debit: &'a str,
+    },
+}
+
+fn csv_error(error: ::csv::Error) -> MoolahCoreError {
+    match error.position() {
+        Some(position) => MoolahCoreError::CsvSyntax {
+            line: position.line() as usize,
+            message: error.to_string(),
+        },
+        None => MoolahCoreError::CsvRead(error.to_string()),
+    }
+}
+
+/// Parse an amount as banks write them, e.g. `-1,234.56`, `$12.00` or `(12.00)`
+fn parse_amount(value: &str, decimal_comma: bool) -> Option<Money> {
+    let (thousands, decimal) = if decimal_comma {
+        ('.', ',')
+    } else {
+        (',', '.')
+    };
+    let (negative, value) = match value
+        .strip_prefix('(')
+        .and_then(|value| value.strip_suffix(')'))
+    {
+        Some(value) => (true, value),
+        None => (false, value),
+    };
+
+    let cleaned: String = value
+        .chars()
+        .filter(|c| !c.is_whitespace() & !matches!(c, '$' | '€' | '£' | '¥'))
+        .filter(|c| *c != thousands)
+        .map(|c| if c == decimal { '.' } else { c })
+        .collect();
+    let amount: Money = cleaned.parse().ok()?;
+
+    Some(if negative { -amount.abs() } else { amount })
+}
@@ -0,0 +1,139 @@
+use super::*;
+use crate::{date_helpers::ymd, delta::Delta};
+
+fn money(amount: &str) -> Money {
+    amount.parse().unwrap()
+}
+
+#[test]
+fn test_simple() {
+    let input = "\
+Date,Description,Amount
+2023-01-01,ACME PAYROLL,\"3,000.00\"
+2023-01-03, Corner Grocery ,-42.17
+";
+    let transactions = CsvFormat::simple()
+        .read_transactions(input.as_bytes())
+        .unwrap();
+    assert_eq!(
+        transactions,
+        [
+            Transaction::new(ymd(2023, 1, 1), "ACME PAYROLL".into(), Money::from(3000)),
+            Transaction::new(ymd(2023, 1, 3), "Corner Grocery".into(), money("-42.17")),
+        ]
+    );
+}
+
+#[test]
+fn test_debit_credit() {
+    let input = "\
+Date,Description,Debit,Credit,Balance
+31/01/2023,Rent,1000.00,,2000.00
+01/02/2023,Salary,,3000.00,5000.00
+";
+    let transactions = CsvFormat::debit_credit()
+        .read_transactions(input.as_bytes())
+        .unwrap();
+    let amounts: Vec<_> = transactions.iter().map(Transaction::amount).collect();
+    assert_eq!(amounts, [Money::from(-1000), Money::from(3000)]);
+    assert_eq!(*transactions[0].date(), ymd(2023, 1, 31));
+}
+
+#[test]
+fn test_credit_card() {
+    let input = "\
+Transaction Date,Post Date,Description,Category,Type,Amount
+01/30/2023,01/31/2023,COFFEE SHOP,Food & Drink,Sale,4.50
+02/01/2023,02/02/2023,PAYMENT THANK YOU,,Payment,-250.00
+";
+    let transactions = CsvFormat::credit_card()
+        .read_transactions(input.as_bytes())
+        .unwrap();
+    assert_eq!(
+        transactions,
+        [
+            Transaction::new(ymd(2023, 1, 30), "COFFEE SHOP".into(), money("-4.50"))
+                .with_category("Food & Drink".into()),
+            Transaction::new(
+                ymd(2023, 2, 1),
+                "PAYMENT THANK YOU".into(),
+                Money::from(250)
+            ),
+        ]
+    );
+}
+
+#[test]
+fn test_semicolon() {
+    let input = "\
+Date;Description;Amount
+31.01.2023;Miete;-1.234,56
+";
+    let transactions = CsvFormat::semicolon()
+        .read_transactions(input.as_bytes())
+        .unwrap();
+    assert_eq!(transactions[0].amount(), money("-1234.56"));
+}
+
+#[test]
+fn test_custom_layout() {
+    let input = "\
+20230105|DR|$15.00|Parking
+20230106|CR|(2.00)|Refund
+";
+    let format = CsvFormat::new(
+        0.into(),
+        3.into(),
+        Amount::Indicator {
+            amount: 2.into(),
+            indicator: 1.into(),
+            debit: "dr".into(),
+        },
+    )
+    .with_date_format("%Y%m%d")
+    .with_delimiter(b'|')
+    .without_headers();
+
+    let transactions = format.read_transactions(input.as_bytes()).unwrap();
+    let amounts: Vec<_> = transactions.iter().map(Transaction::amount).collect();
+    assert_eq!(amounts, [Money::from(-15), Money::from(2)]);
+}
+
+#[test]
+fn test_read_deltas() {
+    let input = "\
+Date,Description,Amount
+2023-01-05,Dentist,-80.00
+";
+    let deltas = CsvFormat::simple().read_deltas(input.as_bytes()).unwrap();
+    assert_eq!(deltas[0].name(), "Dentist");
+    assert_eq!(deltas[0].value(), Money::from(-80));
+    assert_eq!(deltas[0].dates().collect::<Vec<_>>(), [ymd(2023, 1, 5)]);
+}
+
+#[test]
+fn test_row_errors_carry_line() {
+    let read = |input: &str| CsvFormat::simple().read_transactions(input.as_bytes());
+
+    assert!(matches!(
+        read("Date,Amount\n2023-01-01,1.00\n"),
+        Err(MoolahCoreError::CsvMissingColumn { line: 1, column }) if column == "Description"
+    ));
+    assert!(matches!(
+        read("Date,Description,Amount\n2023-01-01,a,1.00\n01/02/2023,b,1.00\n"),
+        Err(MoolahCoreError::CsvInvalidDate { line: 3, value, .. }) if value == "01/02/2023"
+    ));
+    assert!(matches!(
+        read("Date,Description,Amount\n2023-01-01,a,one\n"),
+        Err(MoolahCoreError::CsvInvalidAmount { line: 2, value }) if value == "one"
+    ));
+    assert!(matches!(
+        read("Date,Description,Amount\n2023-01-01,a\n"),
+        Err(MoolahCoreError::CsvMissingColumn { line: 2, column }) if column == "Amount"
+    ));
+    assert!(matches!(
+        CsvFormat::debit_credit()
+            .read_transactions("Date,Description,Debit,Credit\n01/01/2023,a,,\n".as_bytes()),
+        Err(MoolahCoreError::CsvInvalidAmount { line: 2, .. })
+    ));
+}
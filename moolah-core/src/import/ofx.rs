@@ -0,0 +1,148 @@
+//! OFX and QFX downloads, in both the SGML (1.x) and XML (2.x) flavours
+
+#[cfg(test)]
+mod tests;
+
+use super::Statement;
+use crate::{
+    currency::Currency, errors::MoolahCoreError, money::Money, prediction::Checkpoint,
+    transaction::Transaction,
+};
+use chrono::NaiveDate;
+use std::collections::HashMap;
+
+enum Tag<'a> {
+    Open(&'a str),
+    Close(&'a str),
+    Leaf(&'a str, String),
+}
+
+/// Read every bank and credit card statement in an OFX or QFX file
+pub fn from_str(input: &str) -> Result<Vec<Statement>, MoolahCoreError> {
+    let start = input
+        .find("<OFX>")
+        .ok_or_else(|| MoolahCoreError::OfxSyntax("no <OFX> element".into()))?;
+
+    let mut statements = vec![];
+    let mut statement: Option<Statement> = None;
+    let mut fields: Option<HashMap<&str, String>> = None;
+
+    for tag in tags(&input[start..])? {
+        match tag {
+            Tag::Open("STMTRS" | "CCSTMTRS") => statement = Some(Statement::default()),
+            Tag::Open("STMTTRN" | "LEDGERBAL") => fields = Some(HashMap::new()),
+            Tag::Close("STMTRS" | "CCSTMTRS") => statements.extend(statement.take()),
+            Tag::Close(aggregate @ ("STMTTRN" | "LEDGERBAL")) => {
+                let (Some(statement), Some(fields)) = (&mut statement, fields.take()) else {
+                    continue;
+                };
+                let field = |tag: &str| {
+                    fields
+                        .get(tag)
+                        .ok_or_else(|| MoolahCoreError::OfxMissingField {
+                            aggregate: aggregate.into(),
+                            tag: tag.into(),
+                        })
+                };
+                if aggregate == "STMTTRN" {
+                    let description = ["NAME", "MEMO"]
+                        .into_iter()
+                        .find_map(|tag| fields.get(tag))
+                        .cloned()
+                        .unwrap_or_default();
+                    statement.transactions.push(Transaction::new(
+                        parse_date("DTPOSTED", field("DTPOSTED")?)?,
+                        description,
+                        parse_amount("TRNAMT", field("TRNAMT")?)?,
+                    ));
+                } else {
+                    statement.balance = Some(Checkpoint::new(
+                        parse_date("DTASOF", field("DTASOF")?)?,
+                        parse_amount("BALAMT", field("BALAMT")?)?,
+                    ));
+                }
+            }
+            Tag::Leaf(tag, value) => match (&mut fields, &mut statement) {
+                (Some(fields), _) => {
+                    fields.insert(tag, value);
+                }
+                (None, Some(statement)) => match tag {
+                    "ACCTID" => statement.account = Some(value),
+                    "CURDEF" => {
+                        statement.currency = Some(Currency::try_new(&value).map_err(|_| {
+                            MoolahCoreError::OfxInvalidField {
+                                tag: tag.into(),
+                                value,
+                            }
+                        })?)
+                    }
+                    _ => {}
+                },
+                (None, None) => {}
+            },
+            Tag::Open(_) | Tag::Close(_) => {}
+        }
+    }
+
+    Ok(statements)
+}
+
+/// Split OFX into tags. SGML leaves have no closing tag, so a tag followed by text is a leaf and
+/// any closing tag after it is skipped over like the closing tag of any other aggregate.
+fn tags(input: &str) -> Result<Vec<Tag<'_>>, MoolahCoreError> {
+    input
+        .split('<')
+        .skip(1)
+        .map(|token| {
+            let (name, text) = token.split_once('>').ok_or_else(|| {
+                MoolahCoreError::OfxSyntax(format!("unterminated tag `<{}`", token.trim()))
+            })?;
+            let text = text.trim();
+            Ok(match name.strip_prefix('/') {
+                Some(name) => Tag::Close(name.trim()),
+                None if text.is_empty() => Tag::Open(name.trim()),
+                None => Tag::Leaf(name.trim(), unescape(text)),
+            })
+        })
+        .collect()
+}
+
+fn unescape(text: &str) -> String {
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&nbsp;", " ")
+        .replace("&amp;", "&")
+}
+
+/// Dates are `YYYYMMDD`, optionally followed by a time and time zone that are not needed here
+fn parse_date(tag: &str, value: &str) -> Result<NaiveDate, MoolahCoreError> {
+    value
+        .get(..8)
+        .and_then(|date| NaiveDate::parse_from_str(date, "%Y%m%d").ok())
+        .ok_or_else(|| MoolahCoreError::OfxInvalidField {
+            tag: tag.into(),
+            value: value.into(),
+        })
+}
+
+/// Amounts are signed, and some banks use a comma as the decimal point. A comma is only read as
+/// one when there is no `.` and one or two digits follow it, as in `-12,5`; otherwise commas
+/// separate thousands.
+fn parse_amount(tag: &str, value: &str) -> Result<Money, MoolahCoreError> {
+    let amount = match value.split_once(',') {
+        Some((whole, fraction))
+            if !value.contains('.')
+                & (1..=2).contains(&fraction.len())
+                & fraction.chars().all(|c| c.is_ascii_digit()) =>
+        {
+            format!("{whole}.{fraction}").parse()
+        }
+        _ => Money::from_grouped_str(value),
+    };
+    amount.map_err(|_| MoolahCoreError::OfxInvalidField {
+        tag: tag.into(),
+        value: value.into(),
+    })
+}
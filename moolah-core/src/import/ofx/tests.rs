@@ -0,0 +1,101 @@
+use super::*;
+use crate::date_helpers::ymd;
+
+fn money(amount: &str) -> Money {
+    amount.parse().unwrap()
+}
+
+#[test]
+fn test_sgml_checking() {
+    let statements = from_str(include_str!("checking.ofx")).unwrap();
+    assert_eq!(statements.len(), 1);
+
+    let statement = &statements[0];
+    assert_eq!(statement.account(), Some("000123456789"));
+    assert_eq!(
+        statement.currency(),
+        Some(&Currency::try_new("USD").unwrap())
+    );
+    assert_eq!(
+        statement.balance(),
+        Some(&Checkpoint::new(ymd(2023, 1, 31), money("4452.83")))
+    );
+    assert_eq!(
+        statement.transactions(),
+        [
+            Transaction::new(ymd(2023, 1, 3), "ACME PAYROLL".into(), Money::from(3000)),
+            Transaction::new(ymd(2023, 1, 5), "Rent".into(), Money::from(-1000)),
+            Transaction::new(
+                ymd(2023, 1, 14),
+                "SMITH & SONS GROCERY".into(),
+                money("-42.17")
+            ),
+            Transaction::new(
+                ymd(2023, 1, 31),
+                "Monthly service fee".into(),
+                Money::from(-5)
+            ),
+        ]
+    );
+}
+
+#[test]
+fn test_xml_credit_card() {
+    let statements = from_str(include_str!("credit_card.qfx")).unwrap();
+    assert_eq!(statements.len(), 1);
+
+    let statement = &statements[0];
+    assert_eq!(statement.account(), Some("4111111111111111"));
+    assert_eq!(
+        statement.currency(),
+        Some(&Currency::try_new("EUR").unwrap())
+    );
+    let amounts: Vec<_> = statement
+        .transactions()
+        .iter()
+        .map(Transaction::amount)
+        .collect();
+    assert_eq!(amounts, [money("-12.50"), Money::from(200)]);
+    assert_eq!(statement.balance().unwrap().balance(), money("-310.75"));
+}
+
+#[test]
+fn test_prediction_from_balance() {
+    let statement = from_str(include_str!("checking.ofx")).unwrap().remove(0);
+    let prediction = statement.prediction("checking".into(), vec![]).unwrap();
+
+    assert_eq!(*prediction.start(), ymd(2023, 2, 1));
+    assert_eq!(prediction.initial_value(), money("4452.83"));
+    assert_eq!(prediction.currency(), statement.currency());
+}
+
+#[test]
+fn test_parse_amount() {
+    let parse = |value| parse_amount("TRNAMT", value);
+    assert_eq!(parse("1,234.56").unwrap(), money("1234.56"));
+    assert_eq!(parse("-1,234").unwrap(), money("-1234"));
+    assert_eq!(parse("-12,5").unwrap(), money("-12.50"));
+    assert_eq!(parse("12,34").unwrap(), money("12.34"));
+    for value in ["1.234,56", "1,2345", "12,34,5"] {
+        assert!(matches!(
+            parse(value),
+            Err(MoolahCoreError::OfxInvalidField { tag, .. }) if tag == "TRNAMT"
+        ));
+    }
+}
+
+#[test]
+fn test_errors() {
+    assert!(matches!(
+        from_str("OFXHEADER:100"),
+        Err(MoolahCoreError::OfxSyntax(_))
+    ));
+    assert!(matches!(
+        from_str("<OFX><STMTRS><STMTTRN><DTPOSTED>20230101</STMTTRN></STMTRS></OFX>"),
+        Err(MoolahCoreError::OfxMissingField { tag, .. }) if tag == "TRNAMT"
+    ));
+    assert!(matches!(
+        from_str("<OFX><STMTRS><STMTTRN><DTPOSTED>2023<TRNAMT>1.00</STMTTRN></STMTRS></OFX>"),
+        Err(MoolahCoreError::OfxInvalidField { tag, .. }) if tag == "DTPOSTED"
+    ));
+}
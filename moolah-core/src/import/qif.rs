@@ -0,0 +1,202 @@
+//! Quicken Interchange Format downloads
+
+#[cfg(test)]
+mod tests;
+
+use super::Statement;
+use crate::{
+    errors::MoolahCoreError, money::Money, prediction::Checkpoint, transaction::Transaction,
+};
+use chrono::NaiveDate;
+
+/// How to read a date such as `01/02/2023`, since QIF leaves it to the exporter's locale
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DateOrder {
+    #[default]
+    MonthFirst,
+    DayFirst,
+}
+
+#[derive(PartialEq)]
+enum Section {
+    Account,
+    Transactions,
+    Other,
+}
+
+#[derive(Default)]
+struct Record {
+    line: usize,
+    date: Option<NaiveDate>,
+    amount: Option<Money>,
+    payee: Option<String>,
+    memo: Option<String>,
+    category: Option<String>,
+}
+
+/// Read the bank, cash, credit card and asset or liability accounts in a QIF file. An
+/// `Opening Balance` transaction is the balance before the file's transactions, so the
+/// statement's balance is that plus every transaction, as of the last transaction's date.
+pub fn from_str(input: &str, order: DateOrder) -> Result<Vec<Statement>, MoolahCoreError> {
+    let mut statements = vec![];
+    let mut statement: Option<Statement> = None;
+    let mut section = Section::Other;
+    let mut account: Option<String> = None;
+    let mut record = Record::default();
+
+    for (i, line) in input.lines().enumerate() {
+        let line_number = i + 1;
+        let line = line.trim_end();
+        if line.is_empty() {
+            continue;
+        }
+
+        if let Some(header) = line.strip_prefix('!') {
+            let header = header.trim().to_lowercase();
+            if header == "account" {
+                section = Section::Account;
+            } else if let Some(kind) = header.strip_prefix("type:") {
+                section = match kind.trim() {
+                    "bank" | "cash" | "ccard" | "oth a" | "oth l" => Section::Transactions,
+                    "invst" => {
+                        return Err(MoolahCoreError::QifUnsupportedType {
+                            line: line_number,
+                            kind: kind.trim().into(),
+                        })
+                    }
+                    _ => Section::Other,
+                };
+                if section == Section::Transactions {
+                    if let Some(name) = account.take() {
+                        statements.extend(statement.take());
+                        statement = Some(Statement {
+                            account: Some(name),
+                            ..Default::default()
+                        });
+                    }
+                }
+            }
+            record = Record::default();
+            continue;
+        }
+
+        let (field, value) = line.split_at(line.chars().next().map_or(0, char::len_utf8));
+        let field = field.chars().next().unwrap_or_default();
+        let value = value.trim();
+        let invalid = || MoolahCoreError::QifInvalidField {
+            line: line_number,
+            field,
+            value: value.into(),
+        };
+        if record.line == 0 {
+            record.line = line_number;
+        }
+
+        match (&section, field) {
+            (Section::Account, 'N') => account = Some(value.into()),
+            (Section::Transactions, 'D') => {
+                record.date = Some(parse_date(value, order).ok_or_else(invalid)?)
+            }
+            (Section::Transactions, 'T' | 'U') if record.amount.is_none() => {
+                record.amount = Some(Money::from_grouped_str(value).map_err(|_| invalid())?)
+            }
+            (Section::Transactions, 'P') => record.payee = Some(value.into()),
+            (Section::Transactions, 'M') => record.memo = Some(value.into()),
+            (Section::Transactions, 'L') => record.category = Some(value.into()),
+            (Section::Transactions, '^') => {
+                let record = std::mem::take(&mut record);
+                finish(statement.get_or_insert_with(Default::default), record)?;
+            }
+            (_, '^') => record = Record::default(),
+            _ => {}
+        }
+    }
+    statements.extend(statement);
+    for statement in &mut statements {
+        close(statement)?;
+    }
+
+    Ok(statements)
+}
+
+/// Roll the statement's transactions into its opening balance
+fn close(statement: &mut Statement) -> Result<(), MoolahCoreError> {
+    let Some(opening) = &statement.balance else {
+        return Ok(());
+    };
+    let mut date = *opening.date();
+    let mut balance = opening.balance();
+    for transaction in &statement.transactions {
+        date = date.max(*transaction.date());
+        balance = balance
+            .checked_add(transaction.amount())
+            .ok_or(MoolahCoreError::MoneyOverflow)?;
+    }
+    statement.balance = Some(Checkpoint::new(date, balance));
+    Ok(())
+}
+
+/// Add a finished transaction record to `statement`
+fn finish(statement: &mut Statement, record: Record) -> Result<(), MoolahCoreError> {
+    let line = record.line;
+    let date = record
+        .date
+        .ok_or(MoolahCoreError::QifMissingField { line, field: 'D' })?;
+    let amount = record
+        .amount
+        .ok_or(MoolahCoreError::QifMissingField { line, field: 'T' })?;
+
+    // `[Name]` is a transfer to another account rather than a category
+    let transfer = record
+        .category
+        .as_deref()
+        .and_then(|category| category.strip_prefix('['))
+        .and_then(|category| category.strip_suffix(']'));
+    let is_opening_balance = record
+        .payee
+        .as_deref()
+        .is_some_and(|payee| payee.eq_ignore_ascii_case("opening balance"));
+    if is_opening_balance & transfer.is_some() {
+        statement.balance = Some(Checkpoint::new(date, amount));
+        if statement.account.is_none() {
+            statement.account = transfer.map(String::from);
+        }
+        return Ok(());
+    }
+
+    let category = match (transfer, record.category.as_deref()) {
+        (None, Some(category)) => category.split('/').next().filter(|c| !c.is_empty()),
+        _ => None,
+    };
+    let description = record.payee.or(record.memo).unwrap_or_default();
+    let mut transaction = Transaction::new(date, description, amount);
+    if let Some(category) = category {
+        transaction = transaction.with_category(category.into());
+    }
+    statement.transactions.push(transaction);
+
+    Ok(())
+}
+
+/// Read dates such as `01/31/2023`, `1/31'23`, ` 1/31/23` or `2023-01-31`. Two-digit years
+/// before 70 are in the 2000s.
+fn parse_date(value: &str, order: DateOrder) -> Option<NaiveDate> {
+    let parts: Vec<&str> = value.split(['/', '-', '.', '\'']).map(str::trim).collect();
+    let [first, second, third] = parts[..] else {
+        return None;
+    };
+    let (year, month, day) = if first.len() == 4 {
+        (first, second, third)
+    } else {
+        match order {
+            DateOrder::MonthFirst => (third, first, second),
+            DateOrder::DayFirst => (third, second, first),
+        }
+    };
+
+    let mut year: i32 = year.parse().ok()?;
+    if year < 100 {
+        year += if year < 70 { 2000 } else { 1900 };
+    }
+    NaiveDate::from_ymd_opt(year, month.parse().ok()?, day.parse().ok()?)
+}
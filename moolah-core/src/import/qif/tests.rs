@@ -0,0 +1,86 @@
+use super::*;
+use crate::date_helpers::ymd;
+
+#[test]
+fn test_checking() {
+    let statements = from_str(include_str!("checking.qif"), DateOrder::MonthFirst).unwrap();
+    assert_eq!(statements.len(), 1);
+
+    let statement = &statements[0];
+    assert_eq!(statement.account(), Some("Checking"));
+    assert_eq!(
+        statement.balance(),
+        Some(&Checkpoint::new(
+            ymd(2023, 1, 20),
+            "3207.83".parse().unwrap()
+        ))
+    );
+    assert_eq!(
+        statement.transactions(),
+        [
+            Transaction::new(ymd(2023, 1, 3), "ACME Payroll".into(), Money::from(3000))
+                .with_category("Salary".into()),
+            Transaction::new(ymd(2023, 1, 5), "Landlord LLC".into(), Money::from(-1000))
+                .with_category("Rent".into()),
+            Transaction::new(
+                ymd(2023, 1, 14),
+                "Corner Grocery".into(),
+                "-42.17".parse().unwrap()
+            )
+            .with_category("Groceries:Food".into()),
+            Transaction::new(
+                ymd(2023, 1, 20),
+                "Transfer to savings".into(),
+                Money::from(-250)
+            ),
+        ]
+    );
+
+    let deltas = statement.deltas().unwrap();
+    assert_eq!(deltas.len(), 4);
+}
+
+#[test]
+fn test_prediction_starts_after_transactions() {
+    let statements = from_str(include_str!("checking.qif"), DateOrder::MonthFirst).unwrap();
+    let prediction = statements[0].prediction("checking".into(), vec![]).unwrap();
+    assert_eq!(*prediction.start(), ymd(2023, 1, 21));
+    assert_eq!(prediction.initial_value(), "3207.83".parse().unwrap());
+}
+
+#[test]
+fn test_date_order() {
+    let input = "!Type:CCard\nD02/01/2023\nT-9.99\nMStreaming\n^\n";
+
+    let month_first = from_str(input, DateOrder::MonthFirst).unwrap();
+    assert_eq!(*month_first[0].transactions()[0].date(), ymd(2023, 2, 1));
+    assert_eq!(month_first[0].transactions()[0].description(), "Streaming");
+
+    let day_first = from_str(input, DateOrder::DayFirst).unwrap();
+    assert_eq!(*day_first[0].transactions()[0].date(), ymd(2023, 1, 2));
+}
+
+#[test]
+fn test_errors() {
+    let read = |input: &str| from_str(input, DateOrder::MonthFirst);
+
+    assert!(matches!(
+        read("!Type:Invst\nD1/1/23\n^\n"),
+        Err(MoolahCoreError::QifUnsupportedType { line: 1, .. })
+    ));
+    assert!(matches!(
+        read("!Type:Bank\nD1/1/23\nT1.00\n^\nD13/1/23\nT1.00\n^\n"),
+        Err(MoolahCoreError::QifInvalidField {
+            line: 5,
+            field: 'D',
+            ..
+        })
+    ));
+    assert!(matches!(
+        read("!Type:Bank\nD1/1/23\nT1.00\n^\nPNo date\nT1.00\n^\n"),
+        Err(MoolahCoreError::QifMissingField {
+            line: 5,
+            field: 'D'
+        })
+    ));
+}
@@ -0,0 +1,611 @@
+//! Plain-text accounting journals: projected occurrences out, periodic transactions in
+
+#[cfg(test)]
+mod tests;
+
+use crate::{
+    currency::Currency,
+    delta::{DailyDelta, Delta, MonthDay, MonthlyDelta, WeeklyDelta, YearlyDelta},
+    errors::MoolahCoreError,
+    money::Money,
+    prediction::Prediction,
+};
+use chrono::{Datelike, Days, Local, Months, NaiveDate};
+use std::{collections::BTreeSet, fmt::Write};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Dialect {
+    Ledger,
+    Hledger,
+    Beancount,
+}
+
+/// The dialect and accounts of a journal
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct JournalFormat {
+    dialect: Dialect,
+    account: String,
+    income: String,
+    expenses: String,
+    opening_balances: String,
+}
+
+impl JournalFormat {
+    /// `account` is the prediction's own account, e.g. `Assets:Checking`. Deltas post against
+    /// `Income:<name>` or `Expenses:<name>`.
+    pub fn new(dialect: Dialect, account: String) -> Self {
+        JournalFormat {
+            dialect,
+            account,
+            income: "Income".into(),
+            expenses: "Expenses".into(),
+            opening_balances: "Equity:Opening-Balances".into(),
+        }
+    }
+
+    /// The parent account of deltas that pay in
+    pub fn with_income(mut self, income: String) -> Self {
+        self.income = income;
+        self
+    }
+
+    /// The parent account of deltas that pay out
+    pub fn with_expenses(mut self, expenses: String) -> Self {
+        self.expenses = expenses;
+        self
+    }
+
+    pub fn with_opening_balances(mut self, opening_balances: String) -> Self {
+        self.opening_balances = opening_balances;
+        self
+    }
+
+    pub fn dialect(&self) -> Dialect {
+        self.dialect
+    }
+
+    pub fn account(&self) -> &str {
+        &self.account
+    }
+
+    pub fn income(&self) -> &str {
+        &self.income
+    }
+
+    pub fn expenses(&self) -> &str {
+        &self.expenses
+    }
+
+    pub fn opening_balances(&self) -> &str {
+        &self.opening_balances
+    }
+
+    /// A journal of the prediction's initial value, then every delta occurrence from its start
+    /// to `end` as a pending (`!`) transaction in its currency. Occurrences with an uncertainty
+    /// band note it in a comment. Balance-dependent deltas such as interest have no planned
+    /// amount, so they are left out.
+    pub fn write(
+        &self,
+        prediction: &Prediction,
+        end: &NaiveDate,
+    ) -> Result<String, MoolahCoreError> {
+        let commodity = match (prediction.currency(), self.dialect) {
+            (Some(currency), _) => format!(" {currency}"),
+            (None, Dialect::Beancount) => return Err(MoolahCoreError::JournalMissingCurrency),
+            (None, _) => String::new(),
+        };
+
+        let mut entries = vec![];
+        for delta in prediction.deltas() {
+            if delta.is_balance_dependent() {
+                continue;
+            }
+            for occurrence in delta.occurrences(prediction.start(), end) {
+                let rate = prediction.exchange_rate(&**delta, &occurrence.date)?;
+                let value = occurrence.value.scale(rate);
+                let parent = if value.is_negative() {
+                    &self.expenses
+                } else {
+                    &self.income
+                };
+                entries.push(Entry {
+                    date: occurrence.date,
+                    description: delta.name(),
+                    pending: true,
+                    amount: value,
+                    range: (occurrence.min.scale(rate), occurrence.max.scale(rate)),
+                    counter: format!("{parent}:{}", self.account_name(delta.name())),
+                });
+            }
+        }
+        entries.sort_by_key(|entry| entry.date);
+
+        let opening = Entry {
+            date: *prediction.start(),
+            description: "Opening balance",
+            pending: false,
+            amount: prediction.initial_value(),
+            range: (prediction.initial_value(), prediction.initial_value()),
+            counter: self.opening_balances.clone(),
+        };
+
+        let mut journal = String::new();
+        if self.dialect == Dialect::Beancount {
+            let accounts: BTreeSet<_> = entries
+                .iter()
+                .map(|entry| entry.counter.as_str())
+                .chain([self.account.as_str(), self.opening_balances.as_str()])
+                .collect();
+            for account in accounts {
+                writeln!(journal, "{} open {account}", prediction.start()).unwrap();
+            }
+            journal.push('\n');
+        }
+        for (i, entry) in [opening].iter().chain(&entries).enumerate() {
+            if i > 0 {
+                journal.push('\n');
+            }
+            self.write_entry(&mut journal, entry, &commodity);
+        }
+
+        Ok(journal)
+    }
+
+    fn write_entry(&self, journal: &mut String, entry: &Entry, commodity: &str) {
+        match self.dialect {
+            Dialect::Ledger | Dialect::Hledger => {
+                let date = if self.dialect == Dialect::Ledger {
+                    entry.date.format("%Y/%m/%d")
+                } else {
+                    entry.date.format("%Y-%m-%d")
+                };
+                let flag = if entry.pending { " !" } else { "" };
+                writeln!(journal, "{date}{flag} {}", entry.description).unwrap();
+            }
+            Dialect::Beancount => {
+                let flag = if entry.pending { '!' } else { '*' };
+                let description = entry.description.replace('"', "\\\"");
+                writeln!(journal, "{} {flag} \"{description}\"", entry.date).unwrap();
+            }
+        }
+
+        let (min, max) = entry.range;
+        if min != max {
+            writeln!(
+                journal,
+                "    ; between {} and {}",
+                min.min(max),
+                min.max(max)
+            )
+            .unwrap();
+        }
+        writeln!(
+            journal,
+            "    {:<36}  {:>12}{commodity}",
+            self.account, entry.amount
+        )
+        .unwrap();
+        writeln!(journal, "    {}", entry.counter).unwrap();
+    }
+
+    /// `name` as one account name component. Beancount components start with a capital letter
+    /// and hold only letters, digits and dashes.
+    fn account_name(&self, name: &str) -> String {
+        let words = name.split_whitespace();
+        if self.dialect != Dialect::Beancount {
+            return words.collect::<Vec<_>>().join(" ").replace(':', "-");
+        }
+
+        let name: String = words
+            .collect::<Vec<_>>()
+            .join("-")
+            .chars()
+            .map(|c| if c.is_alphanumeric() { c } else { '-' })
+            .collect();
+        let mut chars = name.trim_matches('-').chars();
+        match chars.next() {
+            Some(first) => first.to_uppercase().chain(chars).collect(),
+            None => "Unnamed".into(),
+        }
+    }
+
+    /// Read the periodic transactions (`~ monthly from 2023-01-01`) of a Ledger or hledger
+    /// journal, or the forecast entries (`2023-01-01 # "Rent [MONTHLY]"`) of a Beancount journal,
+    /// as deltas. Each delta's value is what the entry posts to the format's account, and entries
+    /// that do not post to it are left out. A delta is named after the entry's description, or
+    /// else the last component of the account it posts against. Periodic transactions without a
+    /// `from` date start today.
+    pub fn read(&self, input: &str) -> Result<Vec<Box<dyn Delta>>, MoolahCoreError> {
+        let mut deltas = vec![];
+        let mut lines = input.lines().enumerate().peekable();
+
+        while let Some((i, line)) = lines.next() {
+            let line_number = i + 1;
+            let Some((description, period)) = self.read_header(line, line_number)? else {
+                continue;
+            };
+
+            let mut postings = vec![];
+            while let Some((i, posting)) =
+                lines.next_if(|(_, line)| line.starts_with([' ', '\t']) & !line.trim().is_empty())
+            {
+                if let Some(posting) = self.read_posting(posting, i + 1)? {
+                    postings.push(posting);
+                }
+            }
+
+            if let Some(delta) = self.entry_delta(description, period, &postings, line_number)? {
+                deltas.push(delta);
+            }
+        }
+
+        Ok(deltas)
+    }
+
+    /// The description and period of a line that starts a periodic or forecast entry
+    fn read_header(
+        &self,
+        line: &str,
+        line_number: usize,
+    ) -> Result<Option<(Option<String>, Period)>, MoolahCoreError> {
+        let unsupported = |period: &str| MoolahCoreError::JournalUnsupportedPeriod {
+            line: line_number,
+            period: period.trim().into(),
+        };
+
+        match self.dialect {
+            Dialect::Ledger | Dialect::Hledger => {
+                let Some(header) = line.strip_prefix('~') else {
+                    return Ok(None);
+                };
+                let header = header.trim_start();
+                // hledger puts a description two spaces after the period
+                let (period, description) = header
+                    .split_once("  ")
+                    .map_or((header, None), |(period, description)| {
+                        (period, Some(description.trim()))
+                    });
+                let period = parse_period(period).ok_or_else(|| unsupported(period))?;
+                Ok(Some((description.map(String::from), period)))
+            }
+            Dialect::Beancount => {
+                let mut words = line.split_whitespace();
+                let (Some(date), Some("#")) = (words.next(), words.next()) else {
+                    return Ok(None);
+                };
+                let Some(date) = parse_date(date) else {
+                    return Ok(None);
+                };
+
+                // The narration is the last quoted string, after an optional payee
+                let strings: Vec<_> = line.split('"').skip(1).step_by(2).collect();
+                let narration = strings.last().copied().unwrap_or_default();
+                let (name, rule) = match narration.split_once('[') {
+                    Some((name, rule)) => (name, rule.trim_end().trim_end_matches(']')),
+                    None => return Err(unsupported(narration)),
+                };
+                let mut period = parse_forecast(rule).ok_or_else(|| unsupported(rule))?;
+                period.start = Some(date);
+
+                let name = match name.trim() {
+                    "" if strings.len() > 1 => strings[0].trim(),
+                    name => name,
+                };
+                Ok(Some(((!name.is_empty()).then(|| name.into()), period)))
+            }
+        }
+    }
+
+    /// A posting's account and amount, if the amount is not left for the journal to infer
+    fn read_posting(
+        &self,
+        line: &str,
+        line_number: usize,
+    ) -> Result<Option<Posting>, MoolahCoreError> {
+        let line = line.split(';').next().unwrap_or_default().trim();
+        if line.is_empty() | line.starts_with('#') {
+            return Ok(None);
+        }
+
+        let (account, amount) = match self.dialect {
+            Dialect::Ledger | Dialect::Hledger => line
+                .split_once("  ")
+                .or_else(|| line.split_once('\t'))
+                .unwrap_or((line, "")),
+            Dialect::Beancount => line.split_once(char::is_whitespace).unwrap_or((line, "")),
+        };
+        // Beancount metadata such as `note: "..."`
+        if (self.dialect == Dialect::Beancount) & account.ends_with(':') {
+            return Ok(None);
+        }
+
+        // Prices, costs and balance assertions are not part of the amount
+        let amount = amount
+            .split(['@', '{', '='])
+            .next()
+            .unwrap_or_default()
+            .trim();
+        let amount = if amount.is_empty() {
+            None
+        } else {
+            Some(
+                parse_amount(amount).ok_or_else(|| MoolahCoreError::JournalSyntax {
+                    line: line_number,
+                    message: format!("invalid amount `{amount}`"),
+                })?,
+            )
+        };
+
+        Ok(Some(Posting {
+            account: account.trim().into(),
+            amount,
+        }))
+    }
+
+    fn entry_delta(
+        &self,
+        description: Option<String>,
+        period: Period,
+        postings: &[Posting],
+        line: usize,
+    ) -> Result<Option<Box<dyn Delta>>, MoolahCoreError> {
+        let (own, others): (Vec<_>, Vec<_>) = postings
+            .iter()
+            .partition(|posting| posting.account == self.account);
+        if own.is_empty() {
+            return Ok(None);
+        }
+        if postings
+            .iter()
+            .filter(|posting| posting.amount.is_none())
+            .count()
+            > 1
+        {
+            return Err(MoolahCoreError::JournalSyntax {
+                line,
+                message: "more than one posting has no amount".into(),
+            });
+        }
+
+        let sum = |postings: &[&Posting]| -> (Money, Option<Currency>) {
+            let amounts = postings.iter().filter_map(|posting| posting.amount);
+            (
+                amounts.clone().map(|(value, _)| value).sum(),
+                amounts.filter_map(|(_, currency)| currency).next(),
+            )
+        };
+        let (value, currency) = if own.iter().all(|posting| posting.amount.is_some()) {
+            sum(&own)
+        } else {
+            let (value, currency) = sum(&others);
+            (-value - sum(&own).0, currency)
+        };
+
+        let name = description.unwrap_or_else(|| {
+            let account = others
+                .first()
+                .map_or(&self.account, |posting| &posting.account);
+            account.rsplit(':').next().unwrap_or(account).into()
+        });
+        period.delta(name, value, currency).map(Some)
+    }
+}
+
+/// A transaction to write
+struct Entry<'a> {
+    date: NaiveDate,
+    description: &'a str,
+    pending: bool,
+    amount: Money,
+    range: (Money, Money),
+    counter: String,
+}
+
+/// A posting read from an entry
+struct Posting {
+    account: String,
+    amount: Option<(Money, Option<Currency>)>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Interval {
+    Days,
+    Weeks,
+    Months,
+    Years,
+}
+
+/// When an entry repeats
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Period {
+    interval: Interval,
+    every: u16,
+    start: Option<NaiveDate>,
+    /// Inclusive
+    end: Option<NaiveDate>,
+    count: Option<u16>,
+}
+
+impl Period {
+    fn new(interval: Interval, every: u16) -> Self {
+        Period {
+            interval,
+            every,
+            start: None,
+            end: None,
+            count: None,
+        }
+    }
+
+    fn delta(
+        &self,
+        name: String,
+        value: Money,
+        currency: Option<Currency>,
+    ) -> Result<Box<dyn Delta>, MoolahCoreError> {
+        let start = self.start.unwrap_or_else(|| Local::now().date_naive());
+        let skip = self.every.saturating_sub(1);
+        let end = match self.count {
+            Some(count) => {
+                let steps = u32::from(count.saturating_sub(1)) * u32::from(self.every);
+                let end = match self.interval {
+                    Interval::Days => start.checked_add_days(Days::new(steps.into())),
+                    Interval::Weeks => start.checked_add_days(Days::new(7 * u64::from(steps))),
+                    Interval::Months => start.checked_add_months(Months::new(steps)),
+                    Interval::Years => start.checked_add_months(Months::new(12 * steps)),
+                };
+                Some(end.ok_or(MoolahCoreError::InvalidDate(format!(
+                    "{count} occurrences from {start}"
+                )))?)
+            }
+            None => self.end,
+        };
+
+        Ok(match self.interval {
+            Interval::Days => in_currency(
+                DailyDelta::try_new(name, value, None, start, end, skip.into())?,
+                currency,
+                DailyDelta::with_currency,
+            ),
+            Interval::Weeks => in_currency(
+                WeeklyDelta::try_new(name, value, None, start, end, None, skip.into())?,
+                currency,
+                WeeklyDelta::with_currency,
+            ),
+            Interval::Months => in_currency(
+                MonthlyDelta::try_new(
+                    name,
+                    value,
+                    None,
+                    start,
+                    end,
+                    MonthDay::try_new(start.day())?,
+                    skip,
+                )?,
+                currency,
+                MonthlyDelta::with_currency,
+            ),
+            Interval::Years => in_currency(
+                YearlyDelta::try_new(name, value, None, start, end, skip)?,
+                currency,
+                YearlyDelta::with_currency,
+            ),
+        })
+    }
+}
+
+fn in_currency<D: Delta + 'static>(
+    delta: D,
+    currency: Option<Currency>,
+    with_currency: fn(D, Currency) -> D,
+) -> Box<dyn Delta> {
+    match currency {
+        Some(currency) => Box::new(with_currency(delta, currency)),
+        None => Box::new(delta),
+    }
+}
+
+/// A Ledger or hledger period expression such as `monthly`, `every 2 weeks from 2023-01-01` or
+/// `yearly from 2023/06 to 2030`. The `to` date is exclusive.
+fn parse_period(text: &str) -> Option<Period> {
+    let text = text.to_lowercase();
+    let mut words = text.split_whitespace();
+
+    let mut period = match words.next()? {
+        "daily" => Period::new(Interval::Days, 1),
+        "weekly" => Period::new(Interval::Weeks, 1),
+        "biweekly" | "fortnightly" => Period::new(Interval::Weeks, 2),
+        "monthly" => Period::new(Interval::Months, 1),
+        "bimonthly" => Period::new(Interval::Months, 2),
+        "quarterly" => Period::new(Interval::Months, 3),
+        "yearly" | "annually" => Period::new(Interval::Years, 1),
+        "every" => {
+            let mut unit = words.next()?;
+            let mut every = 1;
+            if let Ok(n) = unit.parse() {
+                every = n;
+                unit = words.next()?;
+            }
+            match unit {
+                "day" | "days" => Period::new(Interval::Days, every),
+                "week" | "weeks" => Period::new(Interval::Weeks, every),
+                "month" | "months" => Period::new(Interval::Months, every),
+                "quarter" | "quarters" => Period::new(Interval::Months, every.checked_mul(3)?),
+                "year" | "years" => Period::new(Interval::Years, every),
+                _ => return None,
+            }
+        }
+        _ => return None,
+    };
+
+    while let Some(word) = words.next() {
+        match word {
+            "from" | "since" => period.start = Some(parse_date(words.next()?)?),
+            "to" | "until" => period.end = Some(parse_date(words.next()?)?.pred_opt()?),
+            _ => return None,
+        }
+    }
+
+    (period.every > 0).then_some(period)
+}
+
+/// The rule of a Beancount forecast entry such as `MONTHLY`, `WEEKLY SKIP 1 TIME`,
+/// `YEARLY REPEAT 5 TIMES` or `MONTHLY UNTIL 2024-12-31`. The `UNTIL` date is inclusive.
+fn parse_forecast(rule: &str) -> Option<Period> {
+    let rule = rule.to_uppercase();
+    let mut words = rule.split_whitespace();
+
+    let mut period = match words.next()? {
+        "DAILY" => Period::new(Interval::Days, 1),
+        "WEEKLY" => Period::new(Interval::Weeks, 1),
+        "MONTHLY" => Period::new(Interval::Months, 1),
+        "YEARLY" => Period::new(Interval::Years, 1),
+        _ => return None,
+    };
+
+    while let Some(word) = words.next() {
+        match word {
+            "UNTIL" => period.end = Some(parse_date(words.next()?)?),
+            "REPEAT" | "SKIP" => {
+                let n: u16 = words.next()?.parse().ok()?;
+                if !matches!(words.next()?, "TIME" | "TIMES") {
+                    return None;
+                }
+                if word == "REPEAT" {
+                    period.count = Some(n);
+                } else {
+                    period.every = n.checked_add(1)?;
+                }
+            }
+            _ => return None,
+        }
+    }
+
+    Some(period)
+}
+
+/// Dates such as `2023-01-31`, `2023/01/31` or `2023.01.31`, or `2023-01` or `2023` for the
+/// first day of the month or year
+fn parse_date(text: &str) -> Option<NaiveDate> {
+    let mut parts = text.split(['-', '/', '.']);
+    let year = parts.next()?.parse().ok()?;
+    let month = parts.next().map_or(Some(1), |month| month.parse().ok())?;
+    let day = parts.next().map_or(Some(1), |day| day.parse().ok())?;
+    if parts.next().is_some() {
+        return None;
+    }
+    NaiveDate::from_ymd_opt(year, month, day)
+}
+
+/// An amount such as `-1,000.00 USD`, `$12.50` or `EUR 3`. Commas only separate thousands, so a
+/// decimal comma as in `1.000,00 EUR` is rejected rather than misread.
+fn parse_amount(text: &str) -> Option<(Money, Option<Currency>)> {
+    let number: String = text
+        .chars()
+        .filter(|c| c.is_ascii_digit() | matches!(c, '.' | ',' | '-' | '+'))
+        .collect();
+    let commodity: String = text.chars().filter(|c| c.is_alphabetic()).collect();
+
+    let value = Money::from_grouped_str(&number).ok()?;
+    Some((value, Currency::try_new(&commodity).ok()))
+}
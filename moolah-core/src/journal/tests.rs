@@ -0,0 +1,192 @@
+use super::*;
+use crate::{
+    date_helpers::ymd,
+    delta::{OneTimeDelta, Uncertainty, UncertaintyType},
+};
+
+fn prediction() -> Prediction {
+    let rent = MonthlyDelta::try_new(
+        "rent".into(),
+        Money::from(-1000),
+        None,
+        ymd(2023, 1, 1),
+        None,
+        1.try_into().unwrap(),
+        0,
+    )
+    .unwrap();
+    let bonus = OneTimeDelta::try_new(
+        "year end bonus".into(),
+        Money::from(500),
+        Some(Uncertainty::Balanced(UncertaintyType::Percent(
+            10.0.try_into().unwrap(),
+        ))),
+        ymd(2023, 1, 20),
+    )
+    .unwrap();
+    let deltas: Vec<Box<dyn Delta>> = vec![Box::new(rent), Box::new(bonus)];
+    Prediction::new("test".into(), ymd(2023, 1, 1), Money::from(1500), deltas)
+}
+
+fn dates(delta: &dyn Delta) -> Vec<NaiveDate> {
+    delta.dates().take(5).collect()
+}
+
+#[test]
+fn test_write_ledger() {
+    let format = JournalFormat::new(Dialect::Ledger, "Assets:Checking".into());
+    let journal = format.write(&prediction(), &ymd(2023, 2, 15)).unwrap();
+
+    let expected = "\
+2023/01/01 Opening balance
+    Assets:Checking                            1500.00
+    Equity:Opening-Balances
+
+2023/01/01 ! rent
+    Assets:Checking                           -1000.00
+    Expenses:rent
+
+2023/01/20 ! year end bonus
+    ; between 450.00 and 550.00
+    Assets:Checking                             500.00
+    Income:year end bonus
+
+2023/02/01 ! rent
+    Assets:Checking                           -1000.00
+    Expenses:rent
+";
+    assert_eq!(journal, expected);
+
+    let hledger = JournalFormat::new(Dialect::Hledger, "Assets:Checking".into())
+        .write(&prediction(), &ymd(2023, 2, 15))
+        .unwrap();
+    assert!(hledger.starts_with("2023-01-01 Opening balance\n"));
+}
+
+#[test]
+fn test_write_beancount() {
+    let format = JournalFormat::new(Dialect::Beancount, "Assets:Checking".into());
+    assert!(matches!(
+        format.write(&prediction(), &ymd(2023, 1, 31)),
+        Err(MoolahCoreError::JournalMissingCurrency)
+    ));
+
+    let prediction = prediction().with_currency(Currency::try_new("USD").unwrap());
+    let journal = format.write(&prediction, &ymd(2023, 1, 31)).unwrap();
+    let expected = "\
+2023-01-01 open Assets:Checking
+2023-01-01 open Equity:Opening-Balances
+2023-01-01 open Expenses:Rent
+2023-01-01 open Income:Year-end-bonus
+
+2023-01-01 * \"Opening balance\"
+    Assets:Checking                            1500.00 USD
+    Equity:Opening-Balances
+
+2023-01-01 ! \"rent\"
+    Assets:Checking                           -1000.00 USD
+    Expenses:Rent
+
+2023-01-20 ! \"year end bonus\"
+    ; between 450.00 and 550.00
+    Assets:Checking                             500.00 USD
+    Income:Year-end-bonus
+";
+    assert_eq!(journal, expected);
+}
+
+#[test]
+fn test_read_periodic_transactions() {
+    let input = "\
+; Budget
+~ monthly from 2023-01-01  rent
+    Expenses:Rent         1000.00 USD
+    Assets:Checking
+
+~  every 2 weeks from 2023/01/06 to 2023/02/03
+    Assets:Checking     $2,500.00  ; after tax
+    Income:Salary
+
+~ yearly from 2023-03-15
+    Expenses:Insurance   600
+    Assets:Savings
+
+2023-01-01 Groceries
+    Expenses:Food    50
+    Assets:Checking
+";
+    let format = JournalFormat::new(Dialect::Hledger, "Assets:Checking".into());
+    let deltas = format.read(input).unwrap();
+    assert_eq!(deltas.len(), 2);
+
+    assert_eq!(deltas[0].name(), "rent");
+    assert_eq!(deltas[0].value(), Money::from(-1000));
+    assert_eq!(
+        deltas[0].currency(),
+        Some(&Currency::try_new("USD").unwrap())
+    );
+    assert!(deltas[0].is_open_ended());
+    assert_eq!(
+        dates(&*deltas[0])[..3],
+        [ymd(2023, 1, 1), ymd(2023, 2, 1), ymd(2023, 3, 1)]
+    );
+
+    assert_eq!(deltas[1].name(), "Salary");
+    assert_eq!(deltas[1].value(), Money::from(2500));
+    assert_eq!(deltas[1].currency(), None);
+    assert_eq!(dates(&*deltas[1]), [ymd(2023, 1, 6), ymd(2023, 1, 20)]);
+}
+
+#[test]
+fn test_read_beancount_forecasts() {
+    let input = "\
+2023-01-01 open Assets:Checking
+
+2023-01-15 # \"Gym [MONTHLY REPEAT 3 TIMES]\"
+  Expenses:Gym      40.00 USD
+  Assets:Checking
+
+2023-01-02 # \"Payroll\" \"[WEEKLY SKIP 1 TIME UNTIL 2023-02-01]\"
+  note: \"every other Monday\"
+  Assets:Checking  1000.00 USD
+  Income:Salary
+";
+    let format = JournalFormat::new(Dialect::Beancount, "Assets:Checking".into());
+    let deltas = format.read(input).unwrap();
+    assert_eq!(deltas.len(), 2);
+
+    assert_eq!(deltas[0].name(), "Gym");
+    assert_eq!(deltas[0].value(), Money::from(-40));
+    assert_eq!(
+        dates(&*deltas[0]),
+        [ymd(2023, 1, 15), ymd(2023, 2, 15), ymd(2023, 3, 15)]
+    );
+
+    assert_eq!(deltas[1].name(), "Payroll");
+    assert_eq!(deltas[1].value(), Money::from(1000));
+    assert_eq!(
+        dates(&*deltas[1]),
+        [ymd(2023, 1, 2), ymd(2023, 1, 16), ymd(2023, 1, 30)]
+    );
+}
+
+#[test]
+fn test_read_errors() {
+    let format = JournalFormat::new(Dialect::Ledger, "Assets:Checking".into());
+    assert!(matches!(
+        format.read("\n~ every other tuesday\n    Assets:Checking  1\n    Income:Misc\n"),
+        Err(MoolahCoreError::JournalUnsupportedPeriod { line: 2, .. })
+    ));
+    assert!(matches!(
+        format.read("~ monthly\n    Assets:Checking\n    Income:Misc\n"),
+        Err(MoolahCoreError::JournalSyntax { line: 1, .. })
+    ));
+    assert!(matches!(
+        format.read("~ monthly\n    Assets:Checking  one\n    Income:Misc\n"),
+        Err(MoolahCoreError::JournalSyntax { line: 2, .. })
+    ));
+    assert!(matches!(
+        format.read("~ monthly\n    Assets:Checking  1.000,00 EUR\n    Income:Misc\n"),
+        Err(MoolahCoreError::JournalSyntax { line: 2, .. })
+    ));
+}
@@ -1,4 +1,17 @@
+pub mod calendar;
+pub mod currency;
 pub(crate) mod date_helpers;
 pub mod delta;
 pub mod errors;
+pub mod explanation;
+pub mod import;
+pub mod journal;
+pub mod money;
+pub mod portfolio;
 pub mod prediction;
+pub mod reconciliation;
+pub mod scenario;
+#[cfg(feature = "toml")]
+pub mod scenario_file;
+pub mod simulation;
+pub mod transaction;
@@ -0,0 +1,266 @@
+#[cfg(test)]
+mod tests;
+
+use crate::errors::MoolahCoreError;
+#[cfg(feature = "serde")]
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+use std::{
+    fmt,
+    iter::Sum,
+    ops::{Add, AddAssign, Neg, Sub, SubAssign},
+    str::FromStr,
+};
+
+const CENTS_PER_UNIT: i64 = 100;
+const SCALE_PRECISION: i128 = 1_000_000_000_000;
+
+/// An exact amount of money, stored as a whole number of cents.
+///
+/// Sums of `Money` never drift, and `+`, `-` and negation saturate at its bounds rather than
+/// overflowing. Whenever an amount has to be rounded to the cent - when it is
+/// parsed from a string or `f64` with more than two decimals, or scaled by a factor or percent -
+/// it is rounded to the nearest cent with halves rounded away from zero.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct Money(i64);
+
+impl Money {
+    pub const ZERO: Money = Money(0);
+
+    pub const fn from_cents(cents: i64) -> Self {
+        Money(cents)
+    }
+
+    pub fn cents(&self) -> i64 {
+        self.0
+    }
+
+    pub fn to_f64(&self) -> f64 {
+        self.0 as f64 / CENTS_PER_UNIT as f64
+    }
+
+    pub fn abs(self) -> Self {
+        Money(self.0.saturating_abs())
+    }
+
+    pub fn is_negative(&self) -> bool {
+        self.0 < 0
+    }
+
+    /// Multiply by `factor`, which is first fixed to 12 decimal places. Saturates at the bounds of
+    /// `Money` instead of overflowing.
+    pub fn scale(self, factor: f64) -> Self {
+        let factor = (factor * SCALE_PRECISION as f64).round() as i128;
+        let cents = div_round(i128::from(self.0).saturating_mul(factor), SCALE_PRECISION);
+        Money(cents.clamp(i64::MIN.into(), i64::MAX.into()) as i64)
+    }
+
+    /// `self + rhs`, or `None` if the sum overflows
+    pub fn checked_add(self, rhs: Money) -> Option<Money> {
+        self.0.checked_add(rhs.0).map(Money)
+    }
+
+    /// `self - rhs`, or `None` if the difference overflows
+    pub fn checked_sub(self, rhs: Money) -> Option<Money> {
+        self.0.checked_sub(rhs.0).map(Money)
+    }
+
+    /// `percent` percent of this amount, e.g. `Money::from(200).percent(2.5)` is 5.00
+    pub fn percent(self, percent: f64) -> Self {
+        self.scale(percent / 100.0)
+    }
+
+    /// Parse an amount whose thousands may be separated by commas, such as `-1,234.56`. A comma
+    /// anywhere else, as in a decimal comma, makes it invalid.
+    pub(crate) fn from_grouped_str(s: &str) -> Result<Self, MoolahCoreError> {
+        let (whole, fraction) = s.split_once('.').unwrap_or((s, ""));
+        if fraction.contains(',') | whole.split(',').skip(1).any(|group| group.len() != 3) {
+            return Err(MoolahCoreError::InvalidMoney(s.into()));
+        }
+        s.replace(',', "").parse()
+    }
+}
+
+fn div_round(numerator: i128, denominator: i128) -> i128 {
+    let quotient = numerator / denominator;
+    let remainder = numerator % denominator;
+    if 2 * remainder.abs() >= denominator.abs() {
+        quotient + numerator.signum() * denominator.signum()
+    } else {
+        quotient
+    }
+}
+
+impl From<i32> for Money {
+    fn from(units: i32) -> Self {
+        Money(i64::from(units) * CENTS_PER_UNIT)
+    }
+}
+
+impl TryFrom<f64> for Money {
+    type Error = MoolahCoreError;
+
+    fn try_from(value: f64) -> Result<Self, Self::Error> {
+        if !value.is_finite() {
+            return Err(MoolahCoreError::InvalidMoney(value.to_string()));
+        }
+        // `Display` gives the shortest decimal that round-trips, so 1.005 rounds to 1.01 rather
+        // than to the binary approximation's 1.00
+        value.to_string().parse()
+    }
+}
+
+impl FromStr for Money {
+    type Err = MoolahCoreError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || MoolahCoreError::InvalidMoney(s.into());
+
+        let trimmed = s.trim();
+        let (negative, unsigned) = match trimmed.strip_prefix('-') {
+            Some(rest) => (true, rest),
+            None => (false, trimmed.strip_prefix('+').unwrap_or(trimmed)),
+        };
+        let (units, fraction) = unsigned.split_once('.').unwrap_or((unsigned, ""));
+        if (units.is_empty() & fraction.is_empty())
+            | !units
+                .chars()
+                .chain(fraction.chars())
+                .all(|c| c.is_ascii_digit())
+        {
+            return Err(invalid());
+        }
+
+        let units: i128 = if units.is_empty() {
+            0
+        } else {
+            units.parse().map_err(|_| invalid())?
+        };
+        let mut cents = units
+            .checked_mul(i128::from(CENTS_PER_UNIT))
+            .ok_or_else(invalid)?;
+        for (i, digit) in fraction.chars().take(3).enumerate() {
+            let digit = i128::from(digit.to_digit(10).ok_or_else(invalid)?);
+            let step = match i {
+                0 => digit * 10,
+                1 => digit,
+                _ if digit >= 5 => 1,
+                _ => 0,
+            };
+            cents = cents.checked_add(step).ok_or_else(invalid)?;
+        }
+        if negative {
+            cents = -cents;
+        }
+
+        i64::try_from(cents).map(Money).map_err(|_| invalid())
+    }
+}
+
+impl fmt::Display for Money {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let sign = if self.0 < 0 { "-" } else { "" };
+        let cents = self.0.unsigned_abs();
+        let amount = format!(
+            "{}{}.{:02}",
+            sign,
+            cents / CENTS_PER_UNIT as u64,
+            cents % CENTS_PER_UNIT as u64
+        );
+        f.pad(&amount)
+    }
+}
+
+impl Add for Money {
+    type Output = Money;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        Money(self.0.saturating_add(rhs.0))
+    }
+}
+
+impl AddAssign for Money {
+    fn add_assign(&mut self, rhs: Self) {
+        *self = *self + rhs;
+    }
+}
+
+impl Sub for Money {
+    type Output = Money;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        Money(self.0.saturating_sub(rhs.0))
+    }
+}
+
+impl SubAssign for Money {
+    fn sub_assign(&mut self, rhs: Self) {
+        *self = *self - rhs;
+    }
+}
+
+impl Neg for Money {
+    type Output = Money;
+
+    fn neg(self) -> Self::Output {
+        Money(self.0.saturating_neg())
+    }
+}
+
+impl Sum for Money {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Money::ZERO, Add::add)
+    }
+}
+
+impl<'a> Sum<&'a Money> for Money {
+    fn sum<I: Iterator<Item = &'a Money>>(iter: I) -> Self {
+        iter.copied().sum()
+    }
+}
+
+#[cfg(feature = "serde")]
+impl Serialize for Money {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        // A decimal string keeps the exact cents, which an `f64` may not
+        serializer.collect_str(self)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> Deserialize<'de> for Money {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_any(MoneyVisitor)
+    }
+}
+
+#[cfg(feature = "serde")]
+struct MoneyVisitor;
+
+#[cfg(feature = "serde")]
+impl<'de> de::Visitor<'de> for MoneyVisitor {
+    type Value = Money;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("an amount of money as a number or decimal string")
+    }
+
+    fn visit_i64<E: de::Error>(self, v: i64) -> Result<Self::Value, E> {
+        v.checked_mul(CENTS_PER_UNIT)
+            .map(Money)
+            .ok_or_else(|| E::custom(MoolahCoreError::InvalidMoney(v.to_string())))
+    }
+
+    fn visit_u64<E: de::Error>(self, v: u64) -> Result<Self::Value, E> {
+        i64::try_from(v)
+            .map_err(|_| E::custom(MoolahCoreError::InvalidMoney(v.to_string())))
+            .and_then(|v| self.visit_i64(v))
+    }
+
+    fn visit_f64<E: de::Error>(self, v: f64) -> Result<Self::Value, E> {
+        Money::try_from(v).map_err(E::custom)
+    }
+
+    fn visit_str<E: de::Error>(self, v: &str) -> Result<Self::Value, E> {
+        v.parse().map_err(E::custom)
+    }
+}
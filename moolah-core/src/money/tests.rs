@@ -0,0 +1,161 @@
+use super::*;
+
+#[test]
+fn test_from_units_and_cents() {
+    assert_eq!(Money::from(12), Money::from_cents(1200));
+    assert_eq!(Money::from(-3).cents(), -300);
+    assert_eq!(Money::default(), Money::ZERO);
+}
+
+#[test]
+fn test_parse() {
+    assert_eq!(
+        "1234.56".parse::<Money>().unwrap(),
+        Money::from_cents(123456)
+    );
+    assert_eq!("-0.1".parse::<Money>().unwrap(), Money::from_cents(-10));
+    assert_eq!("+7".parse::<Money>().unwrap(), Money::from(7));
+    assert_eq!(".5".parse::<Money>().unwrap(), Money::from_cents(50));
+    assert_eq!("3.".parse::<Money>().unwrap(), Money::from(3));
+
+    assert!("".parse::<Money>().is_err());
+    assert!(".".parse::<Money>().is_err());
+    assert!("1,000".parse::<Money>().is_err());
+    assert!("12.3.4".parse::<Money>().is_err());
+    assert!("--1".parse::<Money>().is_err());
+    assert!("100000000000000000000".parse::<Money>().is_err());
+}
+
+#[test]
+fn test_parse_too_many_digits() {
+    for s in [
+        "99999999999999999999999999999999999999",
+        "-99999999999999999999999999999999999999",
+        "1701411834604692317316873037158841057.99",
+    ] {
+        assert!(matches!(
+            s.parse::<Money>(),
+            Err(MoolahCoreError::InvalidMoney(invalid)) if invalid == s
+        ));
+    }
+}
+
+#[test]
+fn test_from_grouped_str() {
+    assert_eq!(
+        Money::from_grouped_str("-1,234,567.89").unwrap(),
+        Money::from_cents(-123456789)
+    );
+    assert_eq!(Money::from_grouped_str("1000").unwrap(), Money::from(1000));
+    assert!(Money::from_grouped_str("1.000,00").is_err());
+    assert!(Money::from_grouped_str("1,00").is_err());
+}
+
+#[test]
+fn test_parse_rounds_half_away_from_zero() {
+    assert_eq!("1.005".parse::<Money>().unwrap(), Money::from_cents(101));
+    assert_eq!(
+        "1.0049999".parse::<Money>().unwrap(),
+        Money::from_cents(100)
+    );
+    assert_eq!("-1.005".parse::<Money>().unwrap(), Money::from_cents(-101));
+    assert_eq!("-1.004".parse::<Money>().unwrap(), Money::from_cents(-100));
+}
+
+#[test]
+fn test_from_f64() {
+    assert_eq!(Money::try_from(0.1).unwrap(), Money::from_cents(10));
+    assert_eq!(Money::try_from(1.005).unwrap(), Money::from_cents(101));
+    assert_eq!(Money::try_from(-1800.0).unwrap(), Money::from(-1800));
+    assert!(Money::try_from(f64::NAN).is_err());
+    assert!(Money::try_from(f64::INFINITY).is_err());
+    assert!(Money::try_from(1e30).is_err());
+}
+
+#[test]
+fn test_display() {
+    assert_eq!(Money::from_cents(123456).to_string(), "1234.56");
+    assert_eq!(Money::from_cents(-5).to_string(), "-0.05");
+    assert_eq!(Money::ZERO.to_string(), "0.00");
+    assert_eq!(format!("{:>8}", Money::from(3)), "    3.00");
+    assert_eq!(
+        Money::from_cents(i64::MIN).to_string(),
+        "-92233720368547758.08"
+    );
+}
+
+#[test]
+fn test_arithmetic() {
+    let mut m = Money::from(10);
+    m += Money::from_cents(1);
+    m -= Money::from_cents(2);
+    assert_eq!(m, Money::from_cents(999));
+    assert_eq!(-m, Money::from_cents(-999));
+    assert_eq!(m - Money::from(20), Money::from_cents(-1001));
+    assert_eq!((-m).abs(), m);
+}
+
+#[test]
+fn test_arithmetic_saturates() {
+    let max = Money::from_cents(i64::MAX);
+    let min = Money::from_cents(i64::MIN);
+    assert_eq!(max + Money::from(1), max);
+    assert_eq!(min - Money::from(1), min);
+    assert_eq!(-min, max);
+    assert_eq!(max.checked_add(Money::from_cents(1)), None);
+    assert_eq!(min.checked_sub(Money::from_cents(1)), None);
+    assert_eq!(
+        Money::from(1).checked_add(Money::from(2)),
+        Some(Money::from(3))
+    );
+}
+
+#[test]
+fn test_sum_does_not_drift() {
+    let daily = Money::from_cents(10);
+    let total: Money = std::iter::repeat_n(daily, 365 * 100).sum();
+    assert_eq!(total, Money::from(3650));
+
+    let float_total: f64 = std::iter::repeat_n(0.1, 365 * 100).sum();
+    assert_ne!(float_total, 3650.0);
+}
+
+#[test]
+fn test_percent_rounding() {
+    assert_eq!(Money::from(1000).percent(3.0), Money::from(30));
+    assert_eq!(Money::from(200).percent(2.5), Money::from(5));
+    // 0.5% of 1.00 is exactly half a cent
+    assert_eq!(Money::from(1).percent(0.5), Money::from_cents(1));
+    assert_eq!(Money::from(-1).percent(0.5), Money::from_cents(-1));
+    // 1.5% of 0.33 is 0.495 cents
+    assert_eq!(Money::from_cents(33).percent(1.5), Money::from_cents(0));
+    assert_eq!(
+        Money::from_cents(1001).percent(12.5),
+        Money::from_cents(125)
+    );
+}
+
+#[test]
+fn test_scale() {
+    assert_eq!(Money::from(100).scale(1.0834), Money::from_cents(10834));
+    assert_eq!(Money::from_cents(3).scale(0.5), Money::from_cents(2));
+    assert_eq!(Money::from_cents(-3).scale(0.5), Money::from_cents(-2));
+    assert_eq!(Money::from(1).scale(-2.0), Money::from(-2));
+    assert_eq!(
+        Money::from_cents(i64::MAX).scale(10.0),
+        Money::from_cents(i64::MAX)
+    );
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn test_serde() {
+    let large = Money::from_cents(9_007_199_254_740_993);
+    let value = serde_json::to_value(large).unwrap();
+    assert_eq!(value, serde_json::json!("90071992547409.93"));
+    assert_eq!(serde_json::from_value::<Money>(value).unwrap(), large);
+    assert_eq!(
+        serde_json::from_value::<Money>(serde_json::json!(12.5)).unwrap(),
+        Money::from_cents(1250)
+    );
+}
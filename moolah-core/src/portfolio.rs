@@ -0,0 +1,337 @@
+#[cfg(test)]
+mod tests;
+
+use crate::{
+    currency::{Currency, ExchangeRates},
+    delta::{Delta, DeltaId},
+    errors::MoolahCoreError,
+    money::Money,
+    prediction::{
+        accumulate, AggregatedDelta, Aggregation, Prediction, PredictionState, Valuation,
+    },
+};
+use chrono::NaiveDate;
+use std::collections::{BTreeMap, HashSet};
+
+/// Money moved from one account to another on each of `delta`'s dates.
+///
+/// The delta's value is debited from `from` and credited to `to`, so a transfer never changes the
+/// combined balance of a [`Portfolio`]. A delta without a currency is taken to be in each
+/// account's own currency, so transfers between accounts in different currencies should set one.
+/// Balance-dependent deltas such as interest have no amount of their own, so they cannot be
+/// transfers.
+pub struct Transfer {
+    from: String,
+    to: String,
+    delta: Box<dyn Delta>,
+}
+
+impl Transfer {
+    pub fn try_new(
+        from: String,
+        to: String,
+        delta: Box<dyn Delta>,
+    ) -> Result<Self, MoolahCoreError> {
+        if from == to {
+            return Err(MoolahCoreError::TransferToSameAccount(from));
+        }
+        if delta.is_balance_dependent() {
+            return Err(MoolahCoreError::BalanceDependentTransfer(
+                delta.name().into(),
+            ));
+        }
+
+        Ok(Transfer { from, to, delta })
+    }
+
+    pub fn name(&self) -> &str {
+        self.delta.name()
+    }
+
+    pub fn from(&self) -> &str {
+        &self.from
+    }
+
+    pub fn to(&self) -> &str {
+        &self.to
+    }
+
+    pub fn delta(&self) -> &dyn Delta {
+        &*self.delta
+    }
+}
+
+/// Several accounts, each its own [`Prediction`] named after the account, and the transfers
+/// between them. Like the deltas of a prediction, every delta and transfer in a portfolio has an
+/// id which nothing else in the portfolio has.
+#[derive(Default)]
+pub struct Portfolio {
+    name: String,
+    accounts: Vec<Prediction>,
+    transfers: Vec<Transfer>,
+    /// The id of each of `transfers`
+    transfer_ids: Vec<DeltaId>,
+    currency: Option<Currency>,
+    exchange_rates: ExchangeRates,
+}
+
+impl Portfolio {
+    pub fn try_new(
+        name: String,
+        accounts: Vec<Prediction>,
+        transfers: Vec<Transfer>,
+    ) -> Result<Self, MoolahCoreError> {
+        let mut names = HashSet::new();
+        for account in &accounts {
+            if !names.insert(account.name()) {
+                return Err(MoolahCoreError::DuplicateAccount(account.name().into()));
+            }
+        }
+        let mut ids = HashSet::new();
+        for id in accounts.iter().flat_map(|account| account.ids()) {
+            if !ids.insert(id.as_str()) {
+                return Err(MoolahCoreError::DuplicateDeltaId(id.to_string()));
+            }
+        }
+
+        let mut portfolio = Portfolio {
+            name,
+            accounts,
+            ..Default::default()
+        };
+        for transfer in transfers {
+            let id = DeltaId::from_name(transfer.name(), |id| portfolio.is_taken(id));
+            portfolio.add_transfer(id, transfer)?;
+        }
+        Ok(portfolio)
+    }
+
+    /// Add `transfer` under `id`, which no other transfer or delta may have
+    pub fn with_transfer(
+        mut self,
+        id: DeltaId,
+        transfer: Transfer,
+    ) -> Result<Self, MoolahCoreError> {
+        if self.is_taken(id.as_str()) {
+            return Err(MoolahCoreError::DuplicateDeltaId(id.into()));
+        }
+        self.add_transfer(id, transfer)?;
+        Ok(self)
+    }
+
+    fn add_transfer(&mut self, id: DeltaId, transfer: Transfer) -> Result<(), MoolahCoreError> {
+        for account in [transfer.from(), transfer.to()] {
+            self.account_index(account)?;
+        }
+        self.transfer_ids.push(id);
+        self.transfers.push(transfer);
+        Ok(())
+    }
+
+    fn is_taken(&self, id: &str) -> bool {
+        self.transfer_ids.iter().any(|other| other.as_str() == id)
+            | self
+                .accounts
+                .iter()
+                .flat_map(|account| account.ids())
+                .any(|other| other.as_str() == id)
+    }
+
+    /// Report the combined balance in `currency`, converting accounts in other currencies with
+    /// the portfolio's exchange rates
+    pub fn with_currency(mut self, currency: Currency) -> Self {
+        self.currency = Some(currency);
+        self
+    }
+
+    pub fn with_exchange_rates(mut self, exchange_rates: ExchangeRates) -> Self {
+        self.exchange_rates = exchange_rates;
+        self
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn accounts(&self) -> &[Prediction] {
+        &self.accounts
+    }
+
+    pub fn account(&self, name: &str) -> Option<&Prediction> {
+        self.accounts.iter().find(|account| account.name() == name)
+    }
+
+    pub fn transfers(&self) -> &[Transfer] {
+        &self.transfers
+    }
+
+    /// The id of each of [`Portfolio::transfers`], in the same order
+    pub fn transfer_ids(&self) -> &[DeltaId] {
+        &self.transfer_ids
+    }
+
+    /// The transfer with `id`
+    pub fn transfer(&self, id: &str) -> Option<&Transfer> {
+        self.transfer_ids
+            .iter()
+            .position(|other| other.as_str() == id)
+            .map(|index| &self.transfers[index])
+    }
+
+    pub fn currency(&self) -> Option<&Currency> {
+        self.currency.as_ref()
+    }
+
+    pub fn exchange_rates(&self) -> &ExchangeRates {
+        &self.exchange_rates
+    }
+
+    fn account_index(&self, name: &str) -> Result<usize, MoolahCoreError> {
+        self.accounts
+            .iter()
+            .position(|account| account.name() == name)
+            .ok_or_else(|| MoolahCoreError::UnknownAccount(name.into()))
+    }
+
+    /// Each account's aggregated deltas including its side of every transfer, followed by the
+    /// combined fixed deltas in the portfolio's currency. Transfers cancel out of the combined
+    /// deltas, and each account's starting balance lands there on its start date.
+    fn aggregate_deltas(
+        &self,
+        end: &NaiveDate,
+    ) -> Result<
+        (
+            Vec<Aggregation<'_>>,
+            BTreeMap<NaiveDate, AggregatedDelta<'_>>,
+        ),
+        MoolahCoreError,
+    > {
+        let mut aggregated = self
+            .accounts
+            .iter()
+            .map(|account| account.aggregate_deltas(end))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let mut combined: BTreeMap<NaiveDate, AggregatedDelta> = BTreeMap::new();
+        for (account, aggregation) in self.accounts.iter().zip(&aggregated) {
+            let start = combined.entry(*account.start()).or_default();
+            start.deposit(
+                account
+                    .initial_value()
+                    .scale(self.account_exchange_rate(account, account.start())?),
+            )?;
+            for (date, agg_delta) in &aggregation.fixed {
+                let rate = self.account_exchange_rate(account, date)?;
+                combined.entry(*date).or_default().merge(agg_delta, rate)?;
+            }
+        }
+
+        for (id, transfer) in self.transfer_ids.iter().zip(&self.transfers) {
+            let from = self.account_index(transfer.from())?;
+            let to = self.account_index(transfer.to())?;
+            // A transfer only happens once both accounts exist, so it never affects just one
+            let start = self.accounts[from].start().max(self.accounts[to].start());
+
+            for occurrence in transfer.delta().occurrences(start, end) {
+                let date = &occurrence.date;
+                let debit = -self.accounts[from].exchange_rate(transfer.delta(), date)?;
+                let credit = self.accounts[to].exchange_rate(transfer.delta(), date)?;
+                aggregated[from].fixed.entry(*date).or_default().update(
+                    id.as_str(),
+                    &occurrence,
+                    debit,
+                )?;
+                aggregated[to].fixed.entry(*date).or_default().update(
+                    id.as_str(),
+                    &occurrence,
+                    credit,
+                )?;
+                combined.entry(*date).or_default().mention(id.as_str());
+            }
+        }
+
+        Ok((aggregated, combined))
+    }
+
+    fn account_exchange_rate(
+        &self,
+        account: &Prediction,
+        date: &NaiveDate,
+    ) -> Result<f64, MoolahCoreError> {
+        match (account.currency(), &self.currency) {
+            (None, _) => Ok(1.0),
+            (Some(from), Some(to)) => self.exchange_rates.rate(from, to, date),
+            (Some(from), None) => Err(MoolahCoreError::NoPortfolioCurrency {
+                account: account.name().into(),
+                currency: *from,
+            }),
+        }
+    }
+
+    /// Like deltas in a [`Prediction`], amounts in an account's currency are converted into the
+    /// portfolio's currency on the date they land. Transfers leave the combined timeline unchanged.
+    pub fn predict(&self, end: &NaiveDate) -> Result<PortfolioPrediction, MoolahCoreError> {
+        let (mut aggregated, combined) = self.aggregate_deltas(end)?;
+
+        let mut accounts = BTreeMap::new();
+        let mut combined = Aggregation {
+            fixed: combined,
+            ..Default::default()
+        };
+        for (account, aggregation) in self.accounts.iter().zip(&mut aggregated) {
+            accounts.insert(
+                account.name().into(),
+                accumulate(
+                    account.initial_value(),
+                    account.checkpoints(),
+                    aggregation,
+                    &mut Valuation::Expected,
+                )?,
+            );
+            // Balance-dependent deltas are only known once their account has run
+            for (date, evaluated) in &aggregation.evaluated {
+                let rate = self.account_exchange_rate(account, date)?;
+                combined
+                    .fixed
+                    .entry(*date)
+                    .or_default()
+                    .merge(evaluated, rate)?;
+            }
+            // Checkpoints shift the combined timeline by as much as they moved their account
+            for (date, shift) in &aggregation.checkpointed {
+                let rate = self.account_exchange_rate(account, date)?;
+                combined
+                    .fixed
+                    .entry(*date)
+                    .or_default()
+                    .deposit(shift.scale(rate))?;
+            }
+        }
+
+        Ok(PortfolioPrediction {
+            accounts,
+            combined: accumulate(Money::ZERO, &[], &mut combined, &mut Valuation::Expected)?,
+        })
+    }
+}
+
+/// The per-account and combined timelines of a [`Portfolio`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct PortfolioPrediction {
+    accounts: BTreeMap<String, BTreeMap<NaiveDate, PredictionState>>,
+    combined: BTreeMap<NaiveDate, PredictionState>,
+}
+
+impl PortfolioPrediction {
+    pub fn accounts(&self) -> &BTreeMap<String, BTreeMap<NaiveDate, PredictionState>> {
+        &self.accounts
+    }
+
+    pub fn account(&self, name: &str) -> Option<&BTreeMap<NaiveDate, PredictionState>> {
+        self.accounts.get(name)
+    }
+
+    pub fn combined(&self) -> &BTreeMap<NaiveDate, PredictionState> {
+        &self.combined
+    }
+}
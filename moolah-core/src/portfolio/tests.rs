@@ -0,0 +1,469 @@
+use super::*;
+use crate::currency::RateSchedule;
+use crate::date_helpers::naive_ymd;
+use crate::delta::{
+    Compounding, InterestDelta, MonthlyDelta, OneTimeDelta, Uncertainty, UncertaintyType,
+    WeeklyDelta,
+};
+use crate::prediction::Checkpoint;
+use chrono::Weekday;
+
+fn account(name: &str, initial_value: i32, deltas: Vec<Box<dyn Delta>>) -> Prediction {
+    Prediction::new(
+        name.into(),
+        naive_ymd(2023, 1, 1).unwrap(),
+        Money::from(initial_value),
+        deltas,
+    )
+}
+
+fn savings_transfer() -> Transfer {
+    Transfer::try_new(
+        "checking".into(),
+        "savings".into(),
+        Box::new(
+            MonthlyDelta::try_new(
+                "save".into(),
+                Money::from(300),
+                Some(Uncertainty::Balanced(UncertaintyType::Dollars(
+                    50.0.try_into().unwrap(),
+                ))),
+                naive_ymd(2023, 1, 1).unwrap(),
+                naive_ymd(2023, 3, 31).unwrap(),
+                15.try_into().unwrap(),
+                0,
+            )
+            .unwrap(),
+        ),
+    )
+    .unwrap()
+}
+
+fn household() -> Portfolio {
+    let salary = MonthlyDelta::try_new(
+        "salary".into(),
+        Money::from(2000),
+        None,
+        naive_ymd(2023, 1, 1).unwrap(),
+        naive_ymd(2023, 3, 31).unwrap(),
+        1.try_into().unwrap(),
+        0,
+    )
+    .unwrap();
+
+    Portfolio::try_new(
+        "household".into(),
+        vec![
+            account("checking", 1000, vec![Box::new(salary)]),
+            account("savings", 5000, vec![]),
+        ],
+        vec![savings_transfer()],
+    )
+    .unwrap()
+}
+
+#[test]
+fn test_transfer_to_same_account() {
+    assert!(matches!(
+        Transfer::try_new(
+            "checking".into(),
+            "checking".into(),
+            Box::<OneTimeDelta>::default()
+        ),
+        Err(MoolahCoreError::TransferToSameAccount(account)) if account == "checking"
+    ));
+
+    let interest = InterestDelta::try_new(
+        "interest".into(),
+        5.0,
+        None,
+        Compounding::Monthly,
+        naive_ymd(2023, 1, 1).unwrap(),
+        None,
+    )
+    .unwrap();
+    assert!(matches!(
+        Transfer::try_new("checking".into(), "savings".into(), Box::new(interest)),
+        Err(MoolahCoreError::BalanceDependentTransfer(name)) if name == "interest"
+    ));
+}
+
+#[test]
+fn test_invalid_accounts() {
+    assert!(matches!(
+        Portfolio::try_new(
+            "household".into(),
+            vec![account("checking", 0, vec![]), account("checking", 0, vec![])],
+            vec![],
+        ),
+        Err(MoolahCoreError::DuplicateAccount(account)) if account == "checking"
+    ));
+
+    assert!(matches!(
+        Portfolio::try_new(
+            "household".into(),
+            vec![account("checking", 0, vec![])],
+            vec![savings_transfer()],
+        ),
+        Err(MoolahCoreError::UnknownAccount(account)) if account == "savings"
+    ));
+
+    let rent = || -> Vec<Box<dyn Delta>> {
+        vec![Box::new(
+            OneTimeDelta::try_new(
+                "rent".into(),
+                Money::from(-500),
+                None,
+                naive_ymd(2023, 1, 1).unwrap(),
+            )
+            .unwrap(),
+        )]
+    };
+    assert!(matches!(
+        Portfolio::try_new(
+            "household".into(),
+            vec![account("checking", 0, rent()), account("savings", 0, rent())],
+            vec![],
+        ),
+        Err(MoolahCoreError::DuplicateDeltaId(id)) if id == "rent"
+    ));
+}
+
+#[test]
+fn test_transfer_ids() {
+    let salary_transfer = Transfer::try_new(
+        "checking".into(),
+        "savings".into(),
+        Box::new(
+            OneTimeDelta::try_new(
+                "salary".into(),
+                Money::from(100),
+                None,
+                naive_ymd(2023, 1, 1).unwrap(),
+            )
+            .unwrap(),
+        ),
+    )
+    .unwrap();
+    let portfolio = Portfolio::try_new(
+        "household".into(),
+        household().accounts,
+        vec![savings_transfer(), savings_transfer(), salary_transfer],
+    )
+    .unwrap();
+    let ids: Vec<_> = portfolio
+        .transfer_ids()
+        .iter()
+        .map(DeltaId::as_str)
+        .collect();
+    assert_eq!(ids, ["save", "save-2", "salary-2"]);
+    assert_eq!(portfolio.transfer("salary-2").unwrap().name(), "salary");
+    assert!(portfolio.transfer("salary").is_none());
+
+    let pred = portfolio.predict(&naive_ymd(2023, 1, 31).unwrap()).unwrap();
+    let checking = pred.account("checking").unwrap();
+    assert_eq!(
+        checking[&naive_ymd(2023, 1, 1).unwrap()],
+        PredictionState::new(
+            Money::from(2900),
+            Money::from(2900),
+            Money::from(2900),
+            ["salary".into(), "salary-2".into()].into()
+        )
+    );
+    assert_eq!(
+        checking[&naive_ymd(2023, 1, 15).unwrap()].impactful_deltas(),
+        &["save".to_string(), "save-2".to_string()].into()
+    );
+
+    assert!(matches!(
+        portfolio.with_transfer(DeltaId::try_new("salary").unwrap(), savings_transfer()),
+        Err(MoolahCoreError::DuplicateDeltaId(id)) if id == "salary"
+    ));
+}
+
+#[test]
+fn test_per_account_timelines() {
+    let p = household();
+    let pred = p.predict(&naive_ymd(2023, 2, 28).unwrap()).unwrap();
+
+    let checking = pred.account("checking").unwrap();
+    assert_eq!(
+        checking[&naive_ymd(2023, 1, 1).unwrap()],
+        PredictionState::new(
+            Money::from(3000),
+            Money::from(3000),
+            Money::from(3000),
+            ["salary".into()].into()
+        )
+    );
+    assert_eq!(
+        checking[&naive_ymd(2023, 1, 15).unwrap()],
+        PredictionState::new(
+            Money::from(2700),
+            Money::from(2650),
+            Money::from(2750),
+            ["save".into()].into()
+        )
+    );
+    assert_eq!(
+        checking[&naive_ymd(2023, 2, 15).unwrap()].value(),
+        Money::from(4400)
+    );
+
+    let savings = pred.account("savings").unwrap();
+    assert_eq!(
+        savings.keys().copied().collect::<Vec<_>>(),
+        [
+            naive_ymd(2023, 1, 1).unwrap(),
+            naive_ymd(2023, 1, 15).unwrap(),
+            naive_ymd(2023, 2, 15).unwrap()
+        ]
+    );
+    assert_eq!(
+        savings[&naive_ymd(2023, 2, 15).unwrap()],
+        PredictionState::new(
+            Money::from(5600),
+            Money::from(5500),
+            Money::from(5700),
+            ["save".into()].into()
+        )
+    );
+}
+
+#[test]
+fn test_combined_timeline() {
+    let p = household();
+    let pred = p.predict(&naive_ymd(2023, 2, 28).unwrap()).unwrap();
+    let combined = pred.combined();
+
+    assert_eq!(
+        combined.keys().copied().collect::<Vec<_>>(),
+        [
+            naive_ymd(2023, 1, 1).unwrap(),
+            naive_ymd(2023, 1, 15).unwrap(),
+            naive_ymd(2023, 2, 1).unwrap(),
+            naive_ymd(2023, 2, 15).unwrap()
+        ]
+    );
+    // Transfers move money between accounts without changing the total
+    assert_eq!(
+        combined[&naive_ymd(2023, 1, 15).unwrap()],
+        PredictionState::new(
+            Money::from(8000),
+            Money::from(8000),
+            Money::from(8000),
+            ["save".into()].into()
+        )
+    );
+    assert_eq!(
+        combined[&naive_ymd(2023, 2, 1).unwrap()],
+        PredictionState::new(
+            Money::from(10000),
+            Money::from(10000),
+            Money::from(10000),
+            ["salary".into()].into()
+        )
+    );
+}
+
+#[test]
+fn test_transfer_before_account_starts() {
+    let late_savings = Prediction::new(
+        "savings".into(),
+        naive_ymd(2023, 2, 1).unwrap(),
+        Money::from(0),
+        vec![],
+    );
+    let p = Portfolio::try_new(
+        "household".into(),
+        vec![account("checking", 1000, vec![]), late_savings],
+        vec![savings_transfer()],
+    )
+    .unwrap();
+    let pred = p.predict(&naive_ymd(2023, 2, 28).unwrap()).unwrap();
+
+    let values = |timeline: &BTreeMap<NaiveDate, PredictionState>| {
+        timeline
+            .values()
+            .map(|state| state.value())
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(
+        values(pred.account("checking").unwrap()),
+        [Money::from(1000), Money::from(700)]
+    );
+    assert_eq!(
+        values(pred.account("savings").unwrap()),
+        [Money::from(0), Money::from(300)]
+    );
+    assert_eq!(
+        values(pred.combined()),
+        [Money::from(1000), Money::from(1000), Money::from(1000)]
+    );
+}
+
+#[test]
+fn test_accounts_in_different_currencies() {
+    let usd = Currency::try_new("USD").unwrap();
+    let eur = Currency::try_new("EUR").unwrap();
+    let mut rates = ExchangeRates::default();
+    rates.insert(eur, usd, RateSchedule::try_fixed(1.5).unwrap());
+
+    let groceries = WeeklyDelta::try_new(
+        "groceries".into(),
+        Money::from(-100),
+        None,
+        naive_ymd(2023, 1, 1).unwrap(),
+        naive_ymd(2023, 1, 14).unwrap(),
+        Some(Weekday::Sat),
+        0,
+    )
+    .unwrap();
+    let transfer = Transfer::try_new(
+        "checking".into(),
+        "euro".into(),
+        Box::new(
+            OneTimeDelta::try_new(
+                "top up".into(),
+                Money::from(150),
+                None,
+                naive_ymd(2023, 1, 10).unwrap(),
+            )
+            .unwrap()
+            .with_currency(usd),
+        ),
+    )
+    .unwrap();
+
+    let portfolio = Portfolio::try_new(
+        "travel".into(),
+        vec![
+            account("checking", 1000, vec![]).with_currency(usd),
+            account("euro", 200, vec![Box::new(groceries)])
+                .with_currency(eur)
+                .with_exchange_rates(rates.clone()),
+        ],
+        vec![transfer],
+    )
+    .unwrap();
+
+    assert!(matches!(
+        portfolio.predict(&naive_ymd(2023, 1, 31).unwrap()),
+        Err(MoolahCoreError::NoPortfolioCurrency { account, .. }) if account == "checking"
+    ));
+
+    let portfolio = portfolio.with_currency(usd).with_exchange_rates(rates);
+    let pred = portfolio.predict(&naive_ymd(2023, 1, 31).unwrap()).unwrap();
+
+    let euro = pred.account("euro").unwrap();
+    assert_eq!(
+        euro.values().map(|state| state.value()).collect::<Vec<_>>(),
+        [
+            Money::from(200),
+            Money::from(100),
+            Money::from(200),
+            Money::from(100)
+        ]
+    );
+    assert_eq!(
+        pred.combined()
+            .values()
+            .map(|state| state.value())
+            .collect::<Vec<_>>(),
+        [
+            Money::from(1300),
+            Money::from(1150),
+            Money::from(1150),
+            Money::from(1000)
+        ]
+    );
+}
+
+#[test]
+fn test_interest_on_transferred_balance() {
+    let interest = InterestDelta::try_new(
+        "interest".into(),
+        12.0,
+        None,
+        Compounding::Monthly,
+        naive_ymd(2023, 1, 1).unwrap(),
+        naive_ymd(2023, 12, 31).unwrap(),
+    )
+    .unwrap();
+    let p = Portfolio::try_new(
+        "household".into(),
+        vec![
+            account("checking", 1000, vec![]),
+            account("savings", 5000, vec![Box::new(interest)]),
+        ],
+        vec![savings_transfer()],
+    )
+    .unwrap();
+    let pred = p.predict(&naive_ymd(2023, 3, 1).unwrap()).unwrap();
+
+    let values = |timeline: &BTreeMap<NaiveDate, PredictionState>| {
+        timeline
+            .values()
+            .map(|state| state.value())
+            .collect::<Vec<_>>()
+    };
+    // 1% a month of the savings balance, including what was transferred in
+    assert_eq!(
+        values(pred.account("savings").unwrap()),
+        [
+            Money::from(5000),
+            Money::from(5300),
+            Money::from(5353),
+            Money::from(5653),
+            Money::from_cents(570953)
+        ]
+    );
+    assert_eq!(
+        values(pred.combined()),
+        [
+            Money::from(6000),
+            Money::from(6000),
+            Money::from(6053),
+            Money::from(6053),
+            Money::from_cents(610953)
+        ]
+    );
+}
+
+#[test]
+fn test_account_checkpoint() {
+    let checkpoint = naive_ymd(2023, 2, 10).unwrap();
+    let salary = MonthlyDelta::try_new(
+        "salary".into(),
+        Money::from(2000),
+        None,
+        naive_ymd(2023, 1, 1).unwrap(),
+        None,
+        1.try_into().unwrap(),
+        0,
+    )
+    .unwrap();
+    let portfolio = Portfolio::try_new(
+        "household".into(),
+        vec![
+            account("checking", 1000, vec![Box::new(salary)])
+                .with_checkpoints(vec![Checkpoint::new(checkpoint, Money::from(2500))]),
+            account("savings", 5000, vec![]),
+        ],
+        vec![savings_transfer()],
+    )
+    .unwrap();
+
+    let prediction = portfolio.predict(&naive_ymd(2023, 3, 31).unwrap()).unwrap();
+    let checking = &prediction.account("checking").unwrap()[&checkpoint];
+    // 1000 + 2 * 2000 salary - 300 saved in January
+    assert_eq!(checking.variance(), Some(Money::from(-2200)));
+    assert_eq!(checking.value(), Money::from(2500));
+
+    // The combined timeline moves with the account
+    let combined = &prediction.combined()[&checkpoint];
+    assert_eq!(combined.value(), Money::from(2500 + 5300));
+    assert_eq!(combined.min_uncertainty_val(), Money::from(2500 + 5300));
+    assert_eq!(combined.max_uncertainty_val(), Money::from(2500 + 5300));
+}
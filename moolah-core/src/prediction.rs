@@ -1,15 +1,36 @@
 #[cfg(test)]
 mod tests;
 
-use crate::delta::Delta;
+#[cfg(feature = "serde")]
+use crate::delta::tagged::{IdentifiedDeltaRef, UncheckedIdentifiedDelta};
+use crate::{
+    currency::{Currency, ExchangeRates},
+    delta::{Delta, DeltaId, DeltaMut, Occurrence},
+    errors::MoolahCoreError,
+    explanation::Contribution,
+    money::Money,
+};
 use chrono::{Local, NaiveDate};
+use rand::RngCore;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize, Serializer};
 use std::collections::{BTreeMap, HashSet};
 
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize),
+    serde(try_from = "RawPrediction")
+)]
 pub struct Prediction {
     name: String,
     start: NaiveDate,
-    initial_value: f64,
+    initial_value: Money,
+    currency: Option<Currency>,
+    exchange_rates: ExchangeRates,
     deltas: Vec<Box<dyn Delta>>,
+    /// The id of each of `deltas`, all different
+    ids: Vec<DeltaId>,
+    checkpoints: Vec<Checkpoint>,
 }
 
 impl Default for Prediction {
@@ -18,24 +39,151 @@ impl Default for Prediction {
             name: Default::default(),
             start: Local::now().date_naive(),
             initial_value: Default::default(),
+            currency: Default::default(),
+            exchange_rates: Default::default(),
             deltas: Default::default(),
+            ids: Default::default(),
+            checkpoints: Default::default(),
         }
     }
 }
 
 impl Prediction {
+    /// Each delta's id is its name, with a `-2`, `-3`, ... suffix for any name already taken
     pub fn new(
         name: String,
         start: NaiveDate,
-        initial_value: f64,
+        initial_value: Money,
         deltas: Vec<Box<dyn Delta>>,
     ) -> Self {
-        Prediction {
+        let mut prediction = Prediction {
             name,
             start,
             initial_value,
-            deltas,
+            currency: None,
+            exchange_rates: Default::default(),
+            deltas: vec![],
+            ids: vec![],
+            checkpoints: vec![],
+        };
+        for delta in deltas {
+            prediction.add_delta(delta);
+        }
+        prediction
+    }
+
+    /// Add `delta` under `id`, which no other delta may have
+    pub fn with_delta(
+        mut self,
+        id: DeltaId,
+        delta: Box<dyn Delta>,
+    ) -> Result<Self, MoolahCoreError> {
+        self.add_delta_with_id(id, delta)?;
+        Ok(self)
+    }
+
+    /// Add `deltas`, giving those without an id one from their name that none of the others have
+    #[cfg(feature = "serde")]
+    pub(crate) fn with_identified_deltas(
+        mut self,
+        deltas: Vec<(Option<DeltaId>, Box<dyn Delta>)>,
+    ) -> Result<Self, MoolahCoreError> {
+        let mut chosen = HashSet::new();
+        for id in deltas.iter().filter_map(|(id, _)| id.as_ref()) {
+            if self.ids.contains(id) | !chosen.insert(id.as_str()) {
+                return Err(MoolahCoreError::DuplicateDeltaId(id.to_string()));
+            }
         }
+
+        let mut identified = Vec::with_capacity(deltas.len());
+        for (id, delta) in &deltas {
+            let id = match id {
+                Some(id) => id.clone(),
+                None => self.free_id(delta.name(), |id| {
+                    chosen.contains(id)
+                        | identified
+                            .iter()
+                            .any(|other: &DeltaId| other.as_str() == id)
+                }),
+            };
+            identified.push(id);
+        }
+        self.ids.extend(identified);
+        self.deltas
+            .extend(deltas.into_iter().map(|(_, delta)| delta));
+        Ok(self)
+    }
+
+    fn free_id(&self, name: &str, is_taken: impl Fn(&str) -> bool) -> DeltaId {
+        DeltaId::from_name(name, |id| {
+            is_taken(id) | self.ids.iter().any(|other| other.as_str() == id)
+        })
+    }
+
+    /// Report the prediction in `currency`, which is also the currency of `initial_value` and of
+    /// any delta without its own currency
+    pub fn with_currency(mut self, currency: Currency) -> Self {
+        self.currency = Some(currency);
+        self
+    }
+
+    pub fn with_exchange_rates(mut self, exchange_rates: ExchangeRates) -> Self {
+        self.exchange_rates = exchange_rates;
+        self
+    }
+
+    /// Restart the prediction from balances observed along the way. Checkpoints before the
+    /// prediction's start are ignored.
+    pub fn with_checkpoints(mut self, checkpoints: Vec<Checkpoint>) -> Self {
+        self.set_checkpoints(checkpoints);
+        self
+    }
+
+    pub fn set_name(&mut self, name: String) {
+        self.name = name;
+    }
+
+    pub fn set_start(&mut self, start: NaiveDate) {
+        self.start = start;
+    }
+
+    pub fn set_initial_value(&mut self, initial_value: Money) {
+        self.initial_value = initial_value;
+    }
+
+    pub fn set_currency(&mut self, currency: Option<Currency>) {
+        self.currency = currency;
+    }
+
+    pub fn set_exchange_rates(&mut self, exchange_rates: ExchangeRates) {
+        self.exchange_rates = exchange_rates;
+    }
+
+    pub fn set_checkpoints(&mut self, mut checkpoints: Vec<Checkpoint>) {
+        checkpoints.sort_by_key(|checkpoint| checkpoint.date);
+        self.checkpoints = checkpoints;
+    }
+
+    /// Add `delta` with an id from its name, as in [`Prediction::new`], and return the id
+    pub fn add_delta(&mut self, delta: Box<dyn Delta>) -> &DeltaId {
+        let id = self.free_id(delta.name(), |_| false);
+        self.ids.push(id);
+        self.deltas.push(delta);
+        &self.ids[self.ids.len() - 1]
+    }
+
+    /// Add `delta` under `id`, which no other delta may have
+    pub fn add_delta_with_id(
+        &mut self,
+        id: DeltaId,
+        delta: Box<dyn Delta>,
+    ) -> Result<(), MoolahCoreError> {
+        if self.ids.contains(&id) {
+            return Err(MoolahCoreError::DuplicateDeltaId(id.into()));
+        }
+        self.ids.push(id);
+        self.deltas.push(delta);
+        Ok(())
     }
 
     pub fn name(&self) -> &str {
@@ -46,73 +194,471 @@ impl Prediction {
         &self.start
     }
 
-    pub fn initial_value(&self) -> f64 {
+    pub fn initial_value(&self) -> Money {
         self.initial_value
     }
 
+    pub fn currency(&self) -> Option<&Currency> {
+        self.currency.as_ref()
+    }
+
+    pub fn exchange_rates(&self) -> &ExchangeRates {
+        &self.exchange_rates
+    }
+
     pub fn deltas(&self) -> &[Box<dyn Delta>] {
         &self.deltas
     }
+
+    /// The id of each of [`Prediction::deltas`], in the same order
+    pub fn ids(&self) -> &[DeltaId] {
+        &self.ids
+    }
+
+    /// Each delta alongside its id
+    pub fn identified_deltas(&self) -> impl Iterator<Item = (&DeltaId, &dyn Delta)> {
+        self.ids
+            .iter()
+            .zip(self.deltas.iter().map(|delta| &**delta))
+    }
+
+    pub fn delta(&self, id: &str) -> Option<&dyn Delta> {
+        self.position(id).ok().map(|index| &*self.deltas[index])
+    }
+
+    /// The delta with `id` as its concrete type, to change in place with its setters. `None` if
+    /// there is no such delta or it is defined outside this crate, in which case it can still be
+    /// swapped out with [`Prediction::replace_delta`].
+    pub fn delta_mut(&mut self, id: &str) -> Option<DeltaMut<'_>> {
+        let index = self.position(id).ok()?;
+        self.deltas[index].edit()
+    }
+
+    /// Put `delta` in place of the delta with `id`, keeping its id and position, and hand back the
+    /// delta it replaced
+    pub fn replace_delta(
+        &mut self,
+        id: &str,
+        delta: Box<dyn Delta>,
+    ) -> Result<Box<dyn Delta>, MoolahCoreError> {
+        let index = self.position(id)?;
+        Ok(std::mem::replace(&mut self.deltas[index], delta))
+    }
+
+    pub fn remove_delta(&mut self, id: &str) -> Result<Box<dyn Delta>, MoolahCoreError> {
+        let index = self.position(id)?;
+        self.ids.remove(index);
+        Ok(self.deltas.remove(index))
+    }
+
+    fn position(&self, id: &str) -> Result<usize, MoolahCoreError> {
+        self.ids
+            .iter()
+            .position(|other| other.as_str() == id)
+            .ok_or_else(|| MoolahCoreError::UnknownDelta(id.into()))
+    }
+
+    pub fn checkpoints(&self) -> &[Checkpoint] {
+        &self.checkpoints
+    }
+}
+
+/// A balance actually observed on a date, such as from a bank statement. The prediction restarts
+/// from it once that date's deltas have landed, moving its value and uncertainty band `weight` of
+/// the way toward the balance.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(try_from = "RawCheckpoint")
+)]
+pub struct Checkpoint {
+    date: NaiveDate,
+    balance: Money,
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "is_full_weight"))]
+    weight: f64,
+}
+
+impl Checkpoint {
+    /// A checkpoint resetting the prediction to exactly `balance`
+    pub fn new(date: NaiveDate, balance: Money) -> Self {
+        Checkpoint {
+            date,
+            balance,
+            weight: 1.0,
+        }
+    }
+
+    /// Only move the prediction `weight` of the way toward the balance, such as 0.5 for halfway,
+    /// when the observation itself is uncertain
+    pub fn with_weight(mut self, weight: f64) -> Result<Self, MoolahCoreError> {
+        if !weight.is_finite() | (weight <= 0.0) | (weight > 1.0) {
+            return Err(MoolahCoreError::InvalidCheckpointWeight(weight));
+        }
+        self.weight = weight;
+        Ok(self)
+    }
+
+    pub fn date(&self) -> &NaiveDate {
+        &self.date
+    }
+
+    pub fn balance(&self) -> Money {
+        self.balance
+    }
+
+    pub fn weight(&self) -> f64 {
+        self.weight
+    }
+
+    /// `amount` moved `weight` of the way toward the balance
+    pub(crate) fn toward(&self, amount: Money) -> Money {
+        amount + (self.balance - amount).scale(self.weight)
+    }
+}
+
+#[cfg(feature = "serde")]
+#[derive(Serialize)]
+struct PredictionRef<'a> {
+    name: &'a str,
+    start: &'a NaiveDate,
+    initial_value: Money,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    currency: Option<&'a Currency>,
+    #[serde(skip_serializing_if = "has_no_rates")]
+    exchange_rates: &'a ExchangeRates,
+    deltas: Vec<IdentifiedDeltaRef<'a>>,
+    #[serde(skip_serializing_if = "<[_]>::is_empty")]
+    checkpoints: &'a [Checkpoint],
+}
+
+#[cfg(feature = "serde")]
+fn has_no_rates(exchange_rates: &&ExchangeRates) -> bool {
+    exchange_rates.is_empty()
+}
+
+#[cfg(feature = "serde")]
+impl Serialize for Prediction {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        PredictionRef {
+            name: &self.name,
+            start: &self.start,
+            initial_value: self.initial_value,
+            currency: self.currency.as_ref(),
+            exchange_rates: &self.exchange_rates,
+            deltas: self
+                .identified_deltas()
+                .map(|(id, delta)| IdentifiedDeltaRef {
+                    id: Some(id),
+                    delta: delta.tagged(),
+                })
+                .collect(),
+            checkpoints: &self.checkpoints,
+        }
+        .serialize(serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+#[derive(Deserialize)]
+pub(crate) struct RawPrediction {
+    name: String,
+    start: NaiveDate,
+    initial_value: Money,
+    #[serde(default)]
+    currency: Option<Currency>,
+    #[serde(default)]
+    exchange_rates: ExchangeRates,
+    deltas: Vec<UncheckedIdentifiedDelta>,
+    #[serde(default)]
+    checkpoints: Vec<Checkpoint>,
+}
+
+#[cfg(feature = "serde")]
+impl TryFrom<RawPrediction> for Prediction {
+    type Error = MoolahCoreError;
+
+    fn try_from(raw: RawPrediction) -> Result<Self, Self::Error> {
+        let deltas = raw
+            .deltas
+            .into_iter()
+            .map(|unchecked| Ok((unchecked.id, Box::try_from(unchecked.delta)?)))
+            .collect::<Result<Vec<_>, MoolahCoreError>>()?;
+        let prediction = Prediction::new(raw.name, raw.start, raw.initial_value, vec![])
+            .with_identified_deltas(deltas)?
+            .with_exchange_rates(raw.exchange_rates)
+            .with_checkpoints(raw.checkpoints);
+        Ok(match raw.currency {
+            Some(currency) => prediction.with_currency(currency),
+            None => prediction,
+        })
+    }
+}
+
+#[cfg(feature = "serde")]
+fn is_full_weight(weight: &f64) -> bool {
+    *weight == 1.0
+}
+
+#[cfg(feature = "serde")]
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+pub(crate) struct RawCheckpoint {
+    date: NaiveDate,
+    balance: Money,
+    #[serde(default = "full_weight")]
+    weight: f64,
+}
+
+#[cfg(feature = "serde")]
+fn full_weight() -> f64 {
+    1.0
+}
+
+#[cfg(feature = "serde")]
+impl TryFrom<RawCheckpoint> for Checkpoint {
+    type Error = MoolahCoreError;
+
+    fn try_from(raw: RawCheckpoint) -> Result<Self, Self::Error> {
+        Checkpoint::new(raw.date, raw.balance).with_weight(raw.weight)
+    }
 }
 
 #[derive(Debug, PartialEq, Default)]
 pub(crate) struct AggregatedDelta<'a> {
-    value: f64,
-    min_uncertainty_val: f64,
-    max_uncertainty_val: f64,
+    value: Money,
+    min_uncertainty_val: Money,
+    max_uncertainty_val: Money,
+    /// The ids of the deltas that landed
     impactful_deltas: Vec<&'a str>,
 }
 
 impl<'a> AggregatedDelta<'a> {
-    pub fn update(&mut self, delta: &'a dyn Delta) {
-        self.value += delta.value();
-        self.min_uncertainty_val += delta.min_uncertainty_value();
-        self.max_uncertainty_val += delta.max_uncertainty_value();
-        self.impactful_deltas.push(delta.name());
+    /// Add an `occurrence` of the delta with `id` scaled by `factor`, returning the
+    /// `(value, min, max)` added. A negative factor, as for the debited side of a transfer, swaps
+    /// the occurrence's min and max.
+    pub fn update(
+        &mut self,
+        id: &'a str,
+        occurrence: &Occurrence,
+        factor: f64,
+    ) -> Result<(Money, Money, Money), MoolahCoreError> {
+        let (min, max) = (occurrence.min.scale(factor), occurrence.max.scale(factor));
+        let (value, min, max) = (occurrence.value.scale(factor), min.min(max), min.max(max));
+        self.add(value, min, max)?;
+        self.impactful_deltas.push(id);
+        Ok((value, min, max))
+    }
+
+    /// Add everything in `other`, scaled by a non-negative `factor`
+    pub fn merge(
+        &mut self,
+        other: &AggregatedDelta<'a>,
+        factor: f64,
+    ) -> Result<(), MoolahCoreError> {
+        self.add(
+            other.value.scale(factor),
+            other.min_uncertainty_val.scale(factor),
+            other.max_uncertainty_val.scale(factor),
+        )?;
+        self.impactful_deltas.extend(&other.impactful_deltas);
+        Ok(())
     }
+
+    /// Record that the delta with `id` landed without changing the balance
+    pub fn mention(&mut self, id: &'a str) {
+        self.impactful_deltas.push(id);
+    }
+
+    pub fn deposit(&mut self, amount: Money) -> Result<(), MoolahCoreError> {
+        self.add(amount, amount, amount)
+    }
+
+    /// Add a balance-dependent `delta` landing on a prediction in `state`, valued by
+    /// `valuation`, returning the `(value, min, max)` added
+    pub fn evaluate(
+        &mut self,
+        id: &'a str,
+        delta: &'a dyn Delta,
+        state: &PredictionState,
+        valuation: &mut Valuation,
+    ) -> Result<(Money, Money, Money), MoolahCoreError> {
+        let (value, min, max) = valuation.evaluate(delta, state);
+        self.add(value, min, max)?;
+        self.impactful_deltas.push(id);
+        Ok((value, min, max))
+    }
+
+    fn add(&mut self, value: Money, min: Money, max: Money) -> Result<(), MoolahCoreError> {
+        let sum = |total: Money, amount| {
+            total
+                .checked_add(amount)
+                .ok_or(MoolahCoreError::MoneyOverflow)
+        };
+        self.value = sum(self.value, value)?;
+        self.min_uncertainty_val = sum(self.min_uncertainty_val, min)?;
+        self.max_uncertainty_val = sum(self.max_uncertainty_val, max)?;
+        Ok(())
+    }
+}
+
+/// How deltas are valued as they are aggregated and accumulated: by their expected amounts and
+/// uncertainty, or by a random draw of each occurrence for one trial of a simulation
+pub(crate) enum Valuation<'r> {
+    Expected,
+    Sampled(&'r mut dyn RngCore),
+}
+
+impl Valuation<'_> {
+    fn occurrence(&mut self, delta: &dyn Delta, occurrence: Occurrence) -> Occurrence {
+        match self {
+            Valuation::Expected => occurrence,
+            Valuation::Sampled(rng) => {
+                let value = delta.sample_on(&occurrence.date, &mut **rng);
+                Occurrence {
+                    value,
+                    min: value,
+                    max: value,
+                    ..occurrence
+                }
+            }
+        }
+    }
+
+    fn evaluate(&mut self, delta: &dyn Delta, state: &PredictionState) -> (Money, Money, Money) {
+        match self {
+            Valuation::Expected => delta.evaluate(state),
+            Valuation::Sampled(rng) => {
+                let value = delta.sample_evaluate(state.value(), &mut **rng);
+                (value, value, value)
+            }
+        }
+    }
+}
+
+/// Deltas gathered by date. Balance-dependent deltas cannot be summed up front, so they are kept
+/// aside until [`accumulate`] evaluates them.
+#[derive(Default)]
+pub(crate) struct Aggregation<'a> {
+    pub fixed: BTreeMap<NaiveDate, AggregatedDelta<'a>>,
+    /// Each with its position among the prediction's deltas and its id
+    pub balance_dependent: BTreeMap<NaiveDate, Vec<(usize, &'a str, &'a dyn Delta)>>,
+    /// What the balance-dependent deltas added, filled in by [`accumulate`]
+    pub evaluated: BTreeMap<NaiveDate, AggregatedDelta<'a>>,
+    /// How far checkpoints moved the value, filled in by [`accumulate`]
+    pub checkpointed: BTreeMap<NaiveDate, Money>,
+    /// What each delta added, with balance-dependent deltas filled in by [`accumulate`]
+    pub contributions: BTreeMap<NaiveDate, Vec<Contribution<'a>>>,
 }
 
 impl Prediction {
-    fn aggregate_deltas(&self, end: &NaiveDate) -> BTreeMap<NaiveDate, AggregatedDelta> {
+    pub(crate) fn exchange_rate(
+        &self,
+        delta: &dyn Delta,
+        date: &NaiveDate,
+    ) -> Result<f64, MoolahCoreError> {
+        match (delta.currency(), &self.currency) {
+            (None, _) => Ok(1.0),
+            (Some(from), Some(to)) => self.exchange_rates.rate(from, to, date),
+            (Some(from), None) => Err(MoolahCoreError::NoReportingCurrency {
+                delta: delta.name().into(),
+                currency: *from,
+            }),
+        }
+    }
+
+    pub(crate) fn aggregate_deltas(
+        &self,
+        end: &NaiveDate,
+    ) -> Result<Aggregation<'_>, MoolahCoreError> {
+        self.aggregate(self.identified_ids(), end, &mut Valuation::Expected)
+    }
+
+    /// Each delta alongside its id as a plain string, as aggregation tracks them
+    pub(crate) fn identified_ids(&self) -> impl Iterator<Item = (&str, &dyn Delta)> {
+        self.identified_deltas()
+            .map(|(id, delta)| (id.as_str(), delta))
+    }
+
+    pub(crate) fn aggregate<'a>(
+        &self,
+        deltas: impl IntoIterator<Item = (&'a str, &'a dyn Delta)>,
+        end: &NaiveDate,
+        valuation: &mut Valuation,
+    ) -> Result<Aggregation<'a>, MoolahCoreError> {
         let initial = AggregatedDelta::default();
-        let mut deltas: BTreeMap<NaiveDate, AggregatedDelta> =
+        let mut fixed: BTreeMap<NaiveDate, AggregatedDelta> =
             BTreeMap::from([(*self.start(), initial)]);
+        let mut balance_dependent: BTreeMap<NaiveDate, Vec<(usize, &str, &dyn Delta)>> =
+            BTreeMap::new();
+        let mut contributions: BTreeMap<NaiveDate, Vec<Contribution>> = BTreeMap::new();
 
-        for delta in self.deltas() {
-            for date in delta.dates() {
-                if (*date >= self.start) & (date <= end) {
-                    deltas
-                        .entry(*date)
-                        .and_modify(|pred_state| pred_state.update(&**delta))
-                        .or_insert_with(|| {
-                            let mut pred = AggregatedDelta::default();
-                            pred.update(&**delta);
-                            pred
-                        });
+        for (index, (id, delta)) in deltas.into_iter().enumerate() {
+            for occurrence in delta.occurrences(&self.start, end) {
+                let date = occurrence.date;
+                if delta.is_balance_dependent() {
+                    fixed.entry(date).or_default();
+                    balance_dependent
+                        .entry(date)
+                        .or_default()
+                        .push((index, id, delta));
+                } else {
+                    let exchange_rate = self.exchange_rate(delta, &date)?;
+                    let occurrence = valuation.occurrence(delta, occurrence);
+                    let (value, min, max) =
+                        fixed
+                            .entry(date)
+                            .or_default()
+                            .update(id, &occurrence, exchange_rate)?;
+                    contributions.entry(date).or_default().push(Contribution {
+                        delta: index,
+                        id,
+                        name: delta.name(),
+                        value,
+                        min,
+                        max,
+                    });
                 }
             }
         }
 
         // Add in empty delta at start date if no deltas have been there
-        deltas.entry(self.start).or_default();
+        fixed.entry(self.start).or_default();
+        for checkpoint in &self.checkpoints {
+            if (checkpoint.date >= self.start) & (checkpoint.date <= *end) {
+                fixed.entry(checkpoint.date).or_default();
+            }
+        }
 
-        deltas
+        Ok(Aggregation {
+            fixed,
+            balance_dependent,
+            evaluated: BTreeMap::new(),
+            checkpointed: BTreeMap::new(),
+            contributions,
+        })
     }
 }
 
 #[derive(Debug, Clone, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct PredictionState {
-    value: f64,
-    min_uncertainty_val: f64,
-    max_uncertainty_val: f64,
+    value: Money,
+    min_uncertainty_val: Money,
+    max_uncertainty_val: Money,
     impactful_deltas: HashSet<String>,
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    variance: Option<Money>,
 }
 
 impl PredictionState {
     pub fn new(
-        value: f64,
-        min_uncertainty_val: f64,
-        max_uncertainty_val: f64,
+        value: Money,
+        min_uncertainty_val: Money,
+        max_uncertainty_val: Money,
         impactful_deltas: HashSet<String>,
     ) -> Self {
         PredictionState {
@@ -120,47 +666,137 @@ impl PredictionState {
             min_uncertainty_val,
             max_uncertainty_val,
             impactful_deltas,
+            variance: None,
         }
     }
 
-    pub fn value(&self) -> f64 {
+    pub fn value(&self) -> Money {
         self.value
     }
 
-    fn from(previous_pred_state: &PredictionState, delta_agg: &AggregatedDelta) -> Self {
-        PredictionState {
-            value: previous_pred_state.value + delta_agg.value,
-            min_uncertainty_val: previous_pred_state.min_uncertainty_val
-                + delta_agg.min_uncertainty_val,
-            max_uncertainty_val: previous_pred_state.max_uncertainty_val
-                + delta_agg.max_uncertainty_val,
+    pub fn min_uncertainty_val(&self) -> Money {
+        self.min_uncertainty_val
+    }
+
+    pub fn max_uncertainty_val(&self) -> Money {
+        self.max_uncertainty_val
+    }
+
+    /// The ids of the deltas that landed on this date
+    pub fn impactful_deltas(&self) -> &HashSet<String> {
+        &self.impactful_deltas
+    }
+
+    /// How far a checkpoint on this date found the observed balance from the predicted value,
+    /// positive when there was more money than predicted
+    pub fn variance(&self) -> Option<Money> {
+        self.variance
+    }
+
+    fn from(
+        previous_pred_state: &PredictionState,
+        delta_agg: &AggregatedDelta,
+    ) -> Result<Self, MoolahCoreError> {
+        let sum = |total: Money, amount| {
+            total
+                .checked_add(amount)
+                .ok_or(MoolahCoreError::MoneyOverflow)
+        };
+        Ok(PredictionState {
+            value: sum(previous_pred_state.value, delta_agg.value)?,
+            min_uncertainty_val: sum(
+                previous_pred_state.min_uncertainty_val,
+                delta_agg.min_uncertainty_val,
+            )?,
+            max_uncertainty_val: sum(
+                previous_pred_state.max_uncertainty_val,
+                delta_agg.max_uncertainty_val,
+            )?,
             impactful_deltas: delta_agg
                 .impactful_deltas
                 .clone()
                 .into_iter()
-                .map(|name| name.into())
+                .map(|id| id.into())
                 .collect(),
-        }
+            variance: None,
+        })
     }
 }
 
 impl Prediction {
-    pub fn predict(&self, end: &NaiveDate) -> BTreeMap<NaiveDate, PredictionState> {
-        let agg_deltas = self.aggregate_deltas(end);
+    /// Amounts in other currencies are converted into the prediction's currency with its exchange
+    /// rates on the date they land, so a missing rate is an error. Balance-dependent deltas are
+    /// evaluated against the state just before their date. Each checkpoint restarts the
+    /// timeline from its balance and records the variance it found.
+    pub fn predict(
+        &self,
+        end: &NaiveDate,
+    ) -> Result<BTreeMap<NaiveDate, PredictionState>, MoolahCoreError> {
+        self.predict_with(self.identified_ids(), end)
+    }
 
-        let init_pred_state = PredictionState::new(
-            self.initial_value,
+    /// [`Prediction::predict`] with `deltas`, each alongside its id, in place of the prediction's
+    /// own
+    pub(crate) fn predict_with<'a>(
+        &self,
+        deltas: impl IntoIterator<Item = (&'a str, &'a dyn Delta)>,
+        end: &NaiveDate,
+    ) -> Result<BTreeMap<NaiveDate, PredictionState>, MoolahCoreError> {
+        let mut valuation = Valuation::Expected;
+        accumulate(
             self.initial_value,
-            self.initial_value,
-            [].into(),
-        );
+            &self.checkpoints,
+            &mut self.aggregate(deltas, end, &mut valuation)?,
+            &mut valuation,
+        )
+    }
+}
 
-        agg_deltas
+pub(crate) fn accumulate(
+    initial_value: Money,
+    checkpoints: &[Checkpoint],
+    aggregation: &mut Aggregation,
+    valuation: &mut Valuation,
+) -> Result<BTreeMap<NaiveDate, PredictionState>, MoolahCoreError> {
+    let mut pred_state =
+        PredictionState::new(initial_value, initial_value, initial_value, [].into());
+    let mut timeline = BTreeMap::new();
+
+    for (date, agg_delta) in &aggregation.fixed {
+        let mut total = AggregatedDelta::default();
+        if let Some(deltas) = aggregation.balance_dependent.get(date) {
+            let evaluated = aggregation.evaluated.entry(*date).or_default();
+            let contributions = aggregation.contributions.entry(*date).or_default();
+            for (index, id, delta) in deltas {
+                let (value, min, max) = evaluated.evaluate(id, *delta, &pred_state, valuation)?;
+                contributions.push(Contribution {
+                    delta: *index,
+                    id,
+                    name: delta.name(),
+                    value,
+                    min,
+                    max,
+                });
+            }
+            contributions.sort_by_key(|contribution| contribution.delta);
+            total.merge(evaluated, 1.0)?;
+        }
+        total.merge(agg_delta, 1.0)?;
+
+        pred_state = PredictionState::from(&pred_state, &total)?;
+        for checkpoint in checkpoints
             .iter()
-            .scan(init_pred_state, |pred_state, (date, agg_delta)| {
-                *pred_state = PredictionState::from(pred_state, agg_delta);
-                Some((*date, pred_state.clone()))
-            })
-            .collect::<BTreeMap<_, _>>()
+            .filter(|checkpoint| checkpoint.date == *date)
+        {
+            let value = checkpoint.toward(pred_state.value);
+            pred_state.variance = Some(checkpoint.balance - pred_state.value);
+            *aggregation.checkpointed.entry(*date).or_default() += value - pred_state.value;
+            pred_state.value = value;
+            pred_state.min_uncertainty_val = checkpoint.toward(pred_state.min_uncertainty_val);
+            pred_state.max_uncertainty_val = checkpoint.toward(pred_state.max_uncertainty_val);
+        }
+        timeline.insert(*date, pred_state.clone());
     }
+
+    Ok(timeline)
 }
@@ -1,8 +1,9 @@
 use super::*;
 use crate::date_helpers::naive_ymd;
 use crate::delta::{
-    CustomDelta, DailyDelta, MonthlyDelta, OneTimeDelta, Uncertainty, UncertaintyType, WeeklyDelta,
-    YearlyDelta,
+    Compounding, CustomDelta, DailyDelta, DeltaMut, Escalation, ExtraPayment, InterestDelta,
+    LoanDelta, MonthlyDelta, OneTimeDelta, RRuleDelta, RateUncertainty, Uncertainty,
+    UncertaintyType, WeeklyDelta, YearlyDelta,
 };
 use chrono::Weekday;
 use std::fmt::Debug;
@@ -12,7 +13,7 @@ fn test_all_deltas_insertable() {
     let p = Prediction::new(
         String::from("test"),
         naive_ymd(2022, 10, 10).unwrap(),
-        1000.0,
+        Money::from(1000),
         vec![
             Box::<DailyDelta>::default(),
             Box::<OneTimeDelta>::default(),
@@ -20,19 +21,22 @@ fn test_all_deltas_insertable() {
             Box::<MonthlyDelta>::default(),
             Box::<YearlyDelta>::default(),
             Box::<CustomDelta>::default(),
+            Box::<InterestDelta>::default(),
+            Box::<LoanDelta>::default(),
+            Box::<RRuleDelta>::default(),
         ],
     );
 
-    assert_eq!(p.deltas().len(), 6);
+    assert_eq!(p.deltas().len(), 9);
 }
 
 #[test]
 fn test_default_delta_state() {
     let def = AggregatedDelta::default();
     let manual = AggregatedDelta {
-        value: 0.0,
-        min_uncertainty_val: 0.0,
-        max_uncertainty_val: 0.0,
+        value: Money::from(0),
+        min_uncertainty_val: Money::from(0),
+        max_uncertainty_val: Money::from(0),
         impactful_deltas: vec![],
     };
     assert_eq!(def, manual);
@@ -53,7 +57,7 @@ fn assert_btrees_eq<K: Debug + PartialEq, V: Debug + PartialEq>(
 #[test]
 fn test_no_deltas() {
     let p = Prediction::default();
-    let pred = p.predict(&Local::now().date_naive());
+    let pred = p.predict(&Local::now().date_naive()).unwrap();
 
     let expected = BTreeMap::from([(Local::now().date_naive(), PredictionState::default())]);
     assert_btrees_eq(&expected, &pred);
@@ -62,43 +66,59 @@ fn test_no_deltas() {
 #[test]
 fn test_1_delta() {
     let start_pred = naive_ymd(2022, 10, 28).unwrap();
-    let p = Prediction {
-        start: start_pred,
-        initial_value: 500.0,
-        deltas: vec![Box::new(
-            OneTimeDelta::try_new("test".into(), 100.0, None, naive_ymd(2022, 10, 31).unwrap())
-                .unwrap(),
+    let p = Prediction::new(
+        String::new(),
+        start_pred,
+        Money::from(500),
+        vec![Box::new(
+            OneTimeDelta::try_new(
+                "test".into(),
+                Money::from(100),
+                None,
+                naive_ymd(2022, 10, 31).unwrap(),
+            )
+            .unwrap(),
         )],
-        ..Default::default()
-    };
+    );
 
     let end_pred = naive_ymd(2022, 11, 1).unwrap();
 
     let expected = BTreeMap::from([
         (
             naive_ymd(2022, 10, 28).unwrap(),
-            PredictionState::new(500.0, 500.0, 500.0, [].into()),
+            PredictionState::new(
+                Money::from(500),
+                Money::from(500),
+                Money::from(500),
+                [].into(),
+            ),
         ),
         (
             naive_ymd(2022, 10, 31).unwrap(),
-            PredictionState::new(600.0, 600.0, 600.0, ["test".into()].into()),
+            PredictionState::new(
+                Money::from(600),
+                Money::from(600),
+                Money::from(600),
+                ["test".into()].into(),
+            ),
         ),
     ]);
 
-    assert_btrees_eq(&expected, &p.predict(&end_pred));
+    assert_btrees_eq(&expected, &p.predict(&end_pred).unwrap());
 }
 
 #[test]
 fn test_2_deltas_balanced_uncertainty() {
     let start_pred = naive_ymd(2022, 10, 28).unwrap();
-    let p = Prediction {
-        start: start_pred,
-        initial_value: 500.0,
-        deltas: vec![
+    let p = Prediction::new(
+        String::new(),
+        start_pred,
+        Money::from(500),
+        vec![
             Box::new(
                 OneTimeDelta::try_new(
                     "test 1".into(),
-                    200.0,
+                    Money::from(200),
                     Some(Uncertainty::Balanced(UncertaintyType::Dollars(
                         15.0.try_into().unwrap(),
                     ))),
@@ -109,7 +129,7 @@ fn test_2_deltas_balanced_uncertainty() {
             Box::new(
                 OneTimeDelta::try_new(
                     "test 2".into(),
-                    100.0,
+                    Money::from(100),
                     Some(Uncertainty::Balanced(UncertaintyType::Percent(
                         5.0.try_into().unwrap(),
                     ))),
@@ -118,41 +138,46 @@ fn test_2_deltas_balanced_uncertainty() {
                 .unwrap(),
             ),
         ],
-        ..Default::default()
-    };
+    );
 
     let end_pred = naive_ymd(2022, 11, 1).unwrap();
 
     let expected = BTreeMap::from([
         (
             naive_ymd(2022, 10, 28).unwrap(),
-            PredictionState::new(500.0, 500.0, 500.0, [].into()),
+            PredictionState::new(
+                Money::from(500),
+                Money::from(500),
+                Money::from(500),
+                [].into(),
+            ),
         ),
         (
             naive_ymd(2022, 10, 31).unwrap(),
             PredictionState::new(
-                800.0,
-                780.0,
-                820.0,
+                Money::from(800),
+                Money::from(780),
+                Money::from(820),
                 ["test 1".into(), "test 2".into()].into(),
             ),
         ),
     ]);
 
-    assert_btrees_eq(&expected, &p.predict(&end_pred));
+    assert_btrees_eq(&expected, &p.predict(&end_pred).unwrap());
 }
 
 #[test]
 fn test_2_deltas_unbalanced_uncertainty() {
     let start_pred = naive_ymd(2022, 10, 28).unwrap();
-    let p = Prediction {
-        start: start_pred,
-        initial_value: 500.0,
-        deltas: vec![
+    let p = Prediction::new(
+        String::new(),
+        start_pred,
+        Money::from(500),
+        vec![
             Box::new(
                 OneTimeDelta::try_new(
                     "test 1".into(),
-                    200.0,
+                    Money::from(200),
                     Some(Uncertainty::Unbalanced {
                         low: UncertaintyType::Dollars(15.0.try_into().unwrap()),
                         high: UncertaintyType::Percent(10.0.try_into().unwrap()),
@@ -164,7 +189,7 @@ fn test_2_deltas_unbalanced_uncertainty() {
             Box::new(
                 OneTimeDelta::try_new(
                     "test 2".into(),
-                    100.0,
+                    Money::from(100),
                     Some(Uncertainty::Unbalanced {
                         low: UncertaintyType::Percent(5.0.try_into().unwrap()),
                         high: UncertaintyType::Dollars(5.0.try_into().unwrap()),
@@ -174,40 +199,46 @@ fn test_2_deltas_unbalanced_uncertainty() {
                 .unwrap(),
             ),
         ],
-        ..Default::default()
-    };
+    );
 
     let end_pred = naive_ymd(2022, 11, 1).unwrap();
 
     let expected = BTreeMap::from([
         (
             naive_ymd(2022, 10, 28).unwrap(),
-            PredictionState::new(500.0, 500.0, 500.0, [].into()),
+            PredictionState::new(
+                Money::from(500),
+                Money::from(500),
+                Money::from(500),
+                [].into(),
+            ),
         ),
         (
             naive_ymd(2022, 10, 31).unwrap(),
             PredictionState::new(
-                800.0,
-                780.0,
-                825.0,
+                Money::from(800),
+                Money::from(780),
+                Money::from(825),
                 ["test 1".into(), "test 2".into()].into(),
             ),
         ),
     ]);
 
-    assert_btrees_eq(&expected, &p.predict(&end_pred));
+    assert_btrees_eq(&expected, &p.predict(&end_pred).unwrap());
 }
 
 #[test]
 fn test_prediction_all_delta_types() {
     let start_pred = naive_ymd(2022, 10, 28).unwrap();
-    let p = Prediction {
-        start: start_pred,
-        deltas: vec![
+    let p = Prediction::new(
+        String::new(),
+        start_pred,
+        Money::ZERO,
+        vec![
             Box::new(
                 OneTimeDelta::try_new(
                     "one time".into(),
-                    200.0,
+                    Money::from(200),
                     Some(Uncertainty::Unbalanced {
                         low: UncertaintyType::Dollars(15.0.try_into().unwrap()),
                         high: UncertaintyType::Percent(10.0.try_into().unwrap()),
@@ -219,7 +250,7 @@ fn test_prediction_all_delta_types() {
             Box::new(
                 YearlyDelta::try_new(
                     "yearly".into(),
-                    -155.0,
+                    Money::from(-155),
                     None,
                     naive_ymd(2022, 7, 1).unwrap(),
                     naive_ymd(2024, 12, 14).unwrap(),
@@ -230,7 +261,7 @@ fn test_prediction_all_delta_types() {
             Box::new(
                 MonthlyDelta::try_new(
                     "monthly".into(),
-                    1234.0,
+                    Money::from(1234),
                     Some(Uncertainty::Balanced(UncertaintyType::Dollars(
                         12.0.try_into().unwrap(),
                     ))),
@@ -244,7 +275,7 @@ fn test_prediction_all_delta_types() {
             Box::new(
                 WeeklyDelta::try_new(
                     "weekly".into(),
-                    -13.0,
+                    Money::from(-13),
                     None,
                     naive_ymd(2022, 11, 3).unwrap(),
                     naive_ymd(2022, 12, 12).unwrap(),
@@ -256,7 +287,7 @@ fn test_prediction_all_delta_types() {
             Box::new(
                 DailyDelta::try_new(
                     "daily".into(),
-                    1.0,
+                    Money::from(1),
                     None,
                     naive_ymd(2022, 11, 22).unwrap(),
                     naive_ymd(2022, 12, 7).unwrap(),
@@ -267,10 +298,10 @@ fn test_prediction_all_delta_types() {
             Box::new(
                 CustomDelta::try_new(
                     "custom".into(),
-                    -15.0,
+                    Money::from(-15),
                     Some(Uncertainty::Bounds {
-                        low: -20.0,
-                        high: -12.0,
+                        low: Money::from(-20),
+                        high: Money::from(-12),
                     }),
                     vec![
                         naive_ymd(2022, 11, 12).unwrap(),
@@ -280,71 +311,786 @@ fn test_prediction_all_delta_types() {
                 .unwrap(),
             ),
         ],
-        ..Default::default()
-    };
+    );
 
     let end_pred = naive_ymd(2023, 8, 1).unwrap();
     let expected = BTreeMap::from([
         (naive_ymd(2022, 10, 28).unwrap(), PredictionState::default()),
         (
             naive_ymd(2022, 10, 31).unwrap(),
-            PredictionState::new(200.0, 185.0, 220.0, ["one time".into()].into()),
+            PredictionState::new(
+                Money::from(200),
+                Money::from(185),
+                Money::from(220),
+                ["one time".into()].into(),
+            ),
         ),
         (
             naive_ymd(2022, 11, 3).unwrap(),
-            PredictionState::new(1434.0, 1407.0, 1466.0, ["monthly".into()].into()),
+            PredictionState::new(
+                Money::from(1434),
+                Money::from(1407),
+                Money::from(1466),
+                ["monthly".into()].into(),
+            ),
         ),
         (
             naive_ymd(2022, 11, 9).unwrap(),
-            PredictionState::new(1421.0, 1394.0, 1453.0, ["weekly".into()].into()),
+            PredictionState::new(
+                Money::from(1421),
+                Money::from(1394),
+                Money::from(1453),
+                ["weekly".into()].into(),
+            ),
         ),
         (
             naive_ymd(2022, 11, 12).unwrap(),
-            PredictionState::new(1406.0, 1374.0, 1441.0, ["custom".into()].into()),
+            PredictionState::new(
+                Money::from(1406),
+                Money::from(1374),
+                Money::from(1441),
+                ["custom".into()].into(),
+            ),
         ),
         (
             naive_ymd(2022, 11, 22).unwrap(),
-            PredictionState::new(1407.0, 1375.0, 1442.0, ["daily".into()].into()),
+            PredictionState::new(
+                Money::from(1407),
+                Money::from(1375),
+                Money::from(1442),
+                ["daily".into()].into(),
+            ),
         ),
         (
             naive_ymd(2022, 11, 23).unwrap(),
-            PredictionState::new(1394.0, 1362.0, 1429.0, ["weekly".into()].into()),
+            PredictionState::new(
+                Money::from(1394),
+                Money::from(1362),
+                Money::from(1429),
+                ["weekly".into()].into(),
+            ),
         ),
         (
             naive_ymd(2022, 11, 25).unwrap(),
-            PredictionState::new(1395.0, 1363.0, 1430.0, ["daily".into()].into()),
+            PredictionState::new(
+                Money::from(1395),
+                Money::from(1363),
+                Money::from(1430),
+                ["daily".into()].into(),
+            ),
         ),
         (
             naive_ymd(2022, 11, 28).unwrap(),
-            PredictionState::new(1396.0, 1364.0, 1431.0, ["daily".into()].into()),
+            PredictionState::new(
+                Money::from(1396),
+                Money::from(1364),
+                Money::from(1431),
+                ["daily".into()].into(),
+            ),
         ),
         (
             naive_ymd(2022, 12, 1).unwrap(),
             PredictionState::new(
-                1382.0,
-                1345.0,
-                1420.0,
+                Money::from(1382),
+                Money::from(1345),
+                Money::from(1420),
                 ["daily".into(), "custom".into()].into(),
             ),
         ),
         (
             naive_ymd(2022, 12, 4).unwrap(),
-            PredictionState::new(1383.0, 1346.0, 1421.0, ["daily".into()].into()),
+            PredictionState::new(
+                Money::from(1383),
+                Money::from(1346),
+                Money::from(1421),
+                ["daily".into()].into(),
+            ),
         ),
         (
             naive_ymd(2022, 12, 7).unwrap(),
             PredictionState::new(
-                1371.0,
-                1334.0,
-                1409.0,
+                Money::from(1371),
+                Money::from(1334),
+                Money::from(1409),
                 ["weekly".into(), "daily".into()].into(),
             ),
         ),
         (
             naive_ymd(2023, 7, 1).unwrap(),
-            PredictionState::new(1216.0, 1179.0, 1254.0, ["yearly".into()].into()),
+            PredictionState::new(
+                Money::from(1216),
+                Money::from(1179),
+                Money::from(1254),
+                ["yearly".into()].into(),
+            ),
         ),
     ]);
 
-    assert_btrees_eq(&expected, &p.predict(&end_pred));
+    assert_btrees_eq(&expected, &p.predict(&end_pred).unwrap());
+}
+
+fn bonus(value: i32) -> Box<dyn Delta> {
+    Box::new(
+        OneTimeDelta::try_new(
+            "bonus".into(),
+            Money::from(value),
+            None,
+            naive_ymd(2022, 12, 20).unwrap(),
+        )
+        .unwrap(),
+    )
+}
+
+#[test]
+fn test_delta_ids() {
+    let p = Prediction::new(
+        "test".into(),
+        naive_ymd(2022, 12, 1).unwrap(),
+        Money::ZERO,
+        vec![bonus(500), bonus(250), Box::<DailyDelta>::default()],
+    );
+    assert_eq!(
+        p.ids().iter().map(DeltaId::as_str).collect::<Vec<_>>(),
+        ["bonus", "bonus-2", "delta"]
+    );
+
+    // Deltas that share a name stay apart
+    let pred = p.predict(&naive_ymd(2022, 12, 31).unwrap()).unwrap();
+    assert_eq!(
+        pred[&naive_ymd(2022, 12, 20).unwrap()].impactful_deltas(),
+        &HashSet::from(["bonus".into(), "bonus-2".into()])
+    );
+
+    let mut p = p
+        .with_delta(DeltaId::try_new("gift").unwrap(), bonus(100))
+        .unwrap();
+    assert_eq!(p.delta("gift").unwrap().value(), Money::from(100));
+    assert!(p.delta("bonus-3").is_none());
+
+    let replaced = p.replace_delta("bonus-2", bonus(300)).unwrap();
+    assert_eq!(replaced.value(), Money::from(250));
+    assert_eq!(p.delta("bonus-2").unwrap().value(), Money::from(300));
+
+    let removed = p.remove_delta("bonus").unwrap();
+    assert_eq!(removed.value(), Money::from(500));
+    assert_eq!(p.deltas().len(), 3);
+    assert_eq!(p.ids()[0].as_str(), "bonus-2");
+
+    assert!(matches!(
+        p.remove_delta("bonus"),
+        Err(MoolahCoreError::UnknownDelta(id)) if id == "bonus"
+    ));
+    assert!(matches!(
+        p.with_delta(DeltaId::try_new("gift").unwrap(), bonus(1)),
+        Err(MoolahCoreError::DuplicateDeltaId(id)) if id == "gift"
+    ));
+    assert!(matches!(
+        DeltaId::try_new(" "),
+        Err(MoolahCoreError::EmptyDeltaId)
+    ));
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn test_serde_round_trip() {
+    let p = Prediction::new(
+        "test".into(),
+        naive_ymd(2022, 10, 28).unwrap(),
+        Money::from(500),
+        vec![
+            Box::new(
+                OneTimeDelta::try_new(
+                    "one time".into(),
+                    Money::from(200),
+                    None,
+                    naive_ymd(2022, 10, 31).unwrap(),
+                )
+                .unwrap(),
+            ),
+            Box::new(
+                MonthlyDelta::try_new(
+                    "monthly".into(),
+                    Money::from(-100),
+                    Some(Uncertainty::Balanced(UncertaintyType::Dollars(
+                        12.0.try_into().unwrap(),
+                    ))),
+                    naive_ymd(2022, 10, 28).unwrap(),
+                    naive_ymd(2023, 3, 31).unwrap(),
+                    3.try_into().unwrap(),
+                    0,
+                )
+                .unwrap(),
+            ),
+        ],
+    );
+
+    let serialized = serde_json::to_string(&p).unwrap();
+    let deserialized: Prediction = serde_json::from_str(&serialized).unwrap();
+
+    assert_eq!(deserialized.name(), p.name());
+    assert_eq!(deserialized.start(), p.start());
+    assert_eq!(deserialized.initial_value(), p.initial_value());
+    assert_eq!(deserialized.deltas().len(), 2);
+    assert_eq!(deserialized.ids(), p.ids());
+
+    let end_pred = naive_ymd(2023, 8, 1).unwrap();
+    assert_btrees_eq(
+        &p.predict(&end_pred).unwrap(),
+        &deserialized.predict(&end_pred).unwrap(),
+    );
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn test_serde_invalid_delta() {
+    let serialized = r#"{
+        "name": "test",
+        "start": "2022-10-28",
+        "initial_value": Money::from(500),
+        "deltas": [
+            {"kind": "yearly", "name": "insurance", "value": Money::from(-900), "start": "2023-01-01", "end": "2022-01-01"}
+        ]
+    }"#;
+
+    assert!(serde_json::from_str::<Prediction>(serialized).is_err());
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn test_serde_delta_ids() {
+    let delta = |id: &str| {
+        format!(
+            r#"{{{id}"kind": "one_time", "name": "bonus", "value": 100, "date": "2022-12-20"}}"#
+        )
+    };
+    let prediction = |deltas: [String; 3]| {
+        format!(
+            r#"{{"name": "test", "start": "2022-12-01", "initial_value": 0, "deltas": [{}]}}"#,
+            deltas.join(", ")
+        )
+    };
+
+    // Ids left out are filled in around those given
+    let p: Prediction = serde_json::from_str(&prediction([
+        delta(""),
+        delta(r#""id": "bonus", "#),
+        delta(r#""id": "gift", "#),
+    ]))
+    .unwrap();
+    assert_eq!(
+        p.ids().iter().map(DeltaId::as_str).collect::<Vec<_>>(),
+        ["bonus-2", "bonus", "gift"]
+    );
+    assert_eq!(
+        serde_json::to_value(&p).unwrap()["deltas"][0]["id"],
+        "bonus-2"
+    );
+
+    assert!(serde_json::from_str::<Prediction>(&prediction([
+        delta(r#""id": "gift", "#),
+        delta(""),
+        delta(r#""id": "gift", "#),
+    ]))
+    .is_err());
+}
+
+#[test]
+fn test_currency_conversion() {
+    let usd = Currency::try_new("USD").unwrap();
+    let eur = Currency::try_new("EUR").unwrap();
+    let mut rates = ExchangeRates::default();
+    rates.insert(
+        eur,
+        usd,
+        crate::currency::RateSchedule::try_dated(
+            [
+                (naive_ymd(2023, 1, 1).unwrap(), 1.1),
+                (naive_ymd(2023, 2, 1).unwrap(), 1.2),
+            ],
+            crate::currency::Interpolation::Previous,
+        )
+        .unwrap(),
+    );
+
+    let p = Prediction::new(
+        "test".into(),
+        naive_ymd(2023, 1, 1).unwrap(),
+        Money::from(1000),
+        vec![
+            Box::new(
+                MonthlyDelta::try_new(
+                    "salary".into(),
+                    Money::from(100),
+                    Some(Uncertainty::Balanced(UncertaintyType::Dollars(
+                        10.0.try_into().unwrap(),
+                    ))),
+                    naive_ymd(2023, 1, 1).unwrap(),
+                    naive_ymd(2023, 2, 28).unwrap(),
+                    15.try_into().unwrap(),
+                    0,
+                )
+                .unwrap()
+                .with_currency(eur),
+            ),
+            Box::new(
+                OneTimeDelta::try_new(
+                    "rent".into(),
+                    Money::from(-50),
+                    None,
+                    naive_ymd(2023, 1, 15).unwrap(),
+                )
+                .unwrap()
+                .with_currency(usd),
+            ),
+        ],
+    )
+    .with_currency(usd)
+    .with_exchange_rates(rates);
+
+    let expected = BTreeMap::from([
+        (
+            naive_ymd(2023, 1, 1).unwrap(),
+            PredictionState::new(
+                Money::from(1000),
+                Money::from(1000),
+                Money::from(1000),
+                [].into(),
+            ),
+        ),
+        (
+            naive_ymd(2023, 1, 15).unwrap(),
+            PredictionState::new(
+                Money::from(1060),
+                Money::from(1049),
+                Money::from(1071),
+                ["salary".into(), "rent".into()].into(),
+            ),
+        ),
+        (
+            naive_ymd(2023, 2, 15).unwrap(),
+            PredictionState::new(
+                Money::from(1180),
+                Money::from(1157),
+                Money::from(1203),
+                ["salary".into()].into(),
+            ),
+        ),
+    ]);
+
+    assert_btrees_eq(
+        &expected,
+        &p.predict(&naive_ymd(2023, 3, 1).unwrap()).unwrap(),
+    );
+}
+
+#[test]
+fn test_missing_exchange_rate() {
+    let usd = Currency::try_new("USD").unwrap();
+    let gbp = Currency::try_new("GBP").unwrap();
+    let delta = OneTimeDelta::try_new(
+        "gift".into(),
+        Money::from(20),
+        None,
+        naive_ymd(2023, 1, 15).unwrap(),
+    )
+    .unwrap()
+    .with_currency(gbp);
+
+    let p = Prediction::new(
+        "test".into(),
+        naive_ymd(2023, 1, 1).unwrap(),
+        Money::from(0),
+        vec![Box::new(delta)],
+    )
+    .with_currency(usd);
+    assert!(matches!(
+        p.predict(&naive_ymd(2023, 2, 1).unwrap()),
+        Err(MoolahCoreError::MissingExchangeRate { from, to, .. }) if (from == gbp) & (to == usd)
+    ));
+
+    // Deltas after the end of the prediction are never converted
+    assert!(p.predict(&naive_ymd(2023, 1, 14).unwrap()).is_ok());
+}
+
+#[test]
+fn test_no_reporting_currency() {
+    let delta = OneTimeDelta::try_new(
+        "gift".into(),
+        Money::from(20),
+        None,
+        naive_ymd(2023, 1, 15).unwrap(),
+    )
+    .unwrap()
+    .with_currency(Currency::try_new("GBP").unwrap());
+
+    let p = Prediction::new(
+        "test".into(),
+        naive_ymd(2023, 1, 1).unwrap(),
+        Money::from(0),
+        vec![Box::new(delta)],
+    );
+    assert!(matches!(
+        p.predict(&naive_ymd(2023, 2, 1).unwrap()),
+        Err(MoolahCoreError::NoReportingCurrency { delta, .. }) if delta == "gift"
+    ));
+}
+
+#[test]
+fn test_compound_interest() {
+    let p = Prediction::new(
+        "savings".into(),
+        naive_ymd(2023, 1, 1).unwrap(),
+        Money::from(1200),
+        vec![
+            Box::new(
+                InterestDelta::try_new(
+                    "interest".into(),
+                    12.0,
+                    Some(RateUncertainty::Bounds {
+                        low: 0.0,
+                        high: 24.0,
+                    }),
+                    Compounding::Monthly,
+                    naive_ymd(2023, 1, 1).unwrap(),
+                    naive_ymd(2023, 12, 31).unwrap(),
+                )
+                .unwrap(),
+            ),
+            Box::new(
+                OneTimeDelta::try_new(
+                    "deposit".into(),
+                    Money::from(1000),
+                    None,
+                    naive_ymd(2023, 2, 1).unwrap(),
+                )
+                .unwrap(),
+            ),
+        ],
+    );
+
+    let expected = BTreeMap::from([
+        (
+            naive_ymd(2023, 1, 1).unwrap(),
+            PredictionState::new(
+                Money::from(1200),
+                Money::from(1200),
+                Money::from(1200),
+                [].into(),
+            ),
+        ),
+        // Interest is earned on the balance before the deposit lands
+        (
+            naive_ymd(2023, 2, 1).unwrap(),
+            PredictionState::new(
+                Money::from(2212),
+                Money::from(2200),
+                Money::from(2224),
+                ["interest".into(), "deposit".into()].into(),
+            ),
+        ),
+        (
+            naive_ymd(2023, 3, 1).unwrap(),
+            PredictionState::new(
+                Money::from_cents(223412),
+                Money::from(2200),
+                Money::from_cents(226848),
+                ["interest".into()].into(),
+            ),
+        ),
+        (
+            naive_ymd(2023, 4, 1).unwrap(),
+            PredictionState::new(
+                Money::from_cents(225646),
+                Money::from(2200),
+                Money::from_cents(231385),
+                ["interest".into()].into(),
+            ),
+        ),
+    ]);
+
+    assert_btrees_eq(
+        &expected,
+        &p.predict(&naive_ymd(2023, 4, 15).unwrap()).unwrap(),
+    );
+}
+
+#[test]
+fn test_loan_payments() {
+    let loan = LoanDelta::try_new(
+        "car loan".into(),
+        Money::from(1200),
+        0.0,
+        12,
+        naive_ymd(2023, 1, 1).unwrap(),
+        15.try_into().unwrap(),
+        vec![ExtraPayment::OneTime {
+            amount: Money::from(500),
+            date: naive_ymd(2023, 2, 1).unwrap(),
+        }],
+    )
+    .unwrap();
+    let p = Prediction::new(
+        "test".into(),
+        naive_ymd(2023, 1, 1).unwrap(),
+        Money::from(2000),
+        vec![Box::new(loan)],
+    );
+
+    let values: Vec<(NaiveDate, Money)> = p
+        .predict(&naive_ymd(2023, 12, 31).unwrap())
+        .unwrap()
+        .into_iter()
+        .map(|(date, state)| (date, state.value()))
+        .collect();
+    assert_eq!(
+        values,
+        [
+            (naive_ymd(2023, 1, 1).unwrap(), Money::from(2000)),
+            (naive_ymd(2023, 1, 15).unwrap(), Money::from(1900)),
+            (naive_ymd(2023, 2, 15).unwrap(), Money::from(1300)),
+            (naive_ymd(2023, 3, 15).unwrap(), Money::from(1200)),
+            (naive_ymd(2023, 4, 15).unwrap(), Money::from(1100)),
+            (naive_ymd(2023, 5, 15).unwrap(), Money::from(1000)),
+            (naive_ymd(2023, 6, 15).unwrap(), Money::from(900)),
+            (naive_ymd(2023, 7, 15).unwrap(), Money::from(800)),
+        ]
+    );
+}
+
+#[test]
+fn test_escalated_rent() {
+    let rent = MonthlyDelta::try_new(
+        "rent".into(),
+        Money::from(-1000),
+        Some(Uncertainty::Balanced(UncertaintyType::Percent(
+            10.0.try_into().unwrap(),
+        ))),
+        naive_ymd(2023, 1, 1).unwrap(),
+        naive_ymd(2024, 12, 31).unwrap(),
+        1.try_into().unwrap(),
+        0,
+    )
+    .unwrap()
+    .with_escalation(Escalation::Annual(3.0))
+    .unwrap();
+    let p = Prediction::new(
+        "test".into(),
+        naive_ymd(2023, 1, 1).unwrap(),
+        Money::ZERO,
+        vec![Box::new(rent)],
+    );
+
+    let end = naive_ymd(2024, 1, 1).unwrap();
+    let state = &p.predict(&end).unwrap()[&end];
+    assert_eq!(state.value(), Money::from(-13030));
+    assert_eq!(state.min_uncertainty_val(), Money::from(-14333));
+    assert_eq!(state.max_uncertainty_val(), Money::from(-11727));
+}
+
+#[test]
+fn test_per_date_amounts() {
+    let plan = CustomDelta::try_new("payment plan".into(), Money::ZERO, None, vec![])
+        .unwrap()
+        .with_amounts([
+            (naive_ymd(2023, 1, 10).unwrap(), Money::from(-300)),
+            (naive_ymd(2023, 2, 10).unwrap(), Money::from(-200)),
+            (naive_ymd(2023, 3, 10).unwrap(), Money::from(-100)),
+        ])
+        .unwrap();
+    let p = Prediction::new(
+        "test".into(),
+        naive_ymd(2023, 1, 1).unwrap(),
+        Money::from(1000),
+        vec![Box::new(plan)],
+    );
+
+    let values: Vec<(NaiveDate, Money)> = p
+        .predict(&naive_ymd(2023, 12, 31).unwrap())
+        .unwrap()
+        .into_iter()
+        .map(|(date, state)| (date, state.value()))
+        .collect();
+    assert_eq!(
+        values,
+        [
+            (naive_ymd(2023, 1, 1).unwrap(), Money::from(1000)),
+            (naive_ymd(2023, 1, 10).unwrap(), Money::from(700)),
+            (naive_ymd(2023, 2, 10).unwrap(), Money::from(500)),
+            (naive_ymd(2023, 3, 10).unwrap(), Money::from(400)),
+        ]
+    );
+}
+
+#[test]
+fn test_open_ended_deltas() {
+    let salary = MonthlyDelta::try_new(
+        "salary".into(),
+        Money::from(3000),
+        None,
+        naive_ymd(2023, 1, 1).unwrap(),
+        None,
+        15.try_into().unwrap(),
+        0,
+    )
+    .unwrap();
+    let groceries = WeeklyDelta::try_new(
+        "groceries".into(),
+        Money::from(-100),
+        None,
+        naive_ymd(2023, 1, 1).unwrap(),
+        None,
+        Some(Weekday::Sat),
+        0,
+    )
+    .unwrap();
+    let p = Prediction::new(
+        "test".into(),
+        naive_ymd(2023, 1, 1).unwrap(),
+        Money::ZERO,
+        vec![Box::new(salary), Box::new(groceries)],
+    );
+
+    let end = naive_ymd(2023, 3, 31).unwrap();
+    let timeline = p.predict(&end).unwrap();
+    let (last, state) = timeline.iter().next_back().unwrap();
+    assert_eq!(*last, naive_ymd(2023, 3, 25).unwrap());
+    // 3 paychecks and 12 Saturdays
+    assert_eq!(state.value(), Money::from(9000 - 1200));
+}
+
+fn groceries_with_checkpoints(checkpoints: Vec<Checkpoint>) -> Prediction {
+    let groceries = WeeklyDelta::try_new(
+        "groceries".into(),
+        Money::from(-100),
+        Some(Uncertainty::Balanced(UncertaintyType::Dollars(
+            Money::from(10).try_into().unwrap(),
+        ))),
+        naive_ymd(2023, 1, 1).unwrap(),
+        None,
+        Some(Weekday::Sat),
+        0,
+    )
+    .unwrap();
+    Prediction::new(
+        "test".into(),
+        naive_ymd(2023, 1, 1).unwrap(),
+        Money::from(1000),
+        vec![Box::new(groceries)],
+    )
+    .with_checkpoints(checkpoints)
+}
+
+#[test]
+fn test_checkpoint_resets_timeline() {
+    let checkpoint = naive_ymd(2023, 1, 18).unwrap();
+    let p = groceries_with_checkpoints(vec![Checkpoint::new(checkpoint, Money::from(850))]);
+
+    let timeline = p.predict(&naive_ymd(2023, 1, 31).unwrap()).unwrap();
+    // Two Saturdays had landed, leaving 800 (780 to 820) where 850 was observed
+    let state = &timeline[&checkpoint];
+    assert_eq!(state.value(), Money::from(850));
+    assert_eq!(state.min_uncertainty_val(), Money::from(850));
+    assert_eq!(state.max_uncertainty_val(), Money::from(850));
+    assert_eq!(state.variance(), Some(Money::from(50)));
+    assert!(state.impactful_deltas().is_empty());
+
+    let state = &timeline[&naive_ymd(2023, 1, 28).unwrap()];
+    assert_eq!(state.value(), Money::from(650));
+    assert_eq!(state.min_uncertainty_val(), Money::from(630));
+    assert_eq!(state.max_uncertainty_val(), Money::from(670));
+    assert_eq!(state.variance(), None);
+}
+
+#[test]
+fn test_checkpoint_narrows_timeline() {
+    let checkpoint = naive_ymd(2023, 1, 21).unwrap();
+    let p = groceries_with_checkpoints(vec![Checkpoint::new(checkpoint, Money::from(800))
+        .with_weight(0.5)
+        .unwrap()]);
+
+    // The checkpoint lands after the groceries on the same Saturday, at 700 (670 to 730)
+    let state = &p.predict(&checkpoint).unwrap()[&checkpoint];
+    assert_eq!(state.value(), Money::from(750));
+    assert_eq!(state.min_uncertainty_val(), Money::from(735));
+    assert_eq!(state.max_uncertainty_val(), Money::from(765));
+    assert_eq!(state.variance(), Some(Money::from(100)));
+}
+
+#[test]
+fn test_checkpoints_outside_window() {
+    let p = groceries_with_checkpoints(vec![
+        Checkpoint::new(naive_ymd(2022, 12, 1).unwrap(), Money::ZERO),
+        Checkpoint::new(naive_ymd(2023, 2, 1).unwrap(), Money::ZERO),
+    ]);
+    let end = naive_ymd(2023, 1, 31).unwrap();
+    let timeline = p.predict(&end).unwrap();
+
+    assert!(timeline.values().all(|state| state.variance().is_none()));
+    assert_eq!(
+        timeline.values().next_back().unwrap().value(),
+        Money::from(600)
+    );
+}
+
+#[test]
+fn test_invalid_checkpoint_weight() {
+    let checkpoint = Checkpoint::new(naive_ymd(2023, 1, 1).unwrap(), Money::ZERO);
+    for weight in [0.0, -0.5, 1.5, f64::NAN] {
+        assert!(matches!(
+            checkpoint.clone().with_weight(weight),
+            Err(MoolahCoreError::InvalidCheckpointWeight(_))
+        ));
+    }
+    assert!(checkpoint.with_weight(1.0).is_ok());
+}
+
+#[test]
+fn test_editing() {
+    let mut p = Prediction::new(
+        "test".into(),
+        naive_ymd(2022, 12, 1).unwrap(),
+        Money::ZERO,
+        vec![],
+    );
+    assert_eq!(p.add_delta(bonus(500)).as_str(), "bonus");
+    assert_eq!(p.add_delta(bonus(250)).as_str(), "bonus-2");
+    assert!(matches!(
+        p.add_delta_with_id(DeltaId::try_new("bonus").unwrap(), bonus(1)),
+        Err(MoolahCoreError::DuplicateDeltaId(id)) if id == "bonus"
+    ));
+
+    match p.delta_mut("bonus-2") {
+        Some(DeltaMut::OneTime(d)) => {
+            d.set_date(naive_ymd(2023, 1, 20).unwrap());
+            d.set_value(Money::from(300)).unwrap();
+        }
+        _ => panic!("expected a one-time delta"),
+    }
+    assert!(p.delta_mut("bonus-3").is_none());
+
+    p.set_initial_value(Money::from(100));
+    p.set_start(naive_ymd(2022, 12, 10).unwrap());
+    let pred = p.predict(&naive_ymd(2023, 1, 31).unwrap()).unwrap();
+    assert_eq!(
+        pred[&naive_ymd(2022, 12, 20).unwrap()].value(),
+        Money::from(600)
+    );
+    assert_eq!(
+        pred[&naive_ymd(2023, 1, 20).unwrap()].value(),
+        Money::from(900)
+    );
+}
+
+#[test]
+fn test_overflow() {
+    let p = Prediction::new(
+        "test".into(),
+        naive_ymd(2022, 12, 1).unwrap(),
+        Money::from_cents(i64::MAX),
+        vec![bonus(1)],
+    );
+    assert!(matches!(
+        p.predict(&naive_ymd(2022, 12, 31).unwrap()),
+        Err(MoolahCoreError::MoneyOverflow)
+    ));
 }
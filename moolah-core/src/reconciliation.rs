@@ -0,0 +1,240 @@
+#[cfg(test)]
+mod tests;
+
+use crate::{
+    delta::Occurrence,
+    errors::MoolahCoreError,
+    money::Money,
+    prediction::Prediction,
+    transaction::{Ledger, Transaction},
+};
+use chrono::{Days, NaiveDate};
+
+/// How far an actual transaction may stray from the occurrence it fulfills
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Tolerance {
+    days: u32,
+    amount: Money,
+}
+
+impl Tolerance {
+    /// A transaction matches an occurrence up to `days` before or after it, and is over budget
+    /// once it falls more than `amount` below the occurrence's uncertainty band
+    pub fn try_new(days: u32, amount: Money) -> Result<Self, MoolahCoreError> {
+        if amount.is_negative() {
+            return Err(MoolahCoreError::UnexpectedNegativeMoney(amount));
+        }
+        Ok(Tolerance { days, amount })
+    }
+
+    pub fn days(&self) -> u32 {
+        self.days
+    }
+
+    pub fn amount(&self) -> Money {
+        self.amount
+    }
+}
+
+/// A delta occurrence the prediction expects, in the prediction's currency
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Planned<'a> {
+    /// The id of the delta
+    pub id: &'a str,
+    /// The delta's name, which transactions are matched against
+    pub delta: &'a str,
+    pub occurrence: Occurrence,
+}
+
+/// An actual transaction matched to the occurrence it fulfills
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Matched<'a> {
+    planned: Planned<'a>,
+    transaction: &'a Transaction,
+    over_budget: Money,
+}
+
+impl<'a> Matched<'a> {
+    pub fn planned(&self) -> &Planned<'a> {
+        &self.planned
+    }
+
+    pub fn transaction(&self) -> &'a Transaction {
+        self.transaction
+    }
+
+    /// Days from the planned date to the transaction, negative when it came early
+    pub fn days_late(&self) -> i64 {
+        (*self.transaction.date() - self.planned.occurrence.date).num_days()
+    }
+
+    /// How far the transaction fell below the occurrence's band and its tolerance, i.e. cost more
+    /// or paid less than planned. Zero when it is within budget.
+    pub fn over_budget(&self) -> Money {
+        self.over_budget
+    }
+}
+
+/// A prediction compared with what actually happened
+#[derive(Debug, Clone, PartialEq)]
+pub struct Reconciliation<'a> {
+    matched: Vec<Matched<'a>>,
+    missed: Vec<Planned<'a>>,
+    unplanned: Vec<&'a Transaction>,
+}
+
+impl<'a> Reconciliation<'a> {
+    /// Every occurrence with the transaction that fulfilled it, in date order
+    pub fn matched(&self) -> &[Matched<'a>] {
+        &self.matched
+    }
+
+    /// Occurrences without any transaction
+    pub fn missed(&self) -> &[Planned<'a>] {
+        &self.missed
+    }
+
+    /// Transactions that did not fulfill any occurrence
+    pub fn unplanned(&self) -> &[&'a Transaction] {
+        &self.unplanned
+    }
+
+    /// Matched transactions after their planned date
+    pub fn late(&self) -> impl Iterator<Item = &Matched<'a>> {
+        self.matched
+            .iter()
+            .filter(|matched| matched.days_late() > 0)
+    }
+
+    /// Matched transactions beyond their occurrence's band and the amount tolerance
+    pub fn over_budget(&self) -> impl Iterator<Item = &Matched<'a>> {
+        self.matched
+            .iter()
+            .filter(|matched| matched.over_budget() > Money::ZERO)
+    }
+
+    /// Whether everything happened as planned, on time and within budget
+    pub fn is_on_plan(&self) -> bool {
+        self.missed.is_empty()
+            & self.unplanned.is_empty()
+            & self.late().next().is_none()
+            & self.over_budget().next().is_none()
+    }
+}
+
+/// Whether `transaction` is for `delta`: its category is the delta's name, or its description
+/// contains the delta's name as whole words, ignoring case and punctuation. A name without any
+/// words is never matched.
+fn is_for(transaction: &Transaction, delta: &str) -> bool {
+    let delta = words(delta);
+    if delta.is_empty() {
+        return false;
+    }
+    match transaction.category() {
+        Some(category) => words(category) == delta,
+        None => words(transaction.description())
+            .windows(delta.len())
+            .any(|window| window == delta),
+    }
+}
+
+/// The lowercase words in `text`
+fn words(text: &str) -> Vec<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(str::to_lowercase)
+        .collect()
+}
+
+impl Prediction {
+    /// Match the transactions in `ledger` from the prediction's start to `end` against the delta
+    /// occurrences they fulfill. Each occurrence takes the closest transaction for its delta
+    /// within `tolerance.days()` of it, preferring the closest amount on ties, so transactions up
+    /// to that many days outside the period still count. Only those within the period are
+    /// reported as unplanned. Balance-dependent deltas such as interest have no planned amount, so
+    /// they are left out.
+    pub fn reconcile<'a>(
+        &'a self,
+        ledger: &'a Ledger,
+        end: &NaiveDate,
+        tolerance: &Tolerance,
+    ) -> Result<Reconciliation<'a>, MoolahCoreError> {
+        let mut planned = vec![];
+        for (id, delta) in self.identified_deltas() {
+            if delta.is_balance_dependent() {
+                continue;
+            }
+            for occurrence in delta.occurrences(self.start(), end) {
+                let rate = self.exchange_rate(delta, &occurrence.date)?;
+                planned.push(Planned {
+                    id: id.as_str(),
+                    delta: delta.name(),
+                    occurrence: Occurrence {
+                        date: occurrence.date,
+                        value: occurrence.value.scale(rate),
+                        min: occurrence.min.scale(rate),
+                        max: occurrence.max.scale(rate),
+                    },
+                });
+            }
+        }
+        planned.sort_by_key(|planned| planned.occurrence.date);
+
+        let days = Days::new(tolerance.days.into());
+        let transactions = ledger.between(
+            &self
+                .start()
+                .checked_sub_days(days)
+                .unwrap_or(NaiveDate::MIN),
+            &end.checked_add_days(days).unwrap_or(NaiveDate::MAX),
+        );
+        let mut used = vec![false; transactions.len()];
+        let mut matched = vec![];
+        let mut missed = vec![];
+
+        for planned in planned {
+            let occurrence = &planned.occurrence;
+            let closest = transactions
+                .iter()
+                .enumerate()
+                .filter(|(i, transaction)| {
+                    !used[*i]
+                        & is_for(transaction, planned.delta)
+                        & ((*transaction.date() - occurrence.date).num_days().abs()
+                            <= i64::from(tolerance.days))
+                })
+                .min_by_key(|(_, transaction)| {
+                    (
+                        (*transaction.date() - occurrence.date).num_days().abs(),
+                        (transaction.amount() - occurrence.value).abs(),
+                    )
+                });
+
+            match closest {
+                Some((i, transaction)) => {
+                    used[i] = true;
+                    let low = occurrence.min.min(occurrence.max) - tolerance.amount;
+                    matched.push(Matched {
+                        planned,
+                        transaction,
+                        over_budget: (low - transaction.amount()).max(Money::ZERO),
+                    });
+                }
+                None => missed.push(planned),
+            }
+        }
+
+        Ok(Reconciliation {
+            matched,
+            missed,
+            unplanned: transactions
+                .iter()
+                .zip(used)
+                .filter(|(transaction, used)| {
+                    !used & (self.start()..=end).contains(&transaction.date())
+                })
+                .map(|(transaction, _)| transaction)
+                .collect(),
+        })
+    }
+}
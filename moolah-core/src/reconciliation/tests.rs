@@ -0,0 +1,246 @@
+use super::*;
+use crate::date_helpers::ymd;
+use crate::delta::{
+    Delta, InterestDelta, MonthlyDelta, OneTimeDelta, Uncertainty, UncertaintyType,
+};
+
+fn prediction() -> Prediction {
+    let rent = MonthlyDelta::try_new(
+        "rent".into(),
+        Money::from(-1000),
+        None,
+        ymd(2023, 1, 1),
+        None,
+        1.try_into().unwrap(),
+        0,
+    )
+    .unwrap();
+    let groceries = MonthlyDelta::try_new(
+        "groceries".into(),
+        Money::from(-400),
+        Some(Uncertainty::Balanced(UncertaintyType::Percent(
+            10.0.try_into().unwrap(),
+        ))),
+        ymd(2023, 1, 1),
+        None,
+        15.try_into().unwrap(),
+        0,
+    )
+    .unwrap();
+    let deltas: Vec<Box<dyn Delta>> = vec![Box::new(rent), Box::new(groceries)];
+    Prediction::new("test".into(), ymd(2023, 1, 1), Money::ZERO, deltas)
+}
+
+fn transaction(date: NaiveDate, description: &str, amount: i32) -> Transaction {
+    Transaction::new(date, description.into(), Money::from(amount))
+}
+
+#[test]
+fn test_on_plan() {
+    let p = prediction();
+    let ledger = Ledger::new(vec![
+        transaction(ymd(2023, 1, 1), "RENT PAYMENT", -1000),
+        transaction(ymd(2023, 1, 15), "Groceries", -420),
+        transaction(ymd(2023, 2, 1), "Rent payment", -1000),
+        transaction(ymd(2023, 2, 15), "groceries", -380),
+    ]);
+
+    let reconciliation = p
+        .reconcile(&ledger, &ymd(2023, 2, 28), &Tolerance::default())
+        .unwrap();
+    assert_eq!(reconciliation.matched().len(), 4);
+    assert!(reconciliation.is_on_plan());
+    assert_eq!(
+        reconciliation
+            .matched()
+            .iter()
+            .map(|matched| matched.planned().delta)
+            .collect::<Vec<_>>(),
+        ["rent", "groceries", "rent", "groceries"]
+    );
+}
+
+#[test]
+fn test_report() {
+    let p = prediction();
+    let ledger = Ledger::new(vec![
+        transaction(ymd(2023, 1, 3), "rent", -1000),
+        transaction(ymd(2023, 1, 14), "groceries", -500),
+        transaction(ymd(2023, 1, 20), "concert tickets", -150),
+        transaction(ymd(2023, 2, 20), "groceries", -400),
+    ]);
+
+    let tolerance = Tolerance::try_new(3, Money::from(25)).unwrap();
+    let reconciliation = p.reconcile(&ledger, &ymd(2023, 2, 28), &tolerance).unwrap();
+
+    let late: Vec<_> = reconciliation.late().collect();
+    assert_eq!(late.len(), 1);
+    assert_eq!(late[0].planned().delta, "rent");
+    assert_eq!(late[0].planned().id, "rent");
+    assert_eq!(late[0].days_late(), 2);
+
+    // Groceries were planned at -400 ± 10%, so -500 is 35 beyond the band and its tolerance
+    let over_budget: Vec<_> = reconciliation.over_budget().collect();
+    assert_eq!(over_budget.len(), 1);
+    assert_eq!(over_budget[0].days_late(), -1);
+    assert_eq!(over_budget[0].over_budget(), Money::from(35));
+
+    // February's groceries came too late to count, and February's rent never came
+    let missed: Vec<_> = reconciliation
+        .missed()
+        .iter()
+        .map(|planned| (planned.delta, planned.occurrence.date))
+        .collect();
+    assert_eq!(
+        missed,
+        [("rent", ymd(2023, 2, 1)), ("groceries", ymd(2023, 2, 15))]
+    );
+    let unplanned: Vec<_> = reconciliation
+        .unplanned()
+        .iter()
+        .map(|transaction| transaction.description())
+        .collect();
+    assert_eq!(unplanned, ["concert tickets", "groceries"]);
+    assert!(!reconciliation.is_on_plan());
+}
+
+#[test]
+fn test_tolerance_reaches_outside_period() {
+    let p = prediction();
+    let ledger = Ledger::new(vec![
+        transaction(ymd(2022, 12, 30), "rent", -1000),
+        transaction(ymd(2022, 12, 31), "coffee", -5),
+        transaction(ymd(2023, 1, 15), "groceries", -400),
+        transaction(ymd(2023, 2, 2), "rent", -1000),
+        transaction(ymd(2023, 2, 3), "coffee", -5),
+    ]);
+
+    let tolerance = Tolerance::try_new(3, Money::ZERO).unwrap();
+    let reconciliation = p.reconcile(&ledger, &ymd(2023, 1, 31), &tolerance).unwrap();
+    let matched: Vec<_> = reconciliation
+        .matched()
+        .iter()
+        .map(|matched| (matched.planned().delta, matched.days_late()))
+        .collect();
+    assert_eq!(matched, [("rent", -2), ("groceries", 0)]);
+    assert!(reconciliation.missed().is_empty());
+    assert!(reconciliation.unplanned().is_empty());
+}
+
+#[test]
+fn test_category_overrides_description() {
+    let p = prediction();
+    let ledger = Ledger::new(vec![
+        transaction(ymd(2023, 1, 1), "Landlord LLC", -1000).with_category("Rent".into()),
+        transaction(ymd(2023, 1, 15), "groceries", -400).with_category("household".into()),
+    ]);
+
+    let reconciliation = p
+        .reconcile(&ledger, &ymd(2023, 1, 31), &Tolerance::default())
+        .unwrap();
+    assert_eq!(reconciliation.matched().len(), 1);
+    assert_eq!(reconciliation.matched()[0].planned().delta, "rent");
+    assert_eq!(reconciliation.missed()[0].delta, "groceries");
+    assert_eq!(reconciliation.unplanned().len(), 1);
+}
+
+#[test]
+fn test_name_matches_whole_words() {
+    let p = prediction();
+    let ledger = Ledger::new(vec![
+        transaction(ymd(2023, 1, 1), "Parent-teacher fund", -1000),
+        transaction(ymd(2023, 1, 2), "Rent, January", -1000),
+        transaction(ymd(2023, 1, 15), "GROCERIES/household", -400),
+    ]);
+
+    let tolerance = Tolerance::try_new(3, Money::ZERO).unwrap();
+    let reconciliation = p.reconcile(&ledger, &ymd(2023, 1, 31), &tolerance).unwrap();
+    let matched: Vec<_> = reconciliation
+        .matched()
+        .iter()
+        .map(|matched| matched.transaction().description())
+        .collect();
+    assert_eq!(matched, ["Rent, January", "GROCERIES/household"]);
+    assert_eq!(
+        reconciliation.unplanned()[0].description(),
+        "Parent-teacher fund"
+    );
+}
+
+#[test]
+fn test_unnamed_delta_matches_nothing() {
+    let unnamed =
+        OneTimeDelta::try_new("".into(), Money::from(-20), None, ymd(2023, 1, 10)).unwrap();
+    let p = Prediction::new(
+        "test".into(),
+        ymd(2023, 1, 1),
+        Money::ZERO,
+        vec![Box::new(unnamed)],
+    );
+    let ledger = Ledger::new(vec![
+        transaction(ymd(2023, 1, 10), "Coffee", -20),
+        transaction(ymd(2023, 1, 10), "Lunch", -20).with_category("".into()),
+    ]);
+
+    let reconciliation = p
+        .reconcile(&ledger, &ymd(2023, 1, 31), &Tolerance::default())
+        .unwrap();
+    assert!(reconciliation.matched().is_empty());
+    assert_eq!(reconciliation.missed().len(), 1);
+    assert_eq!(reconciliation.unplanned().len(), 2);
+}
+
+#[test]
+fn test_closest_transaction_wins() {
+    let bonus =
+        OneTimeDelta::try_new("bonus".into(), Money::from(500), None, ymd(2023, 1, 10)).unwrap();
+    let p = Prediction::new(
+        "test".into(),
+        ymd(2023, 1, 1),
+        Money::ZERO,
+        vec![Box::new(bonus)],
+    );
+    let ledger = Ledger::new(vec![
+        transaction(ymd(2023, 1, 8), "bonus", 500),
+        transaction(ymd(2023, 1, 11), "bonus", 100),
+        transaction(ymd(2023, 1, 11), "bonus", 450),
+    ]);
+
+    let tolerance = Tolerance::try_new(5, Money::ZERO).unwrap();
+    let reconciliation = p.reconcile(&ledger, &ymd(2023, 1, 31), &tolerance).unwrap();
+    assert_eq!(
+        reconciliation.matched()[0].transaction().amount(),
+        Money::from(450)
+    );
+    assert_eq!(reconciliation.unplanned().len(), 2);
+}
+
+#[test]
+fn test_balance_dependent_deltas_left_out() {
+    let interest = InterestDelta::try_new(
+        "interest".into(),
+        5.0,
+        None,
+        Default::default(),
+        ymd(2023, 1, 1),
+        ymd(2023, 12, 31),
+    )
+    .unwrap();
+    let p = Prediction::new(
+        "test".into(),
+        ymd(2023, 1, 1),
+        Money::from(1000),
+        vec![Box::new(interest)],
+    );
+
+    let ledger = Ledger::default();
+    let reconciliation = p
+        .reconcile(&ledger, &ymd(2023, 12, 31), &Tolerance::default())
+        .unwrap();
+    assert!(reconciliation.is_on_plan());
+}
+
+#[test]
+fn test_negative_tolerance() {
+    assert!(Tolerance::try_new(1, Money::from(-1)).is_err());
+}
@@ -0,0 +1,429 @@
+//! What-if variants of a prediction, run side by side
+
+#[cfg(test)]
+mod tests;
+
+#[cfg(feature = "serde")]
+use crate::delta::TaggedDeltaRef;
+use crate::{
+    currency::Currency,
+    delta::{Delta, DeltaId, Uncertainty},
+    errors::MoolahCoreError,
+    money::Money,
+    prediction::{Prediction, PredictionState},
+};
+use chrono::{Days, NaiveDate};
+use rand::RngCore;
+use std::collections::{BTreeMap, BTreeSet};
+
+enum Change {
+    Add(DeltaId, Box<dyn Delta>),
+    Remove(String),
+    Override(String, Box<dyn Delta>),
+    Shift(String, i64),
+    Scale(String, f64),
+}
+
+/// Changes to a base prediction's deltas, applied in the order they were given. Deltas are picked
+/// out by their id in the base, or the id they were added under.
+pub struct Variant {
+    name: String,
+    changes: Vec<Change>,
+}
+
+impl Variant {
+    pub fn new(name: String) -> Self {
+        Variant {
+            name,
+            changes: vec![],
+        }
+    }
+
+    pub fn with_added(mut self, id: DeltaId, delta: Box<dyn Delta>) -> Self {
+        self.changes.push(Change::Add(id, delta));
+        self
+    }
+
+    pub fn with_removed(mut self, id: String) -> Self {
+        self.changes.push(Change::Remove(id));
+        self
+    }
+
+    /// Put `delta` in place of the delta with `id`
+    pub fn with_override(mut self, id: String, delta: Box<dyn Delta>) -> Self {
+        self.changes.push(Change::Override(id, delta));
+        self
+    }
+
+    /// Move every date of the delta with `id` `days` later, or earlier when negative
+    pub fn with_shift(mut self, id: String, days: i64) -> Self {
+        self.changes.push(Change::Shift(id, days));
+        self
+    }
+
+    /// Multiply every amount of the delta with `id` by a non-negative `factor`
+    pub fn with_scale(mut self, id: String, factor: f64) -> Result<Self, MoolahCoreError> {
+        if !factor.is_finite() | (factor < 0.0) {
+            return Err(MoolahCoreError::InvalidScaleFactor(factor));
+        }
+        self.changes.push(Change::Scale(id, factor));
+        Ok(self)
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The base deltas with the changes applied, each alongside its id
+    fn deltas<'a>(
+        &'a self,
+        base: &'a Prediction,
+    ) -> Result<Vec<(&'a str, Adjusted<'a>)>, MoolahCoreError> {
+        let mut deltas: Vec<_> = base
+            .identified_deltas()
+            .map(|(id, delta)| (id.as_str(), Adjusted::new(delta)))
+            .collect();
+        let position = |deltas: &[(&str, Adjusted)], id: &str| {
+            deltas
+                .iter()
+                .position(|(other, _)| *other == id)
+                .ok_or_else(|| MoolahCoreError::UnknownDelta(id.into()))
+        };
+
+        for change in &self.changes {
+            match change {
+                Change::Add(id, delta) => {
+                    if position(&deltas, id.as_str()).is_ok() {
+                        return Err(MoolahCoreError::DuplicateDeltaId(id.to_string()));
+                    }
+                    deltas.push((id.as_str(), Adjusted::new(&**delta)));
+                }
+                Change::Remove(id) => {
+                    deltas.remove(position(&deltas, id)?);
+                }
+                Change::Override(id, replacement) => {
+                    let index = position(&deltas, id)?;
+                    deltas[index].1 = Adjusted::new(&**replacement);
+                }
+                Change::Shift(id, days) => {
+                    let index = position(&deltas, id)?;
+                    deltas[index].1.shift += days;
+                }
+                Change::Scale(id, factor) => {
+                    let index = position(&deltas, id)?;
+                    deltas[index].1.factor *= factor;
+                }
+            }
+        }
+
+        Ok(deltas)
+    }
+}
+
+/// A base prediction and the variants to compare against it
+pub struct Scenario<'a> {
+    base: &'a Prediction,
+    variants: Vec<Variant>,
+}
+
+impl<'a> Scenario<'a> {
+    pub fn new(base: &'a Prediction) -> Self {
+        Scenario {
+            base,
+            variants: vec![],
+        }
+    }
+
+    pub fn with_variant(mut self, variant: Variant) -> Self {
+        self.variants.push(variant);
+        self
+    }
+
+    pub fn base(&self) -> &Prediction {
+        self.base
+    }
+
+    pub fn variants(&self) -> &[Variant] {
+        &self.variants
+    }
+
+    /// Predict the base and every variant to `end`, and how far each variant strays from the
+    /// base along the way
+    pub fn compare(&self, end: &NaiveDate) -> Result<Comparison, MoolahCoreError> {
+        let base = self.base.predict(end)?;
+        let initial_value = self.base.initial_value();
+
+        let mut outcomes = vec![];
+        for variant in &self.variants {
+            let deltas = variant.deltas(self.base)?;
+            let timeline = self.base.predict_with(
+                deltas.iter().map(|(id, delta)| (*id, delta as &dyn Delta)),
+                end,
+            )?;
+
+            let dates: BTreeSet<_> = base.keys().chain(timeline.keys()).copied().collect();
+            let differences: BTreeMap<_, _> = dates
+                .into_iter()
+                .map(|date| {
+                    let difference = value_on(&timeline, &date, initial_value)
+                        - value_on(&base, &date, initial_value);
+                    (date, difference)
+                })
+                .collect();
+            let largest_divergence = differences
+                .iter()
+                .filter(|(_, difference)| **difference != Money::ZERO)
+                .fold(
+                    None,
+                    |largest: Option<Divergence>, (date, difference)| match largest {
+                        Some(largest) if largest.amount.abs() >= difference.abs() => Some(largest),
+                        _ => Some(Divergence {
+                            date: *date,
+                            amount: *difference,
+                        }),
+                    },
+                );
+
+            outcomes.push(Outcome {
+                name: variant.name.clone(),
+                timeline,
+                differences,
+                largest_divergence,
+            });
+        }
+
+        Ok(Comparison {
+            initial_value,
+            base,
+            variants: outcomes,
+        })
+    }
+}
+
+/// The value of the latest state in `timeline` on or before `date`
+fn value_on(
+    timeline: &BTreeMap<NaiveDate, PredictionState>,
+    date: &NaiveDate,
+    initial_value: Money,
+) -> Money {
+    timeline
+        .range(..=*date)
+        .next_back()
+        .map_or(initial_value, |(_, state)| state.value())
+}
+
+/// Where a variant strayed furthest from the base, positive when it had more money
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Divergence {
+    pub date: NaiveDate,
+    pub amount: Money,
+}
+
+/// One variant's prediction and how it compares with the base
+#[derive(Debug, Clone, PartialEq)]
+pub struct Outcome {
+    name: String,
+    timeline: BTreeMap<NaiveDate, PredictionState>,
+    differences: BTreeMap<NaiveDate, Money>,
+    largest_divergence: Option<Divergence>,
+}
+
+impl Outcome {
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn timeline(&self) -> &BTreeMap<NaiveDate, PredictionState> {
+        &self.timeline
+    }
+
+    /// The variant's value less the base's on every date either timeline has
+    pub fn differences(&self) -> &BTreeMap<NaiveDate, Money> {
+        &self.differences
+    }
+
+    /// The earliest of the largest differences, or `None` when the variant never strays from
+    /// the base
+    pub fn largest_divergence(&self) -> Option<&Divergence> {
+        self.largest_divergence.as_ref()
+    }
+}
+
+/// The values of the base and each variant on one date
+#[derive(Debug, Clone, PartialEq)]
+pub struct ComparisonRow {
+    pub date: NaiveDate,
+    pub base: Money,
+    pub variants: Vec<Money>,
+}
+
+/// The base prediction and its variants, run to the same end
+#[derive(Debug, Clone, PartialEq)]
+pub struct Comparison {
+    initial_value: Money,
+    base: BTreeMap<NaiveDate, PredictionState>,
+    variants: Vec<Outcome>,
+}
+
+impl Comparison {
+    pub fn base(&self) -> &BTreeMap<NaiveDate, PredictionState> {
+        &self.base
+    }
+
+    /// In the order the variants were added to the scenario
+    pub fn variants(&self) -> &[Outcome] {
+        &self.variants
+    }
+
+    pub fn variant(&self, name: &str) -> Option<&Outcome> {
+        self.variants.iter().find(|outcome| outcome.name == name)
+    }
+
+    /// The timelines side by side, one row for every date any of them has, each carrying its
+    /// latest value forward
+    pub fn rows(&self) -> Vec<ComparisonRow> {
+        let dates: BTreeSet<_> = self
+            .variants
+            .iter()
+            .flat_map(|outcome| outcome.timeline.keys())
+            .chain(self.base.keys())
+            .collect();
+
+        dates
+            .into_iter()
+            .map(|date| ComparisonRow {
+                date: *date,
+                base: value_on(&self.base, date, self.initial_value),
+                variants: self
+                    .variants
+                    .iter()
+                    .map(|outcome| value_on(&outcome.timeline, date, self.initial_value))
+                    .collect(),
+            })
+            .collect()
+    }
+}
+
+/// A base delta moved `shift` days and scaled by `factor`
+struct Adjusted<'a> {
+    delta: &'a dyn Delta,
+    shift: i64,
+    factor: f64,
+}
+
+impl<'a> Adjusted<'a> {
+    fn new(delta: &'a dyn Delta) -> Self {
+        Adjusted {
+            delta,
+            shift: 0,
+            factor: 1.0,
+        }
+    }
+
+    /// `date` moved `days` later
+    fn moved(date: NaiveDate, days: i64) -> NaiveDate {
+        let moved = if days < 0 {
+            date.checked_sub_days(Days::new(days.unsigned_abs()))
+        } else {
+            date.checked_add_days(Days::new(days.unsigned_abs()))
+        };
+        moved.unwrap_or(date)
+    }
+
+    /// The base delta's date that lands on `date` once shifted
+    fn original(&self, date: &NaiveDate) -> NaiveDate {
+        Adjusted::moved(*date, -self.shift)
+    }
+
+    fn scaled(&self, (value, min, max): (Money, Money, Money)) -> (Money, Money, Money) {
+        (
+            value.scale(self.factor),
+            min.scale(self.factor),
+            max.scale(self.factor),
+        )
+    }
+}
+
+impl Delta for Adjusted<'_> {
+    fn name(&self) -> &str {
+        self.delta.name()
+    }
+
+    fn value(&self) -> Money {
+        self.delta.value().scale(self.factor)
+    }
+
+    fn uncertainty(&self) -> &Option<Uncertainty> {
+        self.delta.uncertainty()
+    }
+
+    fn currency(&self) -> Option<&Currency> {
+        self.delta.currency()
+    }
+
+    fn dates(&self) -> Box<dyn Iterator<Item = NaiveDate> + '_> {
+        let shift = self.shift;
+        Box::new(
+            self.delta
+                .dates()
+                .map(move |date| Adjusted::moved(date, shift)),
+        )
+    }
+
+    fn dates_between(
+        &self,
+        start: &NaiveDate,
+        end: &NaiveDate,
+    ) -> Box<dyn Iterator<Item = NaiveDate> + '_> {
+        let shift = self.shift;
+        Box::new(
+            self.delta
+                .dates_between(&self.original(start), &self.original(end))
+                .map(move |date| Adjusted::moved(date, shift)),
+        )
+    }
+
+    fn is_open_ended(&self) -> bool {
+        self.delta.is_open_ended()
+    }
+
+    /// Adjusted deltas only live inside a comparison, so they serialize as the base delta
+    #[cfg(feature = "serde")]
+    fn tagged(&self) -> TaggedDeltaRef<'_> {
+        self.delta.tagged()
+    }
+
+    fn escalation_factor(&self, date: &NaiveDate) -> f64 {
+        self.delta.escalation_factor(&self.original(date))
+    }
+
+    fn amounts_on(&self, date: &NaiveDate) -> (Money, Money, Money) {
+        self.scaled(self.delta.amounts_on(&self.original(date)))
+    }
+
+    fn is_balance_dependent(&self) -> bool {
+        self.delta.is_balance_dependent()
+    }
+
+    fn evaluate(&self, state: &PredictionState) -> (Money, Money, Money) {
+        self.scaled(self.delta.evaluate(state))
+    }
+
+    fn sample_on(&self, date: &NaiveDate, rng: &mut dyn RngCore) -> Money {
+        self.delta
+            .sample_on(&self.original(date), rng)
+            .scale(self.factor)
+    }
+
+    fn sample_evaluate(&self, balance: Money, rng: &mut dyn RngCore) -> Money {
+        self.delta.sample_evaluate(balance, rng).scale(self.factor)
+    }
+
+    fn max_uncertainty_value(&self) -> Money {
+        self.delta.max_uncertainty_value().scale(self.factor)
+    }
+
+    fn min_uncertainty_value(&self) -> Money {
+        self.delta.min_uncertainty_value().scale(self.factor)
+    }
+}
@@ -0,0 +1,154 @@
+use super::*;
+use crate::{
+    date_helpers::ymd,
+    delta::{MonthlyDelta, OneTimeDelta},
+};
+
+fn car(value: i32) -> Box<dyn Delta> {
+    Box::new(
+        OneTimeDelta::try_new("car".into(), Money::from(value), None, ymd(2023, 6, 1)).unwrap(),
+    )
+}
+
+fn base() -> Prediction {
+    let salary = MonthlyDelta::try_new(
+        "salary".into(),
+        Money::from(3000),
+        None,
+        ymd(2023, 1, 1),
+        None,
+        1.try_into().unwrap(),
+        0,
+    )
+    .unwrap();
+    Prediction::new(
+        "base".into(),
+        ymd(2023, 1, 1),
+        Money::from(10000),
+        vec![Box::new(salary), car(-20000)],
+    )
+}
+
+#[test]
+fn test_compare() {
+    let base = base();
+    let scenario = Scenario::new(&base)
+        .with_variant(Variant::new("march".into()).with_shift("car".into(), -92))
+        .with_variant(Variant::new("cheaper".into()).with_override("car".into(), car(-15000)))
+        .with_variant(
+            Variant::new("raise".into())
+                .with_scale("salary".into(), 1.1)
+                .unwrap(),
+        )
+        .with_variant(Variant::new("no car".into()).with_removed("car".into()));
+    let comparison = scenario.compare(&ymd(2023, 12, 31)).unwrap();
+
+    let divergence = |name: &str| {
+        comparison
+            .variant(name)
+            .unwrap()
+            .largest_divergence()
+            .copied()
+            .unwrap()
+    };
+    assert_eq!(
+        divergence("march"),
+        Divergence {
+            date: ymd(2023, 3, 1),
+            amount: Money::from(-20000)
+        }
+    );
+    assert_eq!(
+        divergence("cheaper"),
+        Divergence {
+            date: ymd(2023, 6, 1),
+            amount: Money::from(5000)
+        }
+    );
+    assert_eq!(
+        divergence("raise"),
+        Divergence {
+            date: ymd(2023, 12, 1),
+            amount: Money::from(3600)
+        }
+    );
+    assert_eq!(divergence("no car").amount, Money::from(20000));
+
+    let march = comparison.variant("march").unwrap();
+    assert_eq!(march.differences()[&ymd(2023, 5, 1)], Money::from(-20000));
+    assert_eq!(march.differences()[&ymd(2023, 6, 1)], Money::ZERO);
+
+    let rows = comparison.rows();
+    let march_1 = rows.iter().find(|row| row.date == ymd(2023, 3, 1)).unwrap();
+    assert_eq!(march_1.base, Money::from(19000));
+    assert_eq!(
+        march_1.variants,
+        [
+            Money::from(-1000),
+            Money::from(19000),
+            Money::from(19900),
+            Money::from(19000)
+        ]
+    );
+}
+
+#[test]
+fn test_shift_open_ended_delta() {
+    let base = base();
+    let scenario = Scenario::new(&base)
+        .with_variant(Variant::new("later".into()).with_shift("salary".into(), 14));
+    let comparison = scenario.compare(&ymd(2023, 2, 28)).unwrap();
+
+    let later = &comparison.variants()[0];
+    assert_eq!(
+        later.timeline()[&ymd(2023, 1, 1)].value(),
+        Money::from(10000)
+    );
+    assert_eq!(
+        later.timeline()[&ymd(2023, 1, 15)].value(),
+        Money::from(13000)
+    );
+    assert_eq!(
+        later.timeline()[&ymd(2023, 2, 15)].value(),
+        Money::from(16000)
+    );
+    assert_eq!(
+        later.largest_divergence(),
+        Some(&Divergence {
+            date: ymd(2023, 1, 1),
+            amount: Money::from(-3000)
+        })
+    );
+}
+
+#[test]
+fn test_unchanged_variant() {
+    let base = base();
+    let comparison = Scenario::new(&base)
+        .with_variant(Variant::new("same".into()))
+        .compare(&ymd(2023, 12, 31))
+        .unwrap();
+    assert_eq!(comparison.variants()[0].largest_divergence(), None);
+    assert_eq!(comparison.variants()[0].timeline(), comparison.base());
+}
+
+#[test]
+fn test_errors() {
+    let base = base();
+    let result = Scenario::new(&base)
+        .with_variant(Variant::new("boat".into()).with_shift("boat".into(), 30))
+        .compare(&ymd(2023, 12, 31));
+    assert!(matches!(result, Err(MoolahCoreError::UnknownDelta(id)) if id == "boat"));
+
+    let result = Scenario::new(&base)
+        .with_variant(
+            Variant::new("second car".into())
+                .with_added(DeltaId::try_new("car").unwrap(), car(-5000)),
+        )
+        .compare(&ymd(2023, 12, 31));
+    assert!(matches!(result, Err(MoolahCoreError::DuplicateDeltaId(id)) if id == "car"));
+
+    assert!(Variant::new("refund".into())
+        .with_scale("car".into(), -1.0)
+        .is_err());
+}
@@ -0,0 +1,379 @@
+//! Human-editable TOML scenario files.
+//!
+//! A scenario file describes a [`Prediction`]. The top level holds the prediction's `name`,
+//! `start` and `initial_value`, followed by one `[[delta]]` table per delta:
+//!
+//! ```toml
+//! name = "household"
+//! start = 2023-01-01
+//! initial_value = 5000
+//!
+//! [[delta]]
+//! kind = "monthly"
+//! name = "rent"
+//! value = -1800
+//! on_month_day = 1
+//! start = 2023-01-01
+//! end = 2023-12-31
+//!
+//! [[delta]]
+//! kind = "weekly"
+//! name = "groceries"
+//! value = -150
+//! uncertainty = { balanced = { percent = 10 } }
+//! on_weekday = "Sat"
+//! start = 2023-01-01
+//! end = 2023-12-31
+//! ```
+//!
+//! Every delta has a `kind`, a `name`, a `value`, an optional `id` and an optional `uncertainty`
+//! (`balanced`, `unbalanced` with `low` & `high`, or `bounds` with `low` & `high`, where the first
+//! two take either `dollars` or `percent`). The uncertainty may instead be a distribution around
+//! the value: `normal` with a `std_dev`, `lognormal` with a `sigma`, `triangular` or `uniform`
+//! with `low` & `high`, or `empirical` with a list of past `amounts`. A distribution's min & max
+//! are its 5% and 95% quantiles unless it sets `quantiles`:
+//!
+//! ```toml
+//! uncertainty = { empirical = { amounts = [-140, -120, -95], quantiles = { low = 0.1, high = 0.9 } } }
+//! ```
+//!
+//! A delta's `id` picks it out from the others, so that it stays the same delta when it is renamed
+//! and so that deltas may share a name. No two deltas may have the same `id`, and a delta without
+//! one takes its name, or its name with a `-2`, `-3`, ... suffix when that is taken.
+//!
+//! The remaining keys depend on the kind:
+//!
+//! | kind       | keys                                                     |
+//! |------------|----------------------------------------------------------|
+//! | `one_time` | `date`                                                   |
+//! | `daily`    | `start`, `end` (default none), `skip_days` (default 0)   |
+//! | `weekly`   | `start`, `end` (default none), `on_weekday` (default start's weekday), `skip_weeks` (default 0) |
+//! | `monthly`  | `start`, `end` (default none), `on_month_day` (default 1), `skip_months` (default 0) |
+//! | `yearly`   | `start`, `end` (default none), `skip_years` (default 0)  |
+//! | `custom`   | `dates`, `amounts` (default none)                        |
+//! | `rrule`    | `start`, `end` (default none), `rrule`, `exdates` (default none) |
+//!
+//! A recurring delta without an `end`, such as a salary, carries on indefinitely; a prediction
+//! only looks at its dates up to the date being predicted.
+//!
+//! A `custom` delta adds its `value` on each of its `dates`, and may instead give some dates
+//! their own amounts, such as `amounts = { 2023-01-10 = -300, 2023-02-10 = -200 }` for a known
+//! payment plan. Its `value` then defaults to 0 and its `dates` to none.
+//!
+//! A `monthly` delta's `on_month_day` is a day such as `15`, which falls on the last day of
+//! shorter months, a day counted back from the end of the month such as `-1` for the last day, or
+//! a weekday rule such as `"first Mon"` or `"last Fri"`. It may also be a list of these, such as
+//! `on_month_day = [15, -1]` for a paycheck on the 15th and the last day of each month.
+//!
+//! The `weekly`, `monthly` and `yearly` kinds may also move dates that fall on a weekend or
+//! holiday to a business day with `roll`: `preceding`, `following`, `modified_following` (the
+//! following business day unless that is in the next month) or `none` (the default). The
+//! holidays come from `calendar`, which may include the observed US federal holidays and lists
+//! any others:
+//!
+//! ```toml
+//! roll = "preceding"
+//! calendar = { us_federal = true, holidays = [2023-11-24] }
+//! ```
+//!
+//! Every recurring kind (`daily`, `weekly`, `monthly`, `yearly` and `rrule`) may grow over time
+//! with an `escalation`: `annual` by a percent on each anniversary of `start`, `steps` by a
+//! percent from each step's `date`, or with an `index` such as CPI, relative to its level at
+//! `start`. Each occurrence's amount and uncertainty are scaled accordingly:
+//!
+//! ```toml
+//! escalation = { annual = 3 }
+//! escalation = { steps = [{ date = 2024-06-01, percent = 5 }] }
+//! escalation = { index = [{ date = 2023-01-01, level = 300.5 }, { date = 2024-01-01, level = 310.3 }] }
+//! ```
+//!
+//! An `rrule` delta recurs by an RFC 5545 recurrence rule starting from `start`, such as
+//! `rrule = "FREQ=MONTHLY;BYDAY=MO,TU,WE,TH,FR;BYSETPOS=-1"` for the last weekday of each month.
+//! It supports `INTERVAL`, `COUNT`, `UNTIL`, `BYDAY`, `BYMONTHDAY`, `BYMONTH`, `BYSETPOS` and
+//! `WKST`, and leaves out any dates listed in `exdates`.
+//!
+//! An `interest` delta grows the running balance instead of adding a fixed `value`. It takes a
+//! nominal annual percent `rate`, an optional `rate_uncertainty` (`balanced` percentage points, or
+//! `bounds` with `low` & `high` rates), `compounding` (`daily`, `monthly` (default) or `yearly`),
+//! `start` and an optional `end`:
+//!
+//! ```toml
+//! [[delta]]
+//! kind = "interest"
+//! name = "savings interest"
+//! rate = 4.5
+//! rate_uncertainty = { balanced = 0.5 }
+//! compounding = "daily"
+//! start = 2023-01-01
+//! end = 2023-12-31
+//! ```
+//!
+//! A `loan` delta pays off an amortizing loan with fixed monthly payments. It takes the
+//! `principal`, a nominal annual percent `rate`, `term_months`, the `start` date the money was
+//! borrowed, `on_month_day` (default 1) and optional `extra_payments`, each either `recurring`
+//! from a date or a `one_time` payment applied with the next scheduled one:
+//!
+//! ```toml
+//! [[delta]]
+//! kind = "loan"
+//! name = "mortgage"
+//! principal = 300000
+//! rate = 6
+//! term_months = 360
+//! start = 2023-01-10
+//! extra_payments = [
+//!     { recurring = { amount = 200, from = 2024-01-01 } },
+//!     { one_time = { amount = 10000, date = 2025-06-01 } },
+//! ]
+//! ```
+//!
+//! A scenario in more than one currency sets the reporting `currency` at the top level, which is
+//! also the currency of `initial_value`. Any delta may then set its own `currency`, and is
+//! converted using the `[[exchange_rate]]` tables:
+//!
+//! ```toml
+//! currency = "USD"
+//!
+//! [[exchange_rate]]
+//! from = "EUR"
+//! to = "USD"
+//! rate = 1.08
+//!
+//! [[exchange_rate]]
+//! from = "GBP"
+//! to = "USD"
+//! rates = [{ date = 2023-01-01, rate = 1.21 }, { date = 2023-07-01, rate = 1.27 }]
+//! interpolation = "linear"
+//! ```
+//!
+//! Dated `rates` apply from their date onwards (`interpolation = "previous"`, the default) or are
+//! interpolated between (`"linear"`). A rate given in one direction is also used, inverted, in the
+//! other.
+//!
+//! A balance actually observed along the way, such as from a bank statement, goes in a
+//! `[[checkpoint]]` table. The prediction restarts from its `balance` on its `date`, after that
+//! date's deltas, and reports the variance it found there. An optional `weight` below 1 only
+//! moves the prediction and its uncertainty part of the way toward the balance:
+//!
+//! ```toml
+//! [[checkpoint]]
+//! date = 2023-10-01
+//! balance = 5230.12
+//! weight = 0.8
+//! ```
+//!
+//! Dates may be written either as bare TOML dates or as `"YYYY-MM-DD"` strings. Amounts of money
+//! may be numbers or decimal strings such as `"-1800.10"`, and are rounded to the nearest cent.
+//!
+//! Scenario files are TOML only. There is no YAML loader, since errors point at the failing delta
+//! using the positions TOML keeps for each table.
+
+#[cfg(test)]
+mod tests;
+
+use crate::{
+    currency::{Currency, ExchangeRates, RawExchangeRate},
+    delta::{
+        tagged::{IdentifiedDeltaRef, UncheckedDelta},
+        Delta, DeltaId,
+    },
+    errors::MoolahCoreError,
+    money::Money,
+    prediction::{Checkpoint, Prediction},
+};
+use chrono::NaiveDate;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use toml::{Spanned, Table, Value};
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RawScenarioFile {
+    #[serde(default)]
+    name: String,
+    start: Spanned<Value>,
+    #[serde(default)]
+    initial_value: Money,
+    #[serde(default)]
+    currency: Option<Currency>,
+    #[serde(default)]
+    exchange_rate: Vec<Spanned<Table>>,
+    #[serde(default)]
+    delta: Vec<Spanned<Table>>,
+    #[serde(default)]
+    checkpoint: Vec<Spanned<Table>>,
+}
+
+#[derive(Serialize)]
+struct ScenarioFile<'a> {
+    name: &'a str,
+    start: &'a NaiveDate,
+    initial_value: Money,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    currency: Option<&'a Currency>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    exchange_rate: Vec<RawExchangeRate>,
+    delta: Vec<IdentifiedDeltaRef<'a>>,
+    #[serde(skip_serializing_if = "<[_]>::is_empty")]
+    checkpoint: &'a [Checkpoint],
+}
+
+pub fn from_str(input: &str) -> Result<Prediction, MoolahCoreError> {
+    let raw: RawScenarioFile = toml::from_str(input).map_err(|err| {
+        syntax_error(
+            input,
+            err.span().map_or(0, |span| span.start),
+            err.message(),
+        )
+    })?;
+
+    let start_offset = raw.start.span().start;
+    let start: NaiveDate = from_value(raw.start.into_inner())
+        .map_err(|err| syntax_error(input, start_offset, err.message()))?;
+
+    let mut exchange_rates = ExchangeRates::default();
+    for table in raw.exchange_rate {
+        parse_exchange_rate(input, table, &mut exchange_rates)?;
+    }
+
+    let mut deltas: Vec<(Option<DeltaId>, Box<dyn Delta>)> = vec![];
+    for (index, table) in raw.delta.into_iter().enumerate() {
+        deltas.push(parse_delta(input, index, table, &deltas)?);
+    }
+
+    let checkpoints = raw
+        .checkpoint
+        .into_iter()
+        .map(|table| {
+            let offset = table.span().start;
+            from_value(Value::Table(table.into_inner()))
+                .map_err(|err| syntax_error(input, offset, err.message()))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let prediction = Prediction::new(raw.name, start, raw.initial_value, vec![])
+        .with_identified_deltas(deltas)?
+        .with_exchange_rates(exchange_rates)
+        .with_checkpoints(checkpoints);
+    Ok(match raw.currency {
+        Some(currency) => prediction.with_currency(currency),
+        None => prediction,
+    })
+}
+
+pub fn to_string(prediction: &Prediction) -> Result<String, MoolahCoreError> {
+    toml::to_string(&ScenarioFile {
+        name: prediction.name(),
+        start: prediction.start(),
+        initial_value: prediction.initial_value(),
+        currency: prediction.currency(),
+        exchange_rate: prediction.exchange_rates().to_raw(),
+        delta: prediction
+            .identified_deltas()
+            .map(|(id, delta)| IdentifiedDeltaRef {
+                id: (id.as_str() != delta.name()).then_some(id),
+                delta: delta.tagged(),
+            })
+            .collect(),
+        checkpoint: prediction.checkpoints(),
+    })
+    .map_err(|err| MoolahCoreError::ScenarioFileWrite(err.to_string()))
+}
+
+/// A delta and its `id`, which must differ from those of the `preceding` deltas
+fn parse_delta(
+    input: &str,
+    index: usize,
+    table: Spanned<Table>,
+    preceding: &[(Option<DeltaId>, Box<dyn Delta>)],
+) -> Result<(Option<DeltaId>, Box<dyn Delta>), MoolahCoreError> {
+    let (line, column) = line_column(input, table.span().start);
+    let mut table = table.into_inner();
+    let name = match table.get("name") {
+        Some(Value::String(name)) => name.clone(),
+        _ => format!("#{}", index + 1),
+    };
+    let delta_error = |source| MoolahCoreError::ScenarioFileDelta {
+        name: name.clone(),
+        line,
+        column,
+        source: Box::new(source),
+    };
+
+    let id = match table.remove("id") {
+        Some(Value::String(id)) => Some(DeltaId::try_new(&id).map_err(delta_error)?),
+        Some(_) => {
+            return Err(delta_error(MoolahCoreError::MalformedDelta(
+                "`id` must be a string".into(),
+            )))
+        }
+        None => None,
+    };
+    if let Some(id) = &id {
+        if preceding
+            .iter()
+            .any(|(other, _)| other.as_ref() == Some(id))
+        {
+            return Err(delta_error(MoolahCoreError::DuplicateDeltaId(
+                id.to_string(),
+            )));
+        }
+    }
+
+    let unchecked: UncheckedDelta = from_value(Value::Table(table))
+        .map_err(|err| delta_error(MoolahCoreError::MalformedDelta(err.message().into())))?;
+    Ok((id, Box::try_from(unchecked).map_err(delta_error)?))
+}
+
+fn parse_exchange_rate(
+    input: &str,
+    table: Spanned<Table>,
+    exchange_rates: &mut ExchangeRates,
+) -> Result<(), MoolahCoreError> {
+    let offset = table.span().start;
+    let raw: RawExchangeRate = from_value(Value::Table(table.into_inner()))
+        .map_err(|err| syntax_error(input, offset, err.message()))?;
+    exchange_rates.insert_raw(raw).map_err(|source| {
+        let (line, column) = line_column(input, offset);
+        MoolahCoreError::ScenarioFileExchangeRate {
+            line,
+            column,
+            source: Box::new(source),
+        }
+    })
+}
+
+fn from_value<T: DeserializeOwned>(mut value: Value) -> Result<T, toml::de::Error> {
+    dates_to_strings(&mut value);
+    value.try_into()
+}
+
+/// chrono only deserializes dates from strings, so bare TOML dates are converted first
+fn dates_to_strings(value: &mut Value) {
+    match value {
+        Value::Datetime(datetime) => {
+            let date = datetime.to_string();
+            *value = Value::String(date);
+        }
+        Value::Array(array) => array.iter_mut().for_each(dates_to_strings),
+        Value::Table(table) => table
+            .iter_mut()
+            .for_each(|(_, value)| dates_to_strings(value)),
+        _ => {}
+    }
+}
+
+fn syntax_error(input: &str, offset: usize, message: &str) -> MoolahCoreError {
+    let (line, column) = line_column(input, offset);
+    MoolahCoreError::ScenarioFileSyntax {
+        line,
+        column,
+        message: message.trim().into(),
+    }
+}
+
+fn line_column(input: &str, offset: usize) -> (usize, usize) {
+    let preceding = &input[..offset.min(input.len())];
+    let line = preceding.matches('\n').count() + 1;
+    let column = preceding.chars().rev().take_while(|c| *c != '\n').count() + 1;
+    (line, column)
+}
@@ -0,0 +1,616 @@
+use super::*;
+use crate::{currency::Currency, date_helpers::naive_ymd};
+
+const HOUSEHOLD: &str = r#"name = "household"
+start = 2023-01-01
+initial_value = 5000
+
+[[delta]]
+kind = "monthly"
+name = "rent"
+value = -1800
+on_month_day = 1
+start = 2023-01-01
+end = 2023-12-31
+
+[[delta]]
+kind = "weekly"
+name = "groceries"
+value = -150
+uncertainty = { balanced = { percent = 10 } }
+on_weekday = "Sat"
+start = "2023-01-01"
+end = "2023-12-31"
+
+[[delta]]
+kind = "one_time"
+name = "bonus"
+value = 2500
+uncertainty = { bounds = { low = 2000, high = 3000 } }
+date = 2023-03-15
+
+[[delta]]
+kind = "custom"
+name = "car registration"
+value = -120
+dates = [2023-04-01, 2024-04-01]
+"#;
+
+#[test]
+fn test_load() {
+    let p = from_str(HOUSEHOLD).expect("could not load scenario file");
+
+    assert_eq!(p.name(), "household");
+    assert_eq!(*p.start(), naive_ymd(2023, 1, 1).unwrap());
+    assert_eq!(p.initial_value(), Money::from(5000));
+
+    let names: Vec<&str> = p.deltas().iter().map(|delta| delta.name()).collect();
+    assert_eq!(names, ["rent", "groceries", "bonus", "car registration"]);
+    assert_eq!(p.deltas()[0].dates().count(), 12);
+    assert_eq!(
+        p.deltas()[1].dates().next().unwrap(),
+        naive_ymd(2023, 1, 7).unwrap()
+    );
+    assert_eq!(p.deltas()[2].max_uncertainty_value(), Money::from(3000));
+    assert_eq!(
+        p.deltas()[3].dates().collect::<Vec<_>>(),
+        &[
+            naive_ymd(2023, 4, 1).unwrap(),
+            naive_ymd(2024, 4, 1).unwrap()
+        ]
+    );
+}
+
+#[test]
+fn test_round_trip() {
+    let p = from_str(HOUSEHOLD).unwrap();
+    let written = to_string(&p).expect("could not write scenario file");
+    let reloaded = from_str(&written).expect("could not reload written scenario file");
+
+    assert_eq!(reloaded.name(), p.name());
+    assert_eq!(reloaded.start(), p.start());
+    assert_eq!(reloaded.initial_value(), p.initial_value());
+    let end = naive_ymd(2024, 12, 31).unwrap();
+    assert_eq!(reloaded.predict(&end).unwrap(), p.predict(&end).unwrap());
+}
+
+#[test]
+fn test_syntax_error_location() {
+    let input = "name = \"household\"\nstart = 2023-01-01\ninitial_value = \n";
+
+    match from_str(input) {
+        Err(MoolahCoreError::ScenarioFileSyntax { line, column, .. }) => {
+            assert_eq!((line, column), (3, 17));
+        }
+        _ => panic!("expected a syntax error"),
+    }
+}
+
+#[test]
+fn test_invalid_start() {
+    let input = "name = \"household\"\nstart = \"yesterday\"\n";
+
+    match from_str(input) {
+        Err(MoolahCoreError::ScenarioFileSyntax { line, column, .. }) => {
+            assert_eq!((line, column), (2, 9));
+        }
+        _ => panic!("expected a syntax error"),
+    }
+}
+
+#[test]
+fn test_validation_error_names_delta() {
+    let input = r#"start = 2023-01-01
+
+[[delta]]
+kind = "one_time"
+name = "bonus"
+value = 2500
+date = 2023-03-15
+
+[[delta]]
+kind = "yearly"
+name = "insurance"
+value = -900
+start = 2024-01-01
+end = 2023-01-01
+"#;
+
+    match from_str(input) {
+        Err(MoolahCoreError::ScenarioFileDelta {
+            name,
+            line,
+            column,
+            source,
+        }) => {
+            assert_eq!(name, "insurance");
+            assert_eq!((line, column), (9, 1));
+            assert!(matches!(*source, MoolahCoreError::StartAfterEnd { .. }));
+        }
+        _ => panic!("expected a delta error"),
+    }
+}
+
+#[test]
+fn test_month_day_out_of_range() {
+    let input = r#"start = 2023-01-01
+
+[[delta]]
+kind = "monthly"
+name = "rent"
+value = -1800
+on_month_day = 32
+start = 2023-01-01
+end = 2023-12-31
+"#;
+
+    match from_str(input) {
+        Err(MoolahCoreError::ScenarioFileDelta { name, source, .. }) => {
+            assert_eq!(name, "rent");
+            assert!(matches!(*source, MoolahCoreError::MonthDayOutOfRange(32)));
+        }
+        _ => panic!("expected a delta error"),
+    }
+}
+
+#[test]
+fn test_malformed_delta() {
+    let input = r#"start = 2023-01-01
+
+[[delta]]
+kind = "monthly"
+value = -1800
+start = 2023-01-01
+end = 2023-12-31
+skip_month = 1
+"#;
+
+    match from_str(input) {
+        Err(MoolahCoreError::ScenarioFileDelta {
+            name, line, source, ..
+        }) => {
+            assert_eq!(name, "#1");
+            assert_eq!(line, 3);
+            assert!(matches!(*source, MoolahCoreError::MalformedDelta(_)));
+        }
+        _ => panic!("expected a delta error"),
+    }
+}
+
+const TRAVEL: &str = r#"start = 2023-01-01
+initial_value = 1000
+currency = "USD"
+
+[[exchange_rate]]
+from = "EUR"
+to = "USD"
+rate = 1.25
+
+[[exchange_rate]]
+from = "USD"
+to = "GBP"
+rates = [{ date = 2023-01-01, rate = 0.8 }, { date = 2023-02-01, rate = 0.5 }]
+
+[[delta]]
+kind = "one_time"
+name = "hotel"
+value = -200
+currency = "EUR"
+date = 2023-01-10
+
+[[delta]]
+kind = "one_time"
+name = "refund"
+value = 50
+currency = "GBP"
+date = 2023-02-10
+"#;
+
+#[test]
+fn test_currencies() {
+    let p = from_str(TRAVEL).unwrap();
+    let usd = Currency::try_new("USD").unwrap();
+
+    assert_eq!(p.currency(), Some(&usd));
+    assert_eq!(
+        p.deltas()[0].currency(),
+        Some(&Currency::try_new("EUR").unwrap())
+    );
+
+    let timeline = p.predict(&naive_ymd(2023, 3, 1).unwrap()).unwrap();
+    let values: Vec<Money> = timeline.values().map(|state| state.value()).collect();
+    assert_eq!(
+        values,
+        [Money::from(1000), Money::from(750), Money::from(850)]
+    );
+
+    let reloaded = from_str(&to_string(&p).unwrap()).unwrap();
+    assert_eq!(reloaded.currency(), Some(&usd));
+    assert_eq!(reloaded.exchange_rates(), p.exchange_rates());
+    assert_eq!(
+        reloaded.predict(&naive_ymd(2023, 3, 1).unwrap()).unwrap(),
+        timeline
+    );
+}
+
+#[test]
+fn test_invalid_exchange_rate() {
+    let input = r#"start = 2023-01-01
+currency = "USD"
+
+[[exchange_rate]]
+from = "EUR"
+to = "USD"
+rate = -1.1
+"#;
+
+    match from_str(input) {
+        Err(MoolahCoreError::ScenarioFileExchangeRate {
+            line,
+            column,
+            source,
+        }) => {
+            assert_eq!((line, column), (4, 1));
+            assert!(matches!(*source, MoolahCoreError::InvalidExchangeRate(_)));
+        }
+        _ => panic!("expected an exchange rate error"),
+    }
+
+    let input = "start = 2023-01-01\ncurrency = \"usd\"\n";
+    assert!(matches!(
+        from_str(input),
+        Err(MoolahCoreError::ScenarioFileSyntax { line: 2, .. })
+    ));
+}
+
+#[test]
+fn test_interest() {
+    let input = r#"start = 2023-01-01
+initial_value = 1000
+
+[[delta]]
+kind = "interest"
+name = "savings interest"
+rate = 12
+rate_uncertainty = { bounds = { low = 10, high = 14 } }
+start = 2023-01-01
+end = 2023-12-31
+"#;
+    let p = from_str(input).unwrap();
+    let timeline = p.predict(&naive_ymd(2023, 2, 1).unwrap()).unwrap();
+    let state = &timeline[&naive_ymd(2023, 2, 1).unwrap()];
+
+    assert_eq!(state.value(), Money::from(1010));
+    assert_eq!(state.min_uncertainty_val(), Money::from_cents(100833));
+    assert_eq!(state.max_uncertainty_val(), Money::from_cents(101167));
+
+    let reloaded = from_str(&to_string(&p).unwrap()).unwrap();
+    assert_eq!(
+        reloaded.predict(&naive_ymd(2023, 6, 1).unwrap()).unwrap(),
+        p.predict(&naive_ymd(2023, 6, 1).unwrap()).unwrap()
+    );
+}
+
+#[test]
+fn test_loan() {
+    let input = r#"start = 2023-01-01
+initial_value = 5000
+
+[[delta]]
+kind = "loan"
+name = "car loan"
+principal = 1200
+rate = 0
+term_months = 12
+start = 2023-01-01
+on_month_day = 15
+extra_payments = [{ one_time = { amount = 500, date = 2023-02-01 } }]
+"#;
+    let p = from_str(input).unwrap();
+    let timeline = p.predict(&naive_ymd(2023, 12, 31).unwrap()).unwrap();
+
+    assert_eq!(
+        timeline[&naive_ymd(2023, 2, 15).unwrap()].value(),
+        Money::from(4300)
+    );
+    assert_eq!(timeline.values().last().unwrap().value(), Money::from(3800));
+
+    let reloaded = from_str(&to_string(&p).unwrap()).unwrap();
+    assert_eq!(
+        reloaded.predict(&naive_ymd(2023, 12, 31).unwrap()).unwrap(),
+        timeline
+    );
+}
+
+#[test]
+fn test_distributions() {
+    let input = r#"start = 2023-01-01
+initial_value = 1000
+
+[[delta]]
+kind = "one_time"
+name = "electricity"
+value = -100
+uncertainty = { empirical = { amounts = [-140, -120, -95], quantiles = { low = 0.25, high = 0.75 } } }
+date = 2023-01-15
+
+[[delta]]
+kind = "one_time"
+name = "bonus"
+value = 500
+uncertainty = { lognormal = { sigma = 0.2 } }
+date = 2023-01-20
+"#;
+    let p = from_str(input).unwrap();
+    let state =
+        &p.predict(&naive_ymd(2023, 1, 15).unwrap()).unwrap()[&naive_ymd(2023, 1, 15).unwrap()];
+    assert_eq!(state.min_uncertainty_val(), Money::from(870));
+    assert_eq!(state.max_uncertainty_val(), Money::from_cents(89250));
+
+    let reloaded = from_str(&to_string(&p).unwrap()).unwrap();
+    assert_eq!(
+        reloaded.predict(&naive_ymd(2023, 2, 1).unwrap()).unwrap(),
+        p.predict(&naive_ymd(2023, 2, 1).unwrap()).unwrap()
+    );
+}
+
+#[test]
+fn test_roll_convention() {
+    let input = r#"start = 2023-01-01
+
+[[delta]]
+kind = "monthly"
+name = "paycheck"
+value = 2000
+on_month_day = 15
+start = 2023-01-01
+end = 2023-04-30
+roll = "preceding"
+calendar = { us_federal = true, holidays = [2023-03-15] }
+"#;
+    let p = from_str(input).unwrap();
+    assert_eq!(
+        p.deltas()[0].dates().collect::<Vec<_>>(),
+        [
+            naive_ymd(2023, 1, 13).unwrap(),
+            naive_ymd(2023, 2, 15).unwrap(),
+            naive_ymd(2023, 3, 14).unwrap(),
+            naive_ymd(2023, 4, 14).unwrap(),
+        ]
+    );
+
+    let reloaded = from_str(&to_string(&p).unwrap()).unwrap();
+    assert_eq!(
+        reloaded.deltas()[0].dates().collect::<Vec<_>>(),
+        p.deltas()[0].dates().collect::<Vec<_>>()
+    );
+}
+
+#[test]
+fn test_month_days() {
+    let input = r#"start = 2023-01-01
+
+[[delta]]
+kind = "monthly"
+name = "payroll"
+value = 2000
+on_month_day = [15, -1]
+start = 2023-01-01
+end = 2023-02-28
+
+[[delta]]
+kind = "monthly"
+name = "book club"
+value = -20
+on_month_day = "first Mon"
+start = 2023-01-01
+end = 2023-02-28
+"#;
+    let p = from_str(input).unwrap();
+    assert_eq!(
+        p.deltas()[0].dates().collect::<Vec<_>>(),
+        [
+            naive_ymd(2023, 1, 15).unwrap(),
+            naive_ymd(2023, 1, 31).unwrap(),
+            naive_ymd(2023, 2, 15).unwrap(),
+            naive_ymd(2023, 2, 28).unwrap(),
+        ]
+    );
+    assert_eq!(
+        p.deltas()[1].dates().collect::<Vec<_>>(),
+        [
+            naive_ymd(2023, 1, 2).unwrap(),
+            naive_ymd(2023, 2, 6).unwrap()
+        ]
+    );
+
+    let reloaded = from_str(&to_string(&p).unwrap()).unwrap();
+    assert_eq!(
+        reloaded.deltas()[0].dates().collect::<Vec<_>>(),
+        p.deltas()[0].dates().collect::<Vec<_>>()
+    );
+    assert_eq!(
+        reloaded.deltas()[1].dates().collect::<Vec<_>>(),
+        p.deltas()[1].dates().collect::<Vec<_>>()
+    );
+}
+
+#[test]
+fn test_escalation() {
+    let input = r#"start = 2023-01-01
+
+[[delta]]
+kind = "monthly"
+name = "rent"
+value = -1000
+start = 2023-01-01
+end = 2024-12-31
+escalation = { annual = 3 }
+
+[[delta]]
+kind = "weekly"
+name = "groceries"
+value = -100
+on_weekday = "Sat"
+start = 2023-01-01
+end = 2024-12-31
+escalation = { index = [{ date = 2023-01-01, level = 300 }, { date = 2024-01-01, level = 312 }] }
+"#;
+    let p = from_str(input).unwrap();
+    let date = naive_ymd(2024, 1, 6).unwrap();
+    assert_eq!(p.deltas()[0].amounts_on(&date).0, Money::from(-1030));
+    assert_eq!(p.deltas()[1].amounts_on(&date).0, Money::from(-104));
+
+    let reloaded = from_str(&to_string(&p).unwrap()).unwrap();
+    assert_eq!(
+        reloaded.predict(&naive_ymd(2024, 12, 31).unwrap()).unwrap(),
+        p.predict(&naive_ymd(2024, 12, 31).unwrap()).unwrap()
+    );
+}
+
+#[test]
+fn test_custom_amounts() {
+    let input = r#"start = 2023-01-01
+initial_value = 1000
+
+[[delta]]
+kind = "custom"
+name = "payment plan"
+amounts = { 2023-01-10 = -300, 2023-02-10 = -200 }
+"#;
+    let p = from_str(input).unwrap();
+    let end = naive_ymd(2023, 2, 10).unwrap();
+    assert_eq!(p.predict(&end).unwrap()[&end].value(), Money::from(500));
+
+    let reloaded = from_str(&to_string(&p).unwrap()).unwrap();
+    assert_eq!(reloaded.predict(&end).unwrap(), p.predict(&end).unwrap());
+}
+
+#[test]
+fn test_open_ended() {
+    let input = r#"start = 2023-01-01
+
+[[delta]]
+kind = "monthly"
+name = "salary"
+value = 3000
+start = 2023-01-01
+on_month_day = -1
+
+[[delta]]
+kind = "rrule"
+name = "gym"
+value = -40
+start = 2023-01-01
+rrule = "FREQ=MONTHLY;BYDAY=1MO"
+"#;
+    let p = from_str(input).unwrap();
+    assert!(p.deltas().iter().all(|delta| delta.is_open_ended()));
+    let end = naive_ymd(2050, 12, 31).unwrap();
+    assert_eq!(
+        p.predict(&end).unwrap()[&end].value(),
+        Money::from(336 * (3000 - 40))
+    );
+
+    let serialized = to_string(&p).unwrap();
+    assert!(!serialized.contains("end"));
+    let reloaded = from_str(&serialized).unwrap();
+    assert_eq!(reloaded.predict(&end).unwrap(), p.predict(&end).unwrap());
+}
+
+#[test]
+fn test_checkpoints() {
+    let input = r#"start = 2023-01-01
+initial_value = 1000
+
+[[delta]]
+kind = "monthly"
+name = "rent"
+value = -500
+start = 2023-01-01
+
+[[checkpoint]]
+date = 2023-02-01
+balance = 100
+
+[[checkpoint]]
+date = "2023-03-01"
+balance = 0
+weight = 0.5
+"#;
+    let p = from_str(input).unwrap();
+    assert_eq!(p.checkpoints().len(), 2);
+    let timeline = p.predict(&naive_ymd(2023, 3, 1).unwrap()).unwrap();
+    let february = &timeline[&naive_ymd(2023, 2, 1).unwrap()];
+    assert_eq!(february.variance(), Some(Money::from(100)));
+    let march = &timeline[&naive_ymd(2023, 3, 1).unwrap()];
+    assert_eq!(march.variance(), Some(Money::from(400)));
+    assert_eq!(march.value(), Money::from(-200));
+
+    let reloaded = from_str(&to_string(&p).unwrap()).unwrap();
+    assert_eq!(reloaded.checkpoints(), p.checkpoints());
+
+    let input =
+        "start = 2023-01-01\n\n[[checkpoint]]\ndate = 2023-02-01\nbalance = 100\nweight = 2\n";
+    assert!(matches!(
+        from_str(input),
+        Err(MoolahCoreError::ScenarioFileSyntax { line: 3, .. })
+    ));
+}
+
+#[test]
+fn test_delta_ids() {
+    let input = r#"start = 2023-01-01
+
+[[delta]]
+kind = "one_time"
+name = "bonus"
+value = 500
+date = 2023-06-01
+
+[[delta]]
+kind = "one_time"
+id = "bonus"
+name = "year end bonus"
+value = 1000
+date = 2023-12-01
+
+[[delta]]
+kind = "one_time"
+name = "bonus"
+value = 250
+date = 2023-12-01
+"#;
+    let p = from_str(input).unwrap();
+    assert_eq!(
+        p.ids().iter().map(DeltaId::as_str).collect::<Vec<_>>(),
+        ["bonus-2", "bonus", "bonus-3"]
+    );
+
+    // Only ids that differ from the name are written, and they reload the same
+    let written = to_string(&p).unwrap();
+    assert_eq!(written.matches("id = ").count(), 3);
+    assert_eq!(from_str(&written).unwrap().ids(), p.ids());
+
+    let duplicated = input.replace(
+        "name = \"bonus\"\nvalue = 250",
+        "id = \"bonus\"\nname = \"bonus\"\nvalue = 250",
+    );
+    match from_str(&duplicated) {
+        Err(MoolahCoreError::ScenarioFileDelta { line, source, .. }) => {
+            assert_eq!(line, 16);
+            assert!(matches!(*source, MoolahCoreError::DuplicateDeltaId(id) if id == "bonus"));
+        }
+        _ => panic!("expected a delta error"),
+    }
+}
+
+#[test]
+fn test_line_column() {
+    let input = "ab\ncd\n\nef";
+    assert_eq!(line_column(input, 0), (1, 1));
+    assert_eq!(line_column(input, 1), (1, 2));
+    assert_eq!(line_column(input, 3), (2, 1));
+    assert_eq!(line_column(input, 7), (4, 1));
+    assert_eq!(line_column(input, 100), (4, 3));
+}
@@ -0,0 +1,139 @@
+#[cfg(test)]
+mod tests;
+
+use crate::{
+    errors::MoolahCoreError,
+    money::Money,
+    prediction::{accumulate, Prediction, Valuation},
+};
+use chrono::NaiveDate;
+use rand::{RngCore, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use rand_distr::{Distribution, Triangular};
+use std::collections::BTreeMap;
+
+/// A draw from the triangular distribution between `min` and `max` peaking at `mode`, to the
+/// nearest cent
+pub(crate) fn triangular(min: Money, mode: Money, max: Money, rng: &mut dyn RngCore) -> Money {
+    let (min, max) = (min.min(max), min.max(max));
+    let mode = mode.clamp(min, max);
+    if min == max {
+        return mode;
+    }
+
+    Triangular::new(min.to_f64(), max.to_f64(), mode.to_f64())
+        .ok()
+        .and_then(|distribution| Money::try_from(distribution.sample(rng)).ok())
+        .unwrap_or(mode)
+}
+
+/// The `fraction` quantile of non-empty `sorted` amounts, linearly interpolated between them
+pub(crate) fn quantile(sorted: &[Money], fraction: f64) -> Money {
+    let rank = fraction * (sorted.len() - 1) as f64;
+    let below = sorted[rank.floor() as usize];
+    let above = sorted[rank.ceil() as usize];
+    below + (above - below).scale(rank.fract())
+}
+
+impl Prediction {
+    /// Run `trials` random trials of the prediction until `end`. Each trial draws every delta
+    /// occurrence independently (see [`crate::delta::Delta::sample_on`]), so the spread of the results is
+    /// narrower than the best & worst cases of [`Prediction::predict`]. Otherwise a trial runs
+    /// just as `predict` does, so checkpoints move every trial toward their balance. The same
+    /// `seed` always gives the same simulation.
+    pub fn simulate(
+        &self,
+        end: &NaiveDate,
+        trials: usize,
+        seed: u64,
+    ) -> Result<Simulation, MoolahCoreError> {
+        if trials == 0 {
+            return Err(MoolahCoreError::NoTrials);
+        }
+
+        let mut rng = ChaCha8Rng::seed_from_u64(seed);
+        let mut balances: BTreeMap<NaiveDate, Vec<Money>> = BTreeMap::new();
+        let mut lowest = Vec::with_capacity(trials);
+
+        for _ in 0..trials {
+            let mut valuation = Valuation::Sampled(&mut rng);
+            let mut aggregation = self.aggregate(self.identified_ids(), end, &mut valuation)?;
+            let timeline = accumulate(
+                self.initial_value(),
+                self.checkpoints(),
+                &mut aggregation,
+                &mut valuation,
+            )?;
+
+            let mut lowest_balance = self.initial_value();
+            for (date, state) in timeline {
+                lowest_balance = lowest_balance.min(state.value());
+                balances.entry(date).or_default().push(state.value());
+            }
+            lowest.push(lowest_balance);
+        }
+
+        for on_date in balances.values_mut() {
+            on_date.sort();
+        }
+        Ok(Simulation { balances, lowest })
+    }
+}
+
+/// The balances of every trial of [`Prediction::simulate`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct Simulation {
+    /// Every trial's balance on each date, sorted
+    balances: BTreeMap<NaiveDate, Vec<Money>>,
+    /// Each trial's lowest balance over the whole timeline
+    lowest: Vec<Money>,
+}
+
+impl Simulation {
+    pub fn trials(&self) -> usize {
+        self.lowest.len()
+    }
+
+    /// Every trial's balance on `date`, from lowest to highest
+    pub fn balances(&self, date: &NaiveDate) -> Option<&[Money]> {
+        self.balances.get(date).map(Vec::as_slice)
+    }
+
+    /// The balance on each date that `percentile` percent of trials fall at or below, linearly
+    /// interpolated between trials. `percentile(50.0)` gives the median timeline.
+    pub fn percentile(
+        &self,
+        percentile: f64,
+    ) -> Result<BTreeMap<NaiveDate, Money>, MoolahCoreError> {
+        if !(0.0..=100.0).contains(&percentile) {
+            return Err(MoolahCoreError::PercentileOutOfRange(percentile));
+        }
+
+        Ok(self
+            .balances
+            .iter()
+            .map(|(date, balances)| (*date, quantile(balances, percentile / 100.0)))
+            .collect())
+    }
+
+    /// The fraction of trials with a balance below `threshold` on each date
+    pub fn probability_below(&self, threshold: Money) -> BTreeMap<NaiveDate, f64> {
+        self.balances
+            .iter()
+            .map(|(date, balances)| {
+                let below = balances.partition_point(|balance| *balance < threshold);
+                (*date, below as f64 / balances.len() as f64)
+            })
+            .collect()
+    }
+
+    /// The fraction of trials whose balance drops below `threshold` at any point
+    pub fn probability_ever_below(&self, threshold: Money) -> f64 {
+        let below = self
+            .lowest
+            .iter()
+            .filter(|balance| **balance < threshold)
+            .count();
+        below as f64 / self.trials() as f64
+    }
+}
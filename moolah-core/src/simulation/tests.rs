@@ -0,0 +1,227 @@
+use super::*;
+use crate::date_helpers::naive_ymd;
+use crate::delta::{
+    Compounding, InterestDelta, OneTimeDelta, Uncertainty, UncertaintyType, WeeklyDelta,
+};
+use crate::prediction::Checkpoint;
+use chrono::Weekday;
+
+fn groceries() -> Prediction {
+    let groceries = WeeklyDelta::try_new(
+        "groceries".into(),
+        Money::from(-150),
+        Some(Uncertainty::Balanced(UncertaintyType::Percent(
+            20.0.try_into().unwrap(),
+        ))),
+        naive_ymd(2023, 1, 1).unwrap(),
+        naive_ymd(2023, 12, 31).unwrap(),
+        Some(Weekday::Sat),
+        0,
+    )
+    .unwrap();
+
+    Prediction::new(
+        "groceries".into(),
+        naive_ymd(2023, 1, 1).unwrap(),
+        Money::from(10000),
+        vec![Box::new(groceries)],
+    )
+}
+
+fn risky_bill() -> Prediction {
+    let bill = OneTimeDelta::try_new(
+        "bill".into(),
+        Money::from(-100),
+        Some(Uncertainty::Bounds {
+            low: Money::from(-150),
+            high: Money::from(-50),
+        }),
+        naive_ymd(2023, 1, 10).unwrap(),
+    )
+    .unwrap();
+    let salary = OneTimeDelta::try_new(
+        "salary".into(),
+        Money::from(1000),
+        None,
+        naive_ymd(2023, 1, 20).unwrap(),
+    )
+    .unwrap();
+
+    Prediction::new(
+        "test".into(),
+        naive_ymd(2023, 1, 1).unwrap(),
+        Money::from(100),
+        vec![Box::new(bill), Box::new(salary)],
+    )
+}
+
+#[test]
+fn test_invalid_arguments() {
+    let p = groceries();
+    let end = naive_ymd(2023, 12, 31).unwrap();
+
+    assert!(matches!(
+        p.simulate(&end, 0, 1),
+        Err(MoolahCoreError::NoTrials)
+    ));
+    assert!(matches!(
+        p.simulate(&end, 10, 1).unwrap().percentile(100.5),
+        Err(MoolahCoreError::PercentileOutOfRange(_))
+    ));
+}
+
+#[test]
+fn test_reproducible_from_seed() {
+    let p = groceries();
+    let end = naive_ymd(2023, 12, 31).unwrap();
+
+    assert_eq!(
+        p.simulate(&end, 200, 42).unwrap(),
+        p.simulate(&end, 200, 42).unwrap()
+    );
+    assert_ne!(
+        p.simulate(&end, 200, 42).unwrap(),
+        p.simulate(&end, 200, 43).unwrap()
+    );
+}
+
+#[test]
+fn test_without_uncertainty() {
+    let interest = InterestDelta::try_new(
+        "interest".into(),
+        12.0,
+        None,
+        Compounding::Monthly,
+        naive_ymd(2023, 1, 1).unwrap(),
+        naive_ymd(2023, 12, 31).unwrap(),
+    )
+    .unwrap();
+    let salary = OneTimeDelta::try_new(
+        "salary".into(),
+        Money::from(500),
+        None,
+        naive_ymd(2023, 1, 15).unwrap(),
+    )
+    .unwrap();
+    let p = Prediction::new(
+        "test".into(),
+        naive_ymd(2023, 1, 1).unwrap(),
+        Money::from(1000),
+        vec![Box::new(interest), Box::new(salary)],
+    );
+    let end = naive_ymd(2023, 6, 30).unwrap();
+
+    let sim = p.simulate(&end, 50, 7).unwrap();
+    let expected: BTreeMap<NaiveDate, Money> = p
+        .predict(&end)
+        .unwrap()
+        .into_iter()
+        .map(|(date, state)| (date, state.value()))
+        .collect();
+    assert_eq!(sim.trials(), 50);
+    assert_eq!(sim.percentile(0.0).unwrap(), expected);
+    assert_eq!(sim.percentile(100.0).unwrap(), expected);
+}
+
+#[test]
+fn test_without_uncertainty_matches_checkpointed_prediction() {
+    let mut p = risky_bill();
+    p.remove_delta("bill").unwrap();
+    let p = p.with_checkpoints(vec![Checkpoint::new(
+        naive_ymd(2023, 1, 25).unwrap(),
+        Money::from(2000),
+    )
+    .with_weight(0.5)
+    .unwrap()]);
+    let end = naive_ymd(2023, 1, 31).unwrap();
+
+    let expected: BTreeMap<NaiveDate, Money> = p
+        .predict(&end)
+        .unwrap()
+        .into_iter()
+        .map(|(date, state)| (date, state.value()))
+        .collect();
+    assert_eq!(
+        p.simulate(&end, 20, 3).unwrap().percentile(50.0).unwrap(),
+        expected
+    );
+}
+
+#[test]
+fn test_percentiles_narrower_than_bounds() {
+    let p = groceries();
+    let end = naive_ymd(2023, 12, 31).unwrap();
+    let last = naive_ymd(2023, 12, 30).unwrap();
+
+    let sim = p.simulate(&end, 1000, 1).unwrap();
+    let p5 = sim.percentile(5.0).unwrap()[&last];
+    let p50 = sim.percentile(50.0).unwrap()[&last];
+    let p95 = sim.percentile(95.0).unwrap()[&last];
+
+    let deterministic = &p.predict(&end).unwrap()[&last];
+    assert_eq!(deterministic.value(), Money::from(2200));
+    assert!(deterministic.min_uncertainty_val() < p5);
+    assert!(p5 < p50);
+    assert!(p50 < p95);
+    assert!(p95 < deterministic.max_uncertainty_val());
+
+    // 52 draws averaging out leave the median near the expected value, and the 90% band at a
+    // fraction of the $3120 between best and worst cases
+    assert!((p50 - Money::from(2200)).abs() < Money::from(20));
+    assert!(p95 - p5 < Money::from(500));
+}
+
+#[test]
+fn test_probability_below_threshold() {
+    let p = risky_bill();
+    let sim = p
+        .simulate(&naive_ymd(2023, 1, 31).unwrap(), 2000, 3)
+        .unwrap();
+
+    let probabilities = sim.probability_below(Money::ZERO);
+    assert_eq!(probabilities[&naive_ymd(2023, 1, 1).unwrap()], 0.0);
+    assert!((probabilities[&naive_ymd(2023, 1, 10).unwrap()] - 0.5).abs() < 0.05);
+    assert_eq!(probabilities[&naive_ymd(2023, 1, 20).unwrap()], 0.0);
+
+    // An eighth of the bill's triangular distribution lies below -125, which leaves the balance
+    // below -25
+    let ever_below = sim.probability_ever_below(Money::from(-25));
+    assert!((ever_below - 0.125).abs() < 0.03);
+    assert_eq!(sim.probability_ever_below(Money::from(-150)), 0.0);
+    assert_eq!(sim.probability_ever_below(Money::from(101)), 1.0);
+}
+
+#[test]
+fn test_balances() {
+    let p = risky_bill();
+    let sim = p
+        .simulate(&naive_ymd(2023, 1, 31).unwrap(), 100, 3)
+        .unwrap();
+
+    let balances = sim.balances(&naive_ymd(2023, 1, 10).unwrap()).unwrap();
+    assert_eq!(balances.len(), 100);
+    assert!(balances.windows(2).all(|pair| pair[0] <= pair[1]));
+    assert!(balances
+        .iter()
+        .all(|balance| (Money::from(-50) <= *balance) & (*balance <= Money::from(50))));
+    assert_eq!(sim.balances(&naive_ymd(2023, 1, 11).unwrap()), None);
+}
+
+#[test]
+fn test_checkpoint_resets_trials() {
+    let checkpoint = naive_ymd(2023, 6, 30).unwrap();
+    let p = groceries().with_checkpoints(vec![Checkpoint::new(checkpoint, Money::from(7000))]);
+    let simulation = p.simulate(&naive_ymd(2023, 7, 1).unwrap(), 50, 7).unwrap();
+
+    assert!(simulation
+        .balances(&checkpoint)
+        .unwrap()
+        .iter()
+        .all(|balance| *balance == Money::from(7000)));
+    let after = simulation
+        .balances(&naive_ymd(2023, 7, 1).unwrap())
+        .unwrap();
+    assert!(after
+        .iter()
+        .all(|balance| (Money::from(6820)..=Money::from(6880)).contains(balance)));
+}
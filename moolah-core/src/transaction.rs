@@ -0,0 +1,133 @@
+#[cfg(test)]
+mod tests;
+
+use crate::money::Money;
+use chrono::NaiveDate;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// Money that actually moved on a date, such as a line of a bank statement. Its amount is in the
+/// prediction's currency, negative when money went out.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(deny_unknown_fields)
+)]
+pub struct Transaction {
+    date: NaiveDate,
+    description: String,
+    amount: Money,
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    category: Option<String>,
+}
+
+impl Transaction {
+    pub fn new(date: NaiveDate, description: String, amount: Money) -> Self {
+        Transaction {
+            date,
+            description,
+            amount,
+            category: None,
+        }
+    }
+
+    pub fn with_category(mut self, category: String) -> Self {
+        self.category = Some(category);
+        self
+    }
+
+    pub fn date(&self) -> &NaiveDate {
+        &self.date
+    }
+
+    pub fn description(&self) -> &str {
+        &self.description
+    }
+
+    pub fn amount(&self) -> Money {
+        self.amount
+    }
+
+    pub fn category(&self) -> Option<&str> {
+        self.category.as_deref()
+    }
+}
+
+/// Actual transactions, kept in date order. Transactions on the same date keep the order they
+/// were added in.
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(from = "Vec<Transaction>", into = "Vec<Transaction>")
+)]
+pub struct Ledger {
+    transactions: Vec<Transaction>,
+}
+
+impl Ledger {
+    pub fn new(mut transactions: Vec<Transaction>) -> Self {
+        transactions.sort_by_key(|transaction| transaction.date);
+        Ledger { transactions }
+    }
+
+    pub fn push(&mut self, transaction: Transaction) {
+        let index = self
+            .transactions
+            .partition_point(|existing| existing.date <= transaction.date);
+        self.transactions.insert(index, transaction);
+    }
+
+    pub fn transactions(&self) -> &[Transaction] {
+        &self.transactions
+    }
+
+    /// The transactions from `start` to `end` inclusive
+    pub fn between(&self, start: &NaiveDate, end: &NaiveDate) -> &[Transaction] {
+        let first = self
+            .transactions
+            .partition_point(|transaction| transaction.date < *start);
+        let last = self
+            .transactions
+            .partition_point(|transaction| transaction.date <= *end);
+        &self.transactions[first..last.max(first)]
+    }
+
+    pub fn len(&self) -> usize {
+        self.transactions.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.transactions.is_empty()
+    }
+}
+
+impl From<Vec<Transaction>> for Ledger {
+    fn from(transactions: Vec<Transaction>) -> Self {
+        Ledger::new(transactions)
+    }
+}
+
+impl From<Ledger> for Vec<Transaction> {
+    fn from(ledger: Ledger) -> Self {
+        ledger.transactions
+    }
+}
+
+impl FromIterator<Transaction> for Ledger {
+    fn from_iter<I: IntoIterator<Item = Transaction>>(iter: I) -> Self {
+        Ledger::new(iter.into_iter().collect())
+    }
+}
+
+impl Extend<Transaction> for Ledger {
+    fn extend<I: IntoIterator<Item = Transaction>>(&mut self, iter: I) {
+        self.transactions.extend(iter);
+        self.transactions
+            .sort_by_key(|transaction| transaction.date);
+    }
+}
@@ -0,0 +1,56 @@
+use super::*;
+use crate::date_helpers::ymd;
+
+fn transaction(date: NaiveDate, description: &str) -> Transaction {
+    Transaction::new(date, description.into(), Money::from(-10))
+}
+
+#[test]
+fn test_ledger_keeps_date_order() {
+    let mut ledger = Ledger::new(vec![
+        transaction(ymd(2023, 1, 5), "b"),
+        transaction(ymd(2023, 1, 1), "a"),
+        transaction(ymd(2023, 1, 5), "c"),
+    ]);
+    ledger.push(transaction(ymd(2023, 1, 5), "d"));
+    ledger.push(transaction(ymd(2023, 1, 3), "e"));
+
+    let descriptions: Vec<_> = ledger
+        .transactions()
+        .iter()
+        .map(Transaction::description)
+        .collect();
+    assert_eq!(descriptions, ["a", "e", "b", "c", "d"]);
+    assert_eq!(ledger.len(), 5);
+}
+
+#[test]
+fn test_between() {
+    let ledger: Ledger = (1..=10)
+        .map(|day| transaction(ymd(2023, 1, day), "t"))
+        .collect();
+
+    assert_eq!(ledger.between(&ymd(2023, 1, 3), &ymd(2023, 1, 5)).len(), 3);
+    assert_eq!(
+        ledger.between(&ymd(2022, 1, 1), &ymd(2022, 12, 31)).len(),
+        0
+    );
+    assert_eq!(ledger.between(&ymd(2023, 1, 5), &ymd(2023, 1, 3)).len(), 0);
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn test_serde() {
+    let ledger = Ledger::new(vec![
+        transaction(ymd(2023, 1, 2), "groceries"),
+        Transaction::new(ymd(2023, 1, 1), "ACME PAYROLL".into(), Money::from(3000))
+            .with_category("salary".into()),
+    ]);
+    let value = serde_json::json!([
+        {"date": "2023-01-01", "description": "ACME PAYROLL", "amount": "3000.00", "category": "salary"},
+        {"date": "2023-01-02", "description": "groceries", "amount": "-10.00"},
+    ]);
+
+    assert_eq!(serde_json::to_value(&ledger).unwrap(), value);
+    assert_eq!(serde_json::from_value::<Ledger>(value).unwrap(), ledger);
+}
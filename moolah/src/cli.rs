@@ -0,0 +1,48 @@
+use chrono::NaiveDate;
+use clap::{Parser, Subcommand, ValueEnum};
+use std::path::PathBuf;
+
+#[derive(Parser)]
+#[command(
+    name = "moolah",
+    version,
+    about = "Project personal finances from a scenario file"
+)]
+pub struct Cli {
+    /// TOML scenario file describing the prediction
+    #[arg(short, long, global = true, default_value = "moolah.toml")]
+    pub scenario: PathBuf,
+
+    #[command(subcommand)]
+    pub command: Command,
+}
+
+#[derive(Subcommand)]
+pub enum Command {
+    /// Predict the balance from the scenario's start through a date
+    Predict {
+        /// Last date to predict (YYYY-MM-DD)
+        #[arg(long)]
+        until: NaiveDate,
+
+        #[arg(short, long, value_enum, default_value_t)]
+        format: Format,
+    },
+
+    /// List the scenario's deltas
+    ListDeltas,
+
+    /// Show the predicted balance on a date and the deltas that landed on it
+    Explain {
+        /// Date to explain (YYYY-MM-DD)
+        date: NaiveDate,
+    },
+}
+
+#[derive(Clone, Copy, Default, ValueEnum)]
+pub enum Format {
+    #[default]
+    Table,
+    Csv,
+    Json,
+}
@@ -0,0 +1,38 @@
+mod cli;
+mod output;
+
+use anyhow::Context;
+use clap::Parser;
+use cli::{Cli, Command};
+use moolah_core::scenario_file;
+use std::{fs, io, process::ExitCode};
+
+fn main() -> ExitCode {
+    match run() {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("error: {:#}", err);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn run() -> anyhow::Result<()> {
+    let cli = Cli::parse();
+
+    let input = fs::read_to_string(&cli.scenario)
+        .with_context(|| format!("could not read `{}`", cli.scenario.display()))?;
+    let prediction = scenario_file::from_str(&input)
+        .with_context(|| format!("could not load `{}`", cli.scenario.display()))?;
+
+    let mut stdout = io::stdout().lock();
+    match cli.command {
+        Command::Predict { until, format } => {
+            output::write_timeline(&mut stdout, &prediction.predict(&until)?, format)?
+        }
+        Command::ListDeltas => output::write_deltas(&mut stdout, prediction.deltas())?,
+        Command::Explain { date } => output::write_explanation(&mut stdout, &prediction, &date)?,
+    }
+
+    Ok(())
+}
@@ -0,0 +1,204 @@
+#[cfg(test)]
+mod tests;
+
+use crate::cli::Format;
+use chrono::NaiveDate;
+use moolah_core::{
+    delta::Delta,
+    money::Money,
+    prediction::{Prediction, PredictionState},
+};
+use serde::Serialize;
+use std::{collections::BTreeMap, io::Write};
+
+#[derive(Serialize)]
+struct TimelineRow<'a> {
+    date: &'a NaiveDate,
+    value: Money,
+    min: Money,
+    max: Money,
+    deltas: Vec<&'a str>,
+}
+
+impl<'a> TimelineRow<'a> {
+    fn new(date: &'a NaiveDate, state: &'a PredictionState) -> Self {
+        let mut deltas: Vec<&str> = state
+            .impactful_deltas()
+            .iter()
+            .map(|d| d.as_str())
+            .collect();
+        deltas.sort_unstable();
+
+        TimelineRow {
+            date,
+            value: state.value(),
+            min: state.min_uncertainty_val(),
+            max: state.max_uncertainty_val(),
+            deltas,
+        }
+    }
+}
+
+pub fn write_timeline<W: Write>(
+    writer: &mut W,
+    timeline: &BTreeMap<NaiveDate, PredictionState>,
+    format: Format,
+) -> anyhow::Result<()> {
+    let rows = timeline
+        .iter()
+        .map(|(date, state)| TimelineRow::new(date, state));
+
+    match format {
+        Format::Table => write_table(
+            writer,
+            &["date", "value", "min", "max", "deltas"],
+            rows.map(|row| {
+                vec![
+                    row.date.to_string(),
+                    row.value.to_string(),
+                    row.min.to_string(),
+                    row.max.to_string(),
+                    row.deltas.join(", "),
+                ]
+            }),
+        )?,
+        Format::Csv => {
+            let mut csv = csv::Writer::from_writer(writer);
+            csv.write_record(["date", "value", "min", "max", "deltas"])?;
+            for row in rows {
+                csv.write_record([
+                    row.date.to_string(),
+                    row.value.to_string(),
+                    row.min.to_string(),
+                    row.max.to_string(),
+                    row.deltas.join(";"),
+                ])?;
+            }
+            csv.flush()?;
+        }
+        Format::Json => {
+            serde_json::to_writer_pretty(&mut *writer, &rows.collect::<Vec<_>>())?;
+            writeln!(writer)?;
+        }
+    }
+
+    Ok(())
+}
+
+pub fn write_deltas<W: Write>(writer: &mut W, deltas: &[Box<dyn Delta>]) -> anyhow::Result<()> {
+    write_table(
+        writer,
+        &[
+            "name",
+            "value",
+            "min",
+            "max",
+            "occurrences",
+            "first",
+            "last",
+        ],
+        deltas.iter().map(|delta| {
+            // An open-ended delta has no count or last date to show
+            let (occurrences, last) = if delta.is_open_ended() {
+                ("ongoing".to_string(), String::new())
+            } else {
+                (
+                    delta.dates().count().to_string(),
+                    optional_date(delta.dates().last()),
+                )
+            };
+            vec![
+                delta.name().to_string(),
+                delta.value().to_string(),
+                delta.min_uncertainty_value().to_string(),
+                delta.max_uncertainty_value().to_string(),
+                occurrences,
+                optional_date(delta.dates().next()),
+                last,
+            ]
+        }),
+    )
+}
+
+pub fn write_explanation<W: Write>(
+    writer: &mut W,
+    prediction: &Prediction,
+    date: &NaiveDate,
+) -> anyhow::Result<()> {
+    let explanation = prediction.explain(date)?;
+    let state = match explanation.timeline().range(..=*date).next_back() {
+        Some((_, state)) => state,
+        None => {
+            writeln!(
+                writer,
+                "{} is before the prediction starts on {}",
+                date,
+                prediction.start()
+            )?;
+            return Ok(());
+        }
+    };
+
+    writeln!(
+        writer,
+        "{}: {} (min {}, max {})",
+        date,
+        state.value(),
+        state.min_uncertainty_val(),
+        state.max_uncertainty_val()
+    )?;
+
+    let contributions = explanation.on(date);
+    if contributions.is_empty() {
+        writeln!(writer, "no deltas on this date")?;
+        return Ok(());
+    }
+
+    write_table(
+        writer,
+        &["delta", "value", "min", "max"],
+        contributions.iter().map(|contribution| {
+            vec![
+                contribution.name.to_string(),
+                contribution.value.to_string(),
+                contribution.min.to_string(),
+                contribution.max.to_string(),
+            ]
+        }),
+    )
+}
+
+fn write_table<W: Write>(
+    writer: &mut W,
+    headers: &[&str],
+    rows: impl Iterator<Item = Vec<String>>,
+) -> anyhow::Result<()> {
+    let rows: Vec<Vec<String>> = rows.collect();
+    let widths: Vec<usize> = headers
+        .iter()
+        .enumerate()
+        .map(|(i, header)| {
+            rows.iter()
+                .map(|row| row[i].chars().count())
+                .chain([header.len()])
+                .max()
+                .unwrap_or_default()
+        })
+        .collect();
+
+    let header: Vec<String> = headers.iter().map(|header| header.to_string()).collect();
+    for row in [header].iter().chain(rows.iter()) {
+        let cells: Vec<String> = row
+            .iter()
+            .zip(widths.iter())
+            .map(|(cell, width)| format!("{:<width$}", cell, width = width))
+            .collect();
+        writeln!(writer, "{}", cells.join("  ").trim_end())?;
+    }
+
+    Ok(())
+}
+
+fn optional_date(date: Option<NaiveDate>) -> String {
+    date.map(|date| date.to_string()).unwrap_or_default()
+}
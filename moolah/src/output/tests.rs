@@ -0,0 +1,138 @@
+use super::*;
+use moolah_core::scenario_file;
+
+const SCENARIO: &str = r#"name = "test"
+start = 2023-01-01
+initial_value = 1000
+
+[[delta]]
+kind = "monthly"
+name = "rent"
+value = -500
+on_month_day = 1
+start = 2023-01-01
+end = 2023-02-28
+
+[[delta]]
+kind = "one_time"
+name = "bonus"
+value = 200
+uncertainty = { balanced = { dollars = 50 } }
+date = 2023-02-01
+"#;
+
+fn prediction() -> Prediction {
+    scenario_file::from_str(SCENARIO).unwrap()
+}
+
+fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(year, month, day).unwrap()
+}
+
+fn render<F: FnOnce(&mut Vec<u8>) -> anyhow::Result<()>>(write: F) -> String {
+    let mut buffer = vec![];
+    write(&mut buffer).unwrap();
+    String::from_utf8(buffer).unwrap()
+}
+
+#[test]
+fn test_timeline_table() {
+    let timeline = prediction().predict(&date(2023, 2, 28)).unwrap();
+    let output = render(|w| write_timeline(w, &timeline, Format::Table));
+
+    assert_eq!(
+        output,
+        "date        value   min     max     deltas\n\
+         2023-01-01  500.00  500.00  500.00  rent\n\
+         2023-02-01  200.00  150.00  250.00  bonus, rent\n"
+    );
+}
+
+#[test]
+fn test_timeline_csv() {
+    let timeline = prediction().predict(&date(2023, 2, 28)).unwrap();
+    let output = render(|w| write_timeline(w, &timeline, Format::Csv));
+
+    assert_eq!(
+        output,
+        "date,value,min,max,deltas\n\
+         2023-01-01,500.00,500.00,500.00,rent\n\
+         2023-02-01,200.00,150.00,250.00,bonus;rent\n"
+    );
+}
+
+#[test]
+fn test_timeline_json() {
+    let timeline = prediction().predict(&date(2023, 2, 28)).unwrap();
+    let output = render(|w| write_timeline(w, &timeline, Format::Json));
+    let parsed: serde_json::Value = serde_json::from_str(&output).unwrap();
+
+    assert_eq!(
+        parsed,
+        serde_json::json!([
+            {"date": "2023-01-01", "value": "500.00", "min": "500.00", "max": "500.00", "deltas": ["rent"]},
+            {"date": "2023-02-01", "value": "200.00", "min": "150.00", "max": "250.00", "deltas": ["bonus", "rent"]},
+        ])
+    );
+}
+
+#[test]
+fn test_deltas() {
+    let output = render(|w| write_deltas(w, prediction().deltas()));
+
+    assert_eq!(
+        output,
+        "name   value    min      max      occurrences  first       last\n\
+         rent   -500.00  -500.00  -500.00  2            2023-01-01  2023-02-01\n\
+         bonus  200.00   150.00   250.00   1            2023-02-01  2023-02-01\n"
+    );
+}
+
+#[test]
+fn test_open_ended_deltas() {
+    let p = scenario_file::from_str(
+        r#"start = 2023-01-01
+
+[[delta]]
+kind = "monthly"
+name = "salary"
+value = 3000
+start = 2023-01-01
+on_month_day = 15
+"#,
+    )
+    .unwrap();
+    let output = render(|w| write_deltas(w, p.deltas()));
+
+    assert_eq!(
+        output,
+        "name    value    min      max      occurrences  first       last\n\
+         salary  3000.00  3000.00  3000.00  ongoing      2023-01-15\n"
+    );
+}
+
+#[test]
+fn test_explanation() {
+    let p = prediction();
+
+    let output = render(|w| write_explanation(w, &p, &date(2023, 2, 1)));
+    assert_eq!(
+        output,
+        "2023-02-01: 200.00 (min 150.00, max 250.00)\n\
+         delta  value    min      max\n\
+         rent   -500.00  -500.00  -500.00\n\
+         bonus  200.00   150.00   250.00\n"
+    );
+
+    let output = render(|w| write_explanation(w, &p, &date(2023, 1, 15)));
+    assert_eq!(
+        output,
+        "2023-01-15: 500.00 (min 500.00, max 500.00)\nno deltas on this date\n"
+    );
+
+    let output = render(|w| write_explanation(w, &p, &date(2022, 12, 31)));
+    assert_eq!(
+        output,
+        "2022-12-31 is before the prediction starts on 2023-01-01\n"
+    );
+}